@@ -10,26 +10,171 @@ Pour exécuter le programme à la main, il faut l'ouvrir dans un terminal, lui d
 
 Le programme est disponible dans le OneDrive de Jean-Jeune dans le dossier du camp de jour en cours, avec ses templates et un script qui permet de l'exécuter facilement: Il suffit de double-cliquer sur le script pour l'exécuter.
 
+== Configuration
+Au démarrage, le programme cherche le fichier `presencejj.toml` dans le dossier courant (ou le fichier donné avec l'option `--config`). Toutes les valeurs sont optionnelles; celles qui sont absentes gardent leur valeur par défaut.
+
+```toml
+# dossier de sortie par défaut des documents
+out_dir = "./out"
+# affiche le détail de chaque groupe lu et de chaque document généré
+verbose = true
+# dossier contenant template.typ (remplacé par l'argument du programme, s'il est donné)
+typst_working_dir = "templates"
+# fichier où sont conservés les derniers dossiers utilisés
+fichier_etat = "presencejj_etat.toml"
+# entêtes de colonnes supplémentaires pour les listes de présences (optionnel)
+fichier_colonnes = "colonnes.toml"
+# historique des chandails des saisons passées
+fichier_chandails = "historique_chandails.toml"
+
+[excel]
+# Par défaut, la ligne d'entêtes est trouvée automatiquement dans chaque feuille et les enfants la suivent.
+# Ces deux valeurs imposent plutôt des positions fixes, pour toutes les feuilles; à n'utiliser que si la
+# détection se trompe pour un modèle d'export.
+# ligne des entêtes de colonnes dans les listes de présences (à partir de 0)
+#data_ln = 5
+# nombre de lignes à sauter avant les enfants
+#ln_skip = 6
+```
+
+Les catégories d'âge reconnues dans le nom des groupes (ex. `Camp de jour | Parc Lafontaine | Crocus | Sem. 1`) sont aussi configurables. Si la section `[[categories]]` est présente, elle remplace la liste par défaut (Crocus, Balaous, Basaltes et 12-15 ans).
+
+```toml
+[[categories]]
+nom = "Crocus"
+# autres noms acceptés dans le nom des groupes (optionnel)
+alias = ["Petits"]
+age_min = 5
+age_max = 6
+# nombre d'enfants visé par sous-groupe
+taille_sous_groupe = 10
+# nombre d'enfants par animateur (optionnel, un animateur par sous-groupe par défaut)
+ratio_animateur = 10
+# stratégie de sous-groupes propre à la catégorie (optionnel, voir `[sous_groupes]`)
+strategie = "ages"
+```
+
+Un groupe nommé par sa tranche d'âge (ex. `5-6 ans`) reçoit le nom de la catégorie ayant exactement ces âges. Pour un groupe dont la catégorie est inconnue, le programme demande le nombre de sous-groupes à faire.
+
+D'autres façons de nommer les groupes peuvent être reconnues sans changer le programme, avec `noms_groupes`: une liste d'expressions régulières essayées dans l'ordre, avant le format de Qidigo. Chacune doit avoir les captures nommées `site`, `category` et `semaine`; `activite` est optionnelle (elle vient alors de la colonne Activité de la programmation), tout comme `grage_min` et `grage_max` pour une catégorie écrite en tranche d'âge. Une règle invalide est refusée au démarrage.
+
+```toml
+# ex. "CDJ - Ahuntsic - Crocus - S3"
+noms_groupes = ['^CDJ - (?P<site>[^-]+?) - (?P<category>[^-]+?) - S(?P<semaine>\d+)$']
+```
+
+Une feuille de présences ou une ligne de programmation dont le nom de groupe n'est reconnu par aucune règle est ignorée et signalée dans le rapport d'importation, avec le nom tel qu'écrit dans le fichier.
+
+Le programme se souvient des derniers dossiers utilisés pour ouvrir des fichiers et enregistrer les documents d'une exécution à l'autre, dans le fichier d'état.
+
+La répartition des sous-groupes se règle dans la section `[sous_groupes]` (optionnelle).
+
+```toml
+[sous_groupes]
+# stratégie par défaut: "interets" (sous-groupes à profil), "ages" (tranches d'âge) ou "amities" (amis ensemble)
+strategie = "interets"
+# frères et sœurs: "ensemble", "separees" ou "indifferent"
+fratries = "ensemble"
+# écart de taille permis entre les sous-groupes d'un groupe, en plus d'un enfant
+ecart_taille = 0
+# importance des intérêts, de l'équilibre des genres et de l'écart d'âge
+poids_interets = 1.0
+poids_genres = 1.0
+poids_ages = 1.0
+# importance de garder les amis ensemble (stratégie "amities")
+poids_amities = 1.0
+# points d'un 1er, 2e, 3e et 4e intérêt
+points_interets = [8, 4, 2, 0]
+# part minimale des points d'un intérêt pour qu'il donne un profil de sous-groupe (0.4 = 40 %)
+seuil_profil = 0.4
+```
+
+La stratégie "amities" considère comme amis deux enfants de comptes différents quand l'un est nommé dans le commentaire de l'autre, quand ils quittent avec la même personne ou quand ils ont un contact au même numéro de téléphone. Un numéro ou une personne partagé par plus de 4 enfants du groupe (ex. une garderie) est ignoré.
+
+=== Formats de fichiers
+En plus des fichiers Excel téléchargés de Qidigo (`.xlsx`, ainsi que les anciens `.xls`, `.xlsm` et `.xlsb`), le programme lit les classeurs LibreOffice (`.ods`) et les fichiers `.csv` envoyés par d'autres organismes, pourvu qu'ils aient les mêmes colonnes. Un fichier `.csv` ne contient qu'une feuille, qui porte le nom du fichier; son séparateur (point-virgule, virgule, tabulation ou barre verticale) et son encodage (UTF-8 ou Windows-1252, celui d'Excel en français) sont détectés automatiquement.
+
+Les dates de naissance et les colonnes Début et Fin de la programmation peuvent être des cellules de date d'Excel ou du texte: `2019-03-14`, `14/03/2019` (jour en premier), `14 mars 2019` ou `1er juil. 2019`. Ce qui est entre parenthèses, comme l'âge dans `2019-03-14 (6 ans)`, est ignoré. Un enfant dont la date de naissance ne peut pas être lue est signalé dans le rapport d'importation.
+
+=== Colonnes des listes de présences
+Le programme trouve les colonnes des listes de présences par leur entête, selon le modèle "2025 - Complet" de Qidigo. Si un modèle est renommé, le fichier de colonnes permet d'ajouter d'autres entêtes pour un champ, sans modifier le programme. Les entêtes du fichier sont essayées avant celles par défaut.
+
+```toml
+med_ibu = ["Med Ibuprofène", "Ibuprofène"]
+contact_1_nom = ["Contact d'urgence 1 - Nom"]
+tel = ["Téléphone"]
+```
+
+Les entêtes sont comparées sans tenir compte des majuscules, des accents ni des espaces en trop: `DATE  de naissance` correspond à `Date de naissance`.
+
+La ligne d'entêtes n'a pas à être à une position fixe: dans chaque feuille, le programme choisit, parmi les 30 premières lignes, celle qui contient le plus d'entêtes connues et qui a au moins les colonnes Nom, Prénom et Date de naissance. Le nom du groupe (`Activité | Site | Catégorie | Sem. N`), la ligne juste en dessous (le discriminant) et la ligne `Programmation:` sont cherchés dans n'importe quelle cellule au-dessus des entêtes. Seuls les entêtes et le nom du groupe sont obligatoires: une feuille où l'un d'eux est introuvable est ignorée et signalée dans le rapport d'importation. Sans discriminant, le groupe n'en a pas; sans ligne `Programmation:`, sa saison reste inconnue.
+
+Un nom de champ inconnu est refusé au démarrage. À la lecture de chaque feuille, le programme affiche le nombre de colonnes trouvées, les champs sans colonne et les colonnes qui n'ont été associées à aucun champ (avec `verbose`, il affiche aussi l'entête utilisée pour chaque champ).
+
 == Fonctionnement
 Le logiciel est un programme console, et n'a donc pas d'interface graphique. À chaque niveau du menu, le programme présente les options disponibles. Il faut alors entrer le numéro de l'option désirée et appuyer sur enter.
 
 Les options du premier menu sont les suivantes:
-/ Lire à partir de la programmation: Permet de charger les informations de groupes à partir du fichier de programmation télécharger de Qidigo. Utile pour faire les statistiques de camp, car la programmation permet d'avoir la capacité des groupes.
-/ Lire à partir des listes de présences: Permet de charger les enfants inscrits aux différents groupes à partir du fichier de listes de présences téléchargé de Qidigo. Utile pour les statistiques de camp ainsi que pour les fiches médicales et liste de présences hebdomadaires.
-/ Faire les sous-groupes: Calcul les sous-groupes, selon la capacité des groupes d'âges et le nombre d'enfant inscrits. Tente de rassembler les enfants par intérêts et former des groupes à profil le plus possible.
+/ Lire à partir de la programmation: Permet de charger les informations de groupes à partir du fichier de programmation télécharger de Qidigo. Utile pour faire les statistiques de camp, car la programmation permet d'avoir la capacité des groupes. Les colonnes Début et Fin donnent les dates de chaque groupe, et les restrictions d'âge ont priorité sur celles de la catégorie. Les groupes des listes de présences reçoivent les dates du groupe de la programmation de la même saison, activité et semaine (du même site de préférence), peu importe l'ordre de lecture des fichiers; les listes imprimées affichent alors "du 30 juin au 4 juillet" plutôt que le numéro de semaine.
+/ Lire à partir des listes de présences: Permet de charger les enfants inscrits aux différents groupes à partir du fichier de listes de présences téléchargé de Qidigo. Utile pour les statistiques de camp ainsi que pour les fiches médicales et liste de présences hebdomadaires. Un enfant déjà connu est mis à jour avec les informations du fichier le plus récent (fiche santé, contacts, départ, piscine, etc.) et chaque champ modifié est noté dans son historique. Un parent qui corrige son téléphone, son courriel ou son adresse dans Qidigo garde son compte, reconnu par le mandataire et un enfant en commun: ses coordonnées sont mises à jour et notées dans l'historique du compte, sans créer l'enfant une deuxième fois. Une cellule vide efface la valeur connue (un commentaire retiré, un deuxième contact supprimé), alors qu'une colonne absente ou une cellule invalide la garde: les allergies, les maladies et les personnes autorisées à venir chercher l'enfant sont remplacées, même par une liste vide, dès que toutes leurs colonnes sont dans le fichier. L'historique est visible dans la fiche de l'enfant sous _Afficher les données_. À la fin de la lecture, le programme affiche le rapport d'importation: chaque valeur qu'il n'a pas pu lire (date de naissance, téléphone, carte d'assurance maladie, taille, intérêt, mandataire manquant, etc.) avec la feuille, la ligne, l'entête de colonne et la valeur telle qu'écrite dans le fichier. Une _erreur_ veut dire que la ligne a été ignorée; un _avertissement_, que seule la valeur a été ignorée. La feuille « groupes vides » du fichier est aussi lue: ses groupes, qui n'ont encore aucune inscription, sont ajoutés avec la saison des autres feuilles et marqués comme vides jusqu'à ce qu'un export leur donne des inscriptions. Ils comptent dans les statistiques (nombre de groupes, groupes vides et capacité) et dans la planification des animateurs.
+
+  Plusieurs fichiers peuvent être lus d'un coup, par exemple un par site: choisir _Un ou plusieurs fichiers_, ou _Tous les fichiers d'un dossier_ pour lire tous les fichiers Excel, LibreOffice et csv du dossier (les fichiers temporaires d'Excel, qui commencent par `~$`, sont ignorés). Les fichiers sont lus du plus ancien au plus récent, selon leur date de modification. Un résumé donne ensuite, pour chaque fichier, le nombre de groupes, d'inscriptions, de nouveaux membres, de membres mis à jour, d'erreurs et d'avertissements, puis le total des inscriptions et des enfants distincts. Un groupe présent dans plusieurs fichiers n'est compté qu'une fois: c'est la liste du fichier le plus récent qui est gardée, et les enfants qui n'apparaissent que dans un fichier plus ancien sont retirés du groupe et nommés dans le résumé.
+/ Comparer avec un nouvel export de présences: Compare les données présentement dans le programme (ou, si aucune n'est chargée, un ancien export ou une session) avec un nouvel export de Qidigo, sans le charger. Le rapport donne, pour chaque groupe, les enfants ajoutés (+), retirés (-) et déplacés vers un autre groupe ou une autre semaine (> et <), puis les comptes dont les coordonnées ont changé et les fiches d'enfants modifiées. Il se termine par la liste des listes de présences et des fiches santé à réimprimer.
+/ Faire les sous-groupes: Calcul les sous-groupes, selon la capacité des groupes d'âges et le nombre d'enfant inscrits. Les sous-groupes d'un groupe ont tous la même taille, à un enfant près; les frères et sœurs d'un même compte sont placés ensemble (ou séparés, selon la configuration) et les enfants ayant besoin d'accompagnement ou un problème de comportement sont répartis entre les sous-groupes. Ensuite, le programme tente de rassembler les enfants par intérêts, d'équilibrer les genres et de garder des âges rapprochés dans chaque sous-groupe. Pour chaque groupe, il explique le résultat: contraintes appliquées, contraintes relâchées faute de solution, et composition de chaque sous-groupe. Le programme demande s'il faut refaire tous les sous-groupes ou placer seulement les nouveaux inscrits: dans ce cas, les enfants gardent leur sous-groupe (et leur animateur), les enfants désinscrits en sont retirés et chaque nouvel enfant va dans le sous-groupe qui lui convient le mieux (intérêts, âge, place restante), avec sa fratrie s'il en a une. Le programme affiche ensuite les enfants ajoutés et retirés de chaque sous-groupe, et la liste des listes de présences à réimprimer. Comparer les stratégies affiche côte à côte, pour un groupe, les sous-groupes que donnerait chaque stratégie (profils, âges, genres, amis séparés), puis applique celle choisie. Si tous les sous-groupes ont pu être faits, le programme affiche ensuite un rapport de qualité pour juger du résultat avant d'imprimer les listes: pour chaque sous-groupe, le nombre d'enfants, les âges (minimum, maximum et médiane), les genres, les enfants ayant le profil en 1er et en 2e choix (en jaune si c'est moins de la moitié), les enfants ayant un frère ou une sœur dans le sous-groupe, et les enfants ayant besoin d'accompagnement ou un problème de comportement. Le rapport peut être exporté dans le fichier `qualite_sous_groupes.csv`.
+/ Modifier les sous-groupes: Choisissez un groupe pour voir ses sous-groupes, avec un numéro pour chaque enfant. Entrez `d <enfant> <sous-groupe>` pour déplacer un enfant, `e <enfant> <enfant>` pour échanger deux enfants, `n <sous-groupe> <nom>` pour nommer un sous-groupe (le nom est imprimé sur la liste de présences des animateurs), `p <sous-groupe> <intérêt>` pour changer son profil (`aucun` pour l'enlever), `v <enfant>` pour verrouiller un enfant dans son sous-groupe et `vs <sous-groupe>` pour verrouiller un sous-groupe au complet (la même commande déverrouille). Quand les sous-groupes sont refaits, un sous-groupe verrouillé garde ses enfants, son nom, son profil et son animateur sans en recevoir de nouveaux, et un enfant verrouillé reste dans son sous-groupe; les autres enfants sont répartis autour d'eux. Un sous-groupe garde toujours le nom qui lui a été donné, mais sans verrou son profil est recalculé.
 / Faire les fiches médicales: Génère les fiches médicales au format pdf, trié par site de camp et saison, dans le dossier indiqué.
 / Faire les listes de présences: Génère les listes de présences d'animateur et de service de garde au format pdf, trié par saison, site et semaine, dans le dossier indiqué.
-/ Estimer la quantité de chandail: Permet d'estimer la quantité de chandails à commander selon le nombre d'enfants présentement inscrits. À deux modes: le mode partiel n'utilise que les enfants présentement inscrits, le mode complet extrapole cette information avec le nombre d'enfants total inscrits les années précédentes (ou l'estimation de l'année en cours).
-/ Faire les statistiques de camp: Génère les différentes statistiques de camp (pas encore implémenté).
-/ Afficher les données: Affiche les données présentement dans le programme (pas implémenté complètement).
+/ Estimer la quantité de chandail: Permet d'estimer la quantité de chandails à commander selon le nombre d'enfants présentement inscrits. À deux modes: le mode partiel n'utilise que les enfants présentement inscrits, le mode complet extrapole cette information avec le nombre d'enfants de chaque catégorie de la saison passée, tiré de l'historique des chandails (le programme demande pour chaque catégorie s'il faut utiliser un autre nombre). L'estimation est comparée aux chandails de la saison passée, et peut être enregistrée dans l'historique.
+/ Faire les statistiques de camp: Calcule, par saison, site, semaine et catégorie, les inscriptions par rapport à la capacité des groupes (taux d'occupation), la répartition des genres, des âges et des intérêts (premier choix), le nombre de familles et le nombre d'enfants ayant besoin d'accompagnement. Les statistiques peuvent être affichées, exportées dans le fichier `statistiques.csv` (séparé par des points-virgules, pour Excel) ou imprimées dans le rapport `statistiques.pdf`, avec un graphique de l'occupation de chaque site par semaine. Le taux d'occupation n'est calculé que si la capacité de tous les groupes est connue, c'est-à-dire si la programmation a été lue.
+/ Afficher les données: Affiche les groupes, les membres, les comptes ou le rapport de la dernière importation présentement dans le programme. Le rapport d'importation peut être exporté dans le fichier `rapport_importation.csv`, pour corriger les données à la source dans Qidigo. Les listes de membres et de comptes sont affichées par pages de 20; entrez `s` ou `p` pour changer de page, `t` pour changer le tri (nom, âge ou groupe pour les membres; mandataire ou nombre d'enfants pour les comptes), `r` suivi d'un texte pour rechercher par nom, numéro de téléphone ou NAM (pour les comptes: mandataire, téléphone, courriel ou nom d'un enfant), `e` pour effacer la recherche et `q` pour revenir au menu. Entrez le numéro d'une ligne pour voir tout ce que le programme sait de l'enfant (ou des enfants du compte), incluant chacun de ses groupes et sous-groupes.
+
+  La _Concordance programmation / présences_ compare les groupes lus dans la programmation avec ceux des listes de présences: groupes présents dans une seule des deux sources, catégorie différente pour une même activité, un même site et une même semaine, restrictions d'âge de la programmation différentes des âges de la catégorie, et groupes qui ont plus d'inscriptions que de places (`Nombre de place max`). Les deux fichiers doivent avoir été lus (une session enregistrée avant cette version doit être relue). Le rapport peut être exporté dans le fichier `concordance.csv`.
+/ Sauvegarder la session: Enregistre dans un fichier toutes les données chargées (groupes, sous-groupes, animateurs, comptes et membres), pour pouvoir reprendre le travail plus tard sans relire les fichiers de Qidigo.
+/ Ouvrir une session: Recharge une session sauvegardée. Les données présentement dans le programme sont remplacées par celles de la session.
 / Quitter: Quitte le programme.
 
+=== Historique des chandails
+L'historique des chandails conserve, pour chaque saison passée, le nombre d'enfants de chaque catégorie et le nombre de chandails de chaque taille. Les saisons sont en ordre chronologique: la dernière du fichier est la saison passée. Après une estimation, le programme offre d'y enregistrer la saison courante; il est conseillé de corriger ensuite les tailles avec les quantités réellement commandées.
+
+```toml
+[[saisons]]
+saison = "Été 2024"
+enfants = { Crocus = 85, Balaous = 111, Basaltes = 102 }
+tailles = { XS = 40, S = 95, M = 90, L = 50, XL = 20, XXL = 3 }
+```
+
 == Utilisation Générale
 Les étapes pour générer les listes de présences et les fiches médicales sont les suivantes:
 1. *Télécharger les informations de Qidigo*: Dans l'onglet `Activités > Liste de présences`, sélectionner le modèle approprié contenant toutes les informations nécessaire (présentement le modèle "2025 - Complet"), puis télécharger le fichier excel.
 2. *Ouvrir PrésenceJJ*: Ouvrir le programme en double-cliquant sur le script `presencejj.bat`
 3. *Lire à partir de la liste de présences*: Sélectionnez l'option 2, puis choisissez le fichier téléchargé à l'étape 1 pour charger les informations.
-4. *Faire les sous-groupes*: Sélectionnez l'option 3 pour calculer les sous-groupes. Si le programme est incertain du nombre de sous-groupes à faire, il va vous demander combien vous en voulez pour un groupe donné.
-5. *Générer les fiches médicales*: Sélectionnez l'option 4, puis choisissez le dossier de sortie. PrésenceJJ n'écrase pas les fiches médicales déjà existantes, et ne génère que celles des nouveaux enfants. Vous pouvez donc trier par date de création pour n'imprimer que les nouvelles fiches.
-6. *Générer les listes de présences*: Sélectionnez l'option 5, puis choisissez le dossier de sortie.
+4. *Faire les sous-groupes*: Sélectionnez l'option 4 pour calculer les sous-groupes. Si le programme est incertain du nombre de sous-groupes à faire, il va vous demander combien vous en voulez pour un groupe donné. Corrigez-les ensuite au besoin avec l'option 5, en verrouillant ce qui doit rester en place.
+5. *Générer les fiches médicales*: Sélectionnez l'option 6, puis choisissez le dossier de sortie. PrésenceJJ n'écrase pas les fiches médicales déjà existantes, et ne génère que celles des nouveaux enfants. Vous pouvez donc trier par date de création pour n'imprimer que les nouvelles fiches.
+6. *Générer les listes de présences*: Sélectionnez l'option 7, puis choisissez le dossier de sortie.
 7. *Imprimer les fiches et listes*: Imprimer les documents générés de la manière de votre choix.
+
+== Utilisation en ligne de commande
+Toutes les étapes peuvent aussi être faites sans le menu, par exemple dans un script exécuté chaque lundi matin. Chaque commande retourne un code de sortie différent de 0 si une partie a échoué.
+
+L'option `--session` permet d'enchaîner les commandes: la session est ouverte avant la commande (ou créée si elle n'existe pas), puis sauvegardée après.
+
+```
+presencejj --typst templates --session session.json import-prog programmation.xlsx
+presencejj --typst templates --session session.json import-presence presences.xlsx --rapport sortie/rapport_importation.csv
+presencejj --session session.json import-presence telechargements/sites --rapport sortie/rapport_importation.csv
+presencejj --typst templates --session session.json sous-groupes
+presencejj --session session.json sous-groupes --nouveaux
+presencejj --session session.json sous-groupes --comparer
+presencejj --session session.json sous-groupes --out sortie
+presencejj --typst templates --session session.json print presences --out sortie
+presencejj --typst templates --session session.json print fiches --out sortie
+presencejj --typst templates --session session.json print stats --out sortie
+presencejj --typst templates --session session.json stats --out sortie
+presencejj --session session.json concordance --out sortie
+presencejj --typst templates --session session.json chandails --mode simple
+presencejj --session session.json chandails --mode complex --prevu Crocus=90 --enregistrer "Été 2025"
+presencejj delta presences_lundi.xlsx presences_jeudi.xlsx
+presencejj --session session.json delta presences_jeudi.xlsx
+```
+
+En mode ligne de commande, les groupes dont la catégorie est inconnue n'ont pas de sous-groupes, puisque le programme ne peut pas demander combien en faire. La commande `presencejj --help` donne la liste complète des commandes et options.
@@ -0,0 +1,209 @@
+use clap::{Parser, Subcommand, ValueEnum};
+use console::style;
+
+use crate::ProgramData;
+
+/// Génère les listes de présences et les fiches santé du camp de jour à partir des exports de Qidigo.
+///
+/// Sans commande, le programme démarre le menu interactif.
+#[derive(Debug, Parser)]
+#[command(name = "presencejj", version)]
+pub struct Cli {
+    /// Dossier de travail de Typst (contenant template.typ)
+    #[arg(long = "typst", global = true, value_name = "DOSSIER")]
+    pub typst: Option<String>,
+
+    /// Fichier de configuration (par défaut presencejj.toml dans le dossier courant, s'il existe)
+    #[arg(long, global = true, value_name = "FICHIER")]
+    pub config: Option<String>,
+
+    /// Fichier de session à ouvrir avant la commande et à sauvegarder après (créé s'il n'existe pas)
+    #[arg(long, global = true, value_name = "FICHIER")]
+    pub session: Option<String>,
+
+    #[command(subcommand)]
+    pub commande: Option<Commande>,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Commande {
+    /// Lire les listes de présences téléchargées de Qidigo
+    ImportPresence {
+        /// Fichiers ou dossiers (tous les fichiers excel, ods et csv du dossier), lus du plus ancien au plus récent
+        #[arg(required = true, value_name = "FICHIER")]
+        fichiers: Vec<String>,
+        /// Exporter les problèmes de lecture dans ce fichier CSV
+        #[arg(long, value_name = "CSV")]
+        rapport: Option<String>,
+    },
+    /// Lire la programmation téléchargée de Qidigo
+    ImportProg {
+        #[arg(required = true, value_name = "FICHIER")]
+        fichiers: Vec<String>,
+    },
+    /// Faire les sous-groupes (les groupes de catégorie inconnue sont ignorés)
+    SousGroupes {
+        /// Garder les sous-groupes existants: placer seulement les nouveaux inscrits et retirer les désinscrits
+        #[arg(long)]
+        nouveaux: bool,
+        /// Afficher côte à côte les sous-groupes de chaque stratégie, sans rien changer
+        #[arg(long, conflicts_with = "nouveaux")]
+        comparer: bool,
+        /// Exporter aussi le rapport de qualité des sous-groupes dans qualite_sous_groupes.csv, dans ce dossier
+        #[arg(long, value_name = "DOSSIER", conflicts_with = "comparer")]
+        out: Option<String>,
+    },
+    /// Générer des documents pdf
+    #[command(subcommand)]
+    Print(PrintCommande),
+    /// Afficher les statistiques de camp
+    Stats {
+        /// Exporter aussi les statistiques dans statistiques.csv, dans ce dossier
+        #[arg(long, value_name = "DOSSIER")]
+        out: Option<String>,
+    },
+    /// Comparer la programmation et les listes de présences: groupes absents d'une source,
+    /// catégories ou âges différents et groupes surchargés
+    Concordance {
+        /// Exporter aussi le rapport dans concordance.csv, dans ce dossier
+        #[arg(long, value_name = "DOSSIER")]
+        out: Option<String>,
+    },
+    /// Comparer deux exports de présences (ou une session et un export) pour savoir quoi réimprimer
+    Delta {
+        /// L'ancien export (xlsx ou session json) puis le nouveau. Avec un seul fichier, il est comparé à la session (--session).
+        #[arg(required = true, num_args = 1..=2, value_name = "FICHIER")]
+        fichiers: Vec<String>,
+    },
+    /// Estimer la quantité de chandails à commander
+    Chandails {
+        #[arg(long, value_enum, default_value_t = ModeChandail::Simple)]
+        mode: ModeChandail,
+        /// Nombre d'enfants prévu pour une catégorie, au lieu de celui de la saison passée (mode complex)
+        #[arg(long = "prevu", value_name = "CATEGORIE=N", value_parser = lire_prevu)]
+        prevus: Vec<(String, usize)>,
+        /// Enregistrer l'estimation dans l'historique des chandails sous ce nom de saison
+        #[arg(long, value_name = "SAISON")]
+        enregistrer: Option<String>,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum PrintCommande {
+    /// Listes de présences d'animateur et de service de garde
+    Presences {
+        /// Dossier de sortie (par défaut celui de la configuration)
+        #[arg(long, value_name = "DOSSIER")]
+        out: Option<String>,
+    },
+    /// Fiches santé, triées par site
+    Fiches {
+        /// Dossier de sortie (par défaut celui de la configuration)
+        #[arg(long, value_name = "DOSSIER")]
+        out: Option<String>,
+        /// Regénérer les fiches déjà existantes
+        #[arg(long)]
+        update: bool,
+    },
+    /// Rapport de statistiques de camp (statistiques.pdf)
+    Stats {
+        /// Dossier de sortie (par défaut celui de la configuration)
+        #[arg(long, value_name = "DOSSIER")]
+        out: Option<String>,
+    },
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum ModeChandail {
+    /// N'utilise que les inscriptions courantes
+    Simple,
+    /// Utilise le nombre d'enfants de chaque catégorie de la saison passée (voir l'historique des chandails)
+    Complex,
+}
+
+fn lire_prevu(s: &str) -> Result<(String, usize), String> {
+    let (cat, nb) = s.split_once('=').ok_or("format attendu: CATEGORIE=N")?;
+    let nb = nb.trim().parse().map_err(|_| format!("nombre invalide: {}", nb))?;
+    Ok((cat.trim().to_string(), nb))
+}
+
+/// Exécute une commande sans interaction. Retourne `Err` si une partie de la commande a échoué.
+pub(crate) fn executer(program: &mut ProgramData, commande: &Commande) -> Result<(), ()> {
+    match commande {
+        Commande::ImportPresence { fichiers, rapport } => {
+            let res = crate::charger_presences(program, fichiers);
+            program.rapport_import.afficher(&program.out);
+            match rapport {
+                Some(rapport) => res.and(crate::exporter_rapport_import_csv(program, rapport)),
+                None => res,
+            }
+        },
+        Commande::ImportProg { fichiers } => {
+            let mut res = Ok(());
+            for f in fichiers {
+                if crate::charger_prog(program, f).is_err() {
+                    res = Err(());
+                }
+            }
+            program.rapport_import.afficher(&program.out);
+            res
+        },
+        Commande::SousGroupes { comparer: true, .. } => crate::comparer_strategies(program),
+        Commande::SousGroupes { nouveaux, out, .. } => {
+            // des sous-groupes incomplets ne sont ni mesurés ni exportés
+            crate::build_sous_groupes(program, *nouveaux)?;
+            crate::RapportQualite::calculer(&program.groupes, &program.membres).afficher(&program.out);
+            match out {
+                Some(out) => crate::exporter_qualite_csv(program, out),
+                None => Ok(()),
+            }
+        },
+        Commande::Print(PrintCommande::Presences { out }) => {
+            let res_anim = crate::print_presences_anim(program, out.as_deref());
+            let res_sdj = crate::print_presences_sdj(program, out.as_deref());
+            res_anim.and(res_sdj)
+        },
+        Commande::Print(PrintCommande::Fiches { out, update }) => {
+            let out = out.as_deref().unwrap_or(&program.config.out_dir);
+            crate::print_fiche_santes_dans(program, out, *update)
+        },
+        Commande::Print(PrintCommande::Stats { out }) => crate::print_stats_pdf(program, out.as_deref()),
+        Commande::Stats { out } => {
+            let res = crate::afficher_stats(program);
+            match out {
+                Some(out) => res.and(crate::exporter_stats_csv(program, out)),
+                None => res,
+            }
+        },
+        Commande::Concordance { out } => {
+            let concordance = crate::Concordance::calculer(&program.groupes);
+            concordance.afficher(&program.out);
+            match out {
+                Some(out) => crate::exporter_concordance_csv(program, out),
+                None => Ok(()),
+            }
+        },
+        Commande::Delta { fichiers } => match fichiers.as_slice() {
+            [nouveau] => crate::comparer_exports(program, None, nouveau),
+            [ancien, nouveau, ..] => crate::comparer_exports(program, Some(ancien), nouveau),
+            [] => Err(()),
+        },
+        Commande::Chandails { mode, prevus, enregistrer } => {
+            let mode = match mode {
+                ModeChandail::Simple => crate::EstimationChandailMode::Simple,
+                ModeChandail::Complex => crate::EstimationChandailMode::Complex,
+            };
+            let prevus = prevus.iter().cloned().collect();
+            crate::afficher_estimation_chandail(program, mode, &prevus, enregistrer.as_deref())
+        },
+    }
+}
+
+/// Ouvre la session donnée si elle existe. Un fichier inexistant n'est pas une erreur: il sera créé à la sauvegarde.
+pub(crate) fn ouvrir_session_cli(program: &mut ProgramData, filepath: &str) -> Result<(), ()> {
+    if !std::path::Path::new(filepath).exists() {
+        let _ = program.out.write_line(&format!("Nouvelle session \"{}\"", style(filepath).green()));
+        return Ok(());
+    }
+    crate::ouvrir_session_de(program, filepath)
+}
@@ -0,0 +1,216 @@
+use std::{collections::{BTreeMap, HashMap}, fmt::Display};
+
+use console::{style, Term};
+
+use crate::{groupes::groupes::{Groupe, GroupeID, GroupeReg, TrancheAge, NULL_GROUPE}, prelude::O};
+
+/// Une différence entre la programmation et les listes de présences.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Ecart {
+    /// Groupe d'une liste de présences introuvable dans la programmation
+    AbsentDeLaProg,
+    /// Groupe de la programmation sans liste de présences (ni feuille des groupes vides)
+    AbsentDesPresences,
+    /// Même activité, site et semaine, mais pas la même catégorie
+    Categorie { prog: O<String>, presence: O<String> },
+    /// Restrictions d'âge de la programmation différentes des âges de la catégorie
+    Ages { prog: TrancheAge, presence: TrancheAge },
+    /// Plus d'inscriptions que de places
+    Surcharge { inscriptions: usize, capacite: usize },
+}
+impl Ecart {
+    pub fn titre(&self) -> &'static str {
+        match self {
+            Self::AbsentDeLaProg => "Absent de la programmation",
+            Self::AbsentDesPresences => "Absent des listes de présences",
+            Self::Categorie { .. } => "Catégorie différente",
+            Self::Ages { .. } => "Âges différents",
+            Self::Surcharge { .. } => "Groupe surchargé",
+        }
+    }
+}
+impl Display for Ecart {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::AbsentDeLaProg | Self::AbsentDesPresences => write!(f, "{}", self.titre()),
+            Self::Categorie { prog, presence } => write!(f, "{}: {} dans la programmation, {} dans les présences",
+                self.titre(), prog.as_deref().unwrap_or("aucune"), presence.as_deref().unwrap_or("aucune")),
+            Self::Ages { prog, presence } => write!(f, "{}: {} dans la programmation, {} dans les présences", self.titre(), prog, presence),
+            Self::Surcharge { inscriptions, capacite } => write!(f, "{}: {} inscriptions pour {} places", self.titre(), inscriptions, capacite),
+        }
+    }
+}
+
+/// Un écart, avec la description du groupe tel que nommé dans la source où il a été trouvé.
+#[derive(Debug, Clone)]
+pub struct LigneConcordance {
+    pub groupe: String,
+    pub ecart: Ecart,
+}
+
+/// Groupes d'une même activité, site et semaine, qui devraient se retrouver dans les deux sources.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct CleConcordance {
+    saison: O<String>,
+    activite: O<String>,
+    site: O<String>,
+    semaine: O<String>,
+}
+impl CleConcordance {
+    fn de_groupe(g: &Groupe) -> Self {
+        Self {
+            saison: g.saison.clone(),
+            activite: g.activite.clone(),
+            site: g.site.clone(),
+            semaine: g.semaine.clone(),
+        }
+    }
+}
+
+/// Rapport de concordance entre la programmation et les listes de présences.
+///
+/// Un groupe de la programmation correspond aux groupes des listes de présences de même saison, activité,
+/// site, semaine et catégorie (le discriminant des présences, p. ex. "Groupe A", est ignoré si la
+/// programmation n'en a pas). Les groupes restants d'une même activité, site et semaine sont appariés
+/// s'il n'en reste qu'un de chaque côté: leur catégorie diffère.
+#[derive(Debug, Clone, Default)]
+pub struct Concordance {
+    pub lignes: Vec<LigneConcordance>,
+    pub nb_prog: usize,
+    pub nb_presence: usize,
+    /// Groupes de la programmation retrouvés dans les listes de présences
+    pub nb_apparies: usize,
+}
+impl Concordance {
+    pub fn calculer(groupes: &GroupeReg) -> Self {
+        let mut res = Self::default();
+        let groupes: Vec<&Groupe> = groupes.groupes().filter(|g| **g != *NULL_GROUPE).collect();
+        let progs: Vec<&Groupe> = groupes.iter().copied().filter(|g| g.sources.prog.is_some()).collect();
+        let presences: Vec<&Groupe> = groupes.iter().copied().filter(|g| g.sources.presence.is_some()).collect();
+        res.nb_prog = progs.len();
+        res.nb_presence = presences.len();
+        if progs.is_empty() || presences.is_empty() {
+            return res;
+        }
+
+        // 1. Appariement exact: un groupe lu dans les deux sources est apparié à lui-même
+        let mut apparies: BTreeMap<GroupeID, Vec<&Groupe>> = BTreeMap::new();
+        let mut seuls: Vec<&Groupe> = Vec::new();
+        for q in presences.iter().copied() {
+            let p = if q.sources.prog.is_some() {
+                Some(q)
+            } else {
+                progs.iter().copied().find(|p| correspond(p, q))
+            };
+            match p {
+                Some(p) => apparies.entry(p.id).or_default().push(q),
+                None => seuls.push(q),
+            }
+        }
+        let progs_seuls: Vec<&Groupe> = progs.iter().copied().filter(|p| !apparies.contains_key(&p.id)).collect();
+
+        // 2. Appariement par activité, site et semaine, s'il ne reste qu'un groupe de chaque côté
+        let mut par_cle: HashMap<CleConcordance, (Vec<&Groupe>, Vec<&Groupe>)> = HashMap::new();
+        for p in progs_seuls.iter().copied() {
+            par_cle.entry(CleConcordance::de_groupe(p)).or_default().0.push(p);
+        }
+        for q in seuls.iter().copied() {
+            par_cle.entry(CleConcordance::de_groupe(q)).or_default().1.push(q);
+        }
+        for (ps, qs) in par_cle.into_values() {
+            if let ([p], [q]) = (ps.as_slice(), qs.as_slice()) {
+                res.ajouter(q, Ecart::Categorie { prog: p.category.clone(), presence: q.category.clone() });
+                apparies.entry(p.id).or_default().push(q);
+                continue;
+            }
+            for p in ps {
+                res.ajouter(p, Ecart::AbsentDesPresences);
+            }
+            for q in qs {
+                res.ajouter(q, Ecart::AbsentDeLaProg);
+            }
+        }
+
+        // 3. Âges et capacité des groupes appariés
+        res.nb_apparies = apparies.len();
+        for (pid, qs) in apparies.iter() {
+            let p = progs.iter().copied().find(|p| p.id == *pid).unwrap();
+            let tranche_prog = p.sources.prog.unwrap();
+            for q in qs.iter() {
+                let tranche_presence = q.sources.presence.unwrap();
+                if tranche_prog != tranche_presence {
+                    res.ajouter(q, Ecart::Ages { prog: tranche_prog, presence: tranche_presence });
+                }
+            }
+            // un groupe lu dans les deux sources n'est compté qu'une fois
+            let mut inscriptions: Vec<_> = qs.iter().flat_map(|q| q.participants.iter()).collect();
+            inscriptions.sort();
+            inscriptions.dedup();
+            if let Some(capacite) = p.capacite {
+                if inscriptions.len() > capacite {
+                    res.ajouter(p, Ecart::Surcharge { inscriptions: inscriptions.len(), capacite });
+                }
+            }
+        }
+        res.lignes.sort_by(|a, b| a.groupe.cmp(&b.groupe).then_with(|| a.ecart.cmp(&b.ecart)));
+        res
+    }
+
+    fn ajouter(&mut self, g: &Groupe, ecart: Ecart) {
+        self.lignes.push(LigneConcordance { groupe: g.desc(), ecart });
+    }
+
+    pub fn afficher(&self, term: &Term) {
+        if self.nb_prog == 0 || self.nb_presence == 0 {
+            let manquant = if self.nb_prog == 0 {"programmation"} else {"liste de présences"};
+            let _ = term.write_line(&format!("{}", style(format!("Aucune {} lue: lire les deux sources pour les comparer", manquant)).yellow()));
+            return;
+        }
+        let _ = term.write_line(&format!("{}", style(format!("Concordance: {} groupe(s) de la programmation, {} groupe(s) des listes de présences, {} apparié(s)",
+            self.nb_prog, self.nb_presence, self.nb_apparies)).bold().cyan()));
+        if self.lignes.is_empty() {
+            let _ = term.write_line(&format!("{}", style("La programmation et les listes de présences concordent").green()));
+            return;
+        }
+        let mut groupe = None;
+        for l in self.lignes.iter() {
+            if groupe != Some(&l.groupe) {
+                let _ = term.write_line(&format!("{}", style(&l.groupe).bold()));
+                groupe = Some(&l.groupe);
+            }
+            let ligne = match l.ecart {
+                Ecart::Surcharge { .. } | Ecart::AbsentDeLaProg => style(l.ecart.to_string()).red(),
+                _ => style(l.ecart.to_string()).yellow(),
+            };
+            let _ = term.write_line(&format!("\t{}", ligne));
+        }
+    }
+
+    pub fn exporter_csv(&self, filepath: &str) -> Result<(), csv::Error> {
+        let mut wtr = csv::WriterBuilder::new().delimiter(b';').from_path(filepath)?;
+        wtr.write_record(["Groupe", "Écart", "Programmation", "Listes de présences"])?;
+        for l in self.lignes.iter() {
+            let (prog, presence) = match &l.ecart {
+                Ecart::AbsentDeLaProg => (String::new(), "présent".into()),
+                Ecart::AbsentDesPresences => ("présent".into(), String::new()),
+                Ecart::Categorie { prog, presence } => (prog.clone().unwrap_or_default(), presence.clone().unwrap_or_default()),
+                Ecart::Ages { prog, presence } => (prog.to_string(), presence.to_string()),
+                Ecart::Surcharge { inscriptions, capacite } => (format!("{} places", capacite), format!("{} inscriptions", inscriptions)),
+            };
+            wtr.write_record([l.groupe.clone(), l.ecart.titre().into(), prog, presence])?;
+        }
+        wtr.flush()?;
+        Ok(())
+    }
+}
+
+/// Le groupe des présences `q` est-il le groupe `p` de la programmation?
+fn correspond(p: &Groupe, q: &Groupe) -> bool {
+    let meme = |a: &O<String>, b: &O<String>| a.is_none() || b.is_none() || a == b;
+    p.saison == q.saison
+        && p.site == q.site
+        && p.semaine == q.semaine
+        && p.category == q.category
+        && meme(&p.activite, &q.activite)
+        && (p.discriminant.is_none() || p.discriminant == q.discriminant)
+}
@@ -1,9 +1,51 @@
+use std::{error::Error, fmt::Display, path::Path};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{extract::{colonnes::AliasColonnes, noms::ReglesNoms}, groupes::{categories::Categories, sous_groupes::ConfigSousGroupes, strategies::NomStrategie}, prelude::*};
+
+/// Nom du fichier de configuration cherché dans le dossier de travail si aucun n'est donné.
+pub static CONFIG_FILENAME: &str = "presencejj.toml";
+
+#[derive(Debug)]
+pub enum ConfigError {
+    Lecture(std::io::Error),
+    Ecriture(std::io::Error),
+    Format(String),
+}
+impl Display for ConfigError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ConfigError::Lecture(e) => write!(f, "ConfigError: N'a pu lire le fichier ({})", e),
+            ConfigError::Ecriture(e) => write!(f, "ConfigError: N'a pu écrire le fichier ({})", e),
+            ConfigError::Format(e) => write!(f, "ConfigError: Fichier de configuration invalide ({})", e),
+        }
+    }
+}
+impl Error for ConfigError {}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct Config {
-    pub out_dir: String, 
+    pub out_dir: String,
     pub working_dir: String,
     pub verbose: bool,
     pub excel: ExcelConfig,
     pub typst_working_dir: String,
+    /// Fichier où sont conservés les derniers dossiers utilisés, relatif au dossier de travail
+    pub fichier_etat: String,
+    /// Fichier de correspondance entre les champs et les entêtes de colonnes des listes de présences
+    pub fichier_colonnes: O<String>,
+    /// Historique des chandails des saisons passées, relatif au dossier de travail
+    pub fichier_chandails: String,
+    /// Catégories d'âge reconnues dans le nom des groupes
+    pub categories: Categories,
+    /// Règles de noms de groupes supplémentaires (regex avec les captures activite, site, category et semaine)
+    pub noms_groupes: ReglesNoms,
+    /// Contraintes et objectifs de la formation des sous-groupes
+    pub sous_groupes: ConfigSousGroupes,
+    #[serde(skip)]
+    pub colonnes: AliasColonnes,
 }
 impl Default for Config {
     fn default() -> Self {
@@ -13,18 +55,89 @@ impl Default for Config {
             excel: ExcelConfig::default(),
             working_dir: ".".into(),
             typst_working_dir: "templates".into(),
+            fichier_etat: "presencejj_etat.toml".into(),
+            fichier_colonnes: None,
+            fichier_chandails: "historique_chandails.toml".into(),
+            categories: Categories::default(),
+            noms_groupes: ReglesNoms::default(),
+            sous_groupes: ConfigSousGroupes::default(),
+            colonnes: AliasColonnes::default(),
+        }
+    }
+}
+impl Config {
+    /// La stratégie de sous-groupes de la catégorie, ou celle de `[sous_groupes]`.
+    pub fn strategie(&self, categorie: O<&str>) -> NomStrategie {
+        categorie.and_then(|c| self.categories.get(c)).and_then(|c| c.strategie).unwrap_or(self.sous_groupes.strategie)
+    }
+
+    pub fn charger(filepath: &str) -> Result<Self, ConfigError> {
+        let contenu = std::fs::read_to_string(filepath).map_err(ConfigError::Lecture)?;
+        toml::from_str(&contenu).map_err(|e| ConfigError::Format(e.to_string()))
+    }
+
+    /// Charge le fichier donné, ou sinon `presencejj.toml` dans le dossier de travail s'il existe.
+    /// Sans fichier, la configuration par défaut est utilisée.
+    pub fn trouver(filepath: O<&str>, working_dir: &str) -> Result<Self, ConfigError> {
+        let mut config = match filepath {
+            Some(f) => Self::charger(f)?,
+            None => {
+                let defaut = Path::new(working_dir).join(CONFIG_FILENAME);
+                if defaut.exists() {
+                    Self::charger(defaut.to_str().unwrap())?
+                } else {
+                    Self::default()
+                }
+            },
+        };
+        if config.working_dir == "." {
+            config.working_dir = working_dir.into();
         }
+        if let Some(f) = &config.fichier_colonnes {
+            let chemin = Path::new(&config.working_dir).join(f);
+            config.colonnes = AliasColonnes::charger(chemin.to_str().unwrap())?;
+        }
+        Ok(config)
+    }
+
+    pub fn chemin_etat(&self) -> String {
+        Path::new(&self.working_dir).join(&self.fichier_etat).to_str().unwrap().to_string()
+    }
+
+    pub fn chemin_chandails(&self) -> String {
+        Path::new(&self.working_dir).join(&self.fichier_chandails).to_str().unwrap().to_string()
     }
 }
+
+/// Disposition des listes de présences. Par défaut, la ligne d'entêtes est trouvée automatiquement
+/// dans chaque feuille; ces valeurs ne servent qu'à l'imposer.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
 pub struct ExcelConfig {
-    pub ln_skip: usize,
-    pub data_ln: usize,
+    /// Nombre de lignes à sauter avant les enfants (par défaut, ils suivent la ligne d'entêtes)
+    pub ln_skip: O<usize>,
+    /// Ligne des entêtes de colonnes, à partir de 0
+    pub data_ln: O<usize>,
 }
-impl Default for ExcelConfig {
-    fn default() -> Self {
-        Self {
-            ln_skip: 6,
-            data_ln: 5,
-        }
+
+/// Ce dont le programme se souvient d'une exécution à l'autre.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Etat {
+    pub dernier_dossier_sortie: O<String>,
+    pub dernier_dossier_entree: O<String>,
+}
+impl Etat {
+    /// Un fichier d'état absent ou illisible donne simplement un état vide.
+    pub fn charger(filepath: &str) -> Self {
+        std::fs::read_to_string(filepath)
+            .ok()
+            .and_then(|s| toml::from_str(&s).ok())
+            .unwrap_or_default()
     }
-}
\ No newline at end of file
+
+    pub fn sauvegarder(&self, filepath: &str) -> Result<(), ConfigError> {
+        let contenu = toml::to_string_pretty(self).map_err(|e| ConfigError::Format(e.to_string()))?;
+        std::fs::write(filepath, contenu).map_err(ConfigError::Ecriture)
+    }
+}
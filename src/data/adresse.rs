@@ -7,6 +7,7 @@ use crate::prelude::*;
 
 use lazy_static::lazy_static;
 use regex::Regex;
+use serde::{Deserialize, Serialize};
 
 use super::{ParsingError, ParsingResult};
 
@@ -17,7 +18,7 @@ lazy_static! {
 	pub static ref ADRESSE_FULL_REGEX: Regex = Regex::new(r"^\s*(?P<num>\d+)\s*(?:,\s*)?(?P<rue>[a-zA-Z0-9éÉàÀùÙÇçïÏôÔêÊèÈâÂûÛëËäÄöÖüÜòÒ\- .']+)(?:\s*(,\s*)?#(?P<app>\d+))?\s*,\s*(?P<ville>[a-zA-Z0-9éÉàÀùÙÇçïÏôÔêÊèÈâÂûÛëËäÄöÖüÜòÒ\- .']+)\s*,\s*(?P<province>[a-zA-Z0-9éÉàÀùÙÇçïÏôÔêÊèÈâÂûÛëËäÄöÖüÜòÒ\- .']+)\s*,\s*(?P<pays>[a-zA-Z0-9éÉàÀùÙÇçïÏôÔêÊèÈâÂûÛëËäÄöÖüÜòÒ\- .']+)\s*,\s*(?P<codepostal>[a-zA-Z][0-9][a-zA-Z] ?[0-9][a-zA-Z][0-9])\s*$").unwrap();
 }
 
-#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct Adresse {
 	pub numero: O<i32>,
 	pub rue: O<String>,
@@ -179,6 +180,7 @@ impl SimpleRegItem<str> for Pays {
 	}
 }
 */
+serde_par_chaine!(Pays, |s: &str| Ok::<_, ParsingError>(Pays::from(s)));
 impl From<Arc<str>> for Pays {
 	fn from(value: Arc<str>) -> Self {
 		Self { ptr: value }
@@ -223,6 +225,7 @@ impl From<&str> for Province {
 		Arc::from(src)
 	}
 } */
+serde_par_chaine!(Province, |s: &str| Ok::<_, ParsingError>(Province::from(s)));
 impl From<Arc<str>> for Province {
 	fn from(value: Arc<str>) -> Self {
 		Self { ptr: value }
@@ -268,6 +271,7 @@ impl From<&str> for Ville {
 		Arc::from(src)
 	}
 } */
+serde_par_chaine!(Ville, |s: &str| Ok::<_, ParsingError>(Ville::from(s)));
 impl From<Arc<str>> for Ville {
 	fn from(value: Arc<str>) -> Self {
 		Self { ptr: value }
@@ -460,6 +464,7 @@ impl PolarsObject for CodePostal {
 	}
 }
 */
+serde_par_chaine!(CodePostal, CodePostal::parse);
 impl str::FromStr for CodePostal {
 	type Err = ParsingError;
 	fn from_str(s: &str) -> Result<Self, Self::Err> {
@@ -6,6 +6,7 @@ use super::{Genre, ParsingError, ParsingResult};
 
 use lazy_static::lazy_static;
 use regex::Regex;
+use serde::{Deserialize, Serialize};
 
 lazy_static! {
 	pub static ref NAM_RE: Regex =
@@ -114,6 +115,7 @@ impl NAM {
 		NAM(num)
 	}
 }
+serde_par_chaine!(NAM, NAM::parse);
 impl FromStr for NAM {
 	type Err = ParsingError;
 	fn from_str(s: &str) -> Result<Self, Self::Err> {
@@ -126,7 +128,7 @@ impl fmt::Display for NAM {
 	}
 }
 
-#[derive(Debug, PartialEq, Eq, Clone, Hash, PartialOrd, Ord, Default, Copy)]
+#[derive(Debug, PartialEq, Eq, Clone, Hash, PartialOrd, Ord, Default, Copy, Serialize, Deserialize)]
 pub struct CAM {
 	pub num: NAM,
 	pub exp: (i32, u8),
@@ -75,6 +75,7 @@ impl PolarsObject for Email {
 	}
 }
 */
+serde_par_chaine!(Email, Email::parse);
 impl str::FromStr for Email {
 	type Err = ParsingError;
 	fn from_str(s: &str) -> Result<Self, Self::Err> {
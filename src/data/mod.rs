@@ -2,6 +2,26 @@ use crate::prelude::*;
 use std::{error::Error, fmt::{self, Display}, str::FromStr};
 use lazy_static::lazy_static;
 use regex::Regex;
+use serde::{Deserialize, Serialize};
+use strum_macros::EnumIter;
+
+/// Implémente `Serialize` et `Deserialize` pour un type qui se représente par une chaîne.
+/// Le type doit avoir une méthode `as_str`, et `$parse` doit transformer un `&str` en `Result<Self, E: Display>`.
+macro_rules! serde_par_chaine {
+	($t:ty, $parse:expr) => {
+		impl serde::Serialize for $t {
+			fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+				serializer.serialize_str(self.as_str())
+			}
+		}
+		impl<'de> serde::Deserialize<'de> for $t {
+			fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+				let s = <String as serde::Deserialize>::deserialize(deserializer)?;
+				($parse)(s.as_str()).map_err(serde::de::Error::custom)
+			}
+		}
+	};
+}
 
 pub mod adresse;
 pub mod cam;
@@ -17,7 +37,7 @@ lazy_static! {
 	pub static ref adr_re: Regex = Regex::new(r"(:?[\w-]+\s*:\s*)?(?P<num>\d+)\s*,?\s*(?:(?:(?P<rue>[\w\s-]+?)\s*(?P<ville>[\w-]+))|(?:(?P<ruewapp>[\w\s-]+?)\s*#(?:(?P<app>\d+)|(?P<falseapp>-))\s*,\s*(?P<villewapp>[\w-]+)))\s*,\s*(?P<province>[\w -]+?)\s*,\s*(?P<pays>[\w -]+?)\s*,\s*(?P<code>[A-Za-z]\d[A-Za-z]\s*\d[A-Za-z]\d)").unwrap();
 }
 
-#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug, Default, Hash)]
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug, Default, Hash, EnumIter, Serialize, Deserialize)]
 pub enum Genre {
     #[default]
     Homme,
@@ -48,7 +68,7 @@ impl FromStr for Genre {
 
 static TAILLES: [Taille; 6] = [Taille::XS, Taille::S, Taille::M, Taille::L, Taille::XL, Taille::XXL];
 
-#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug, Hash)]
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug, Hash, Serialize, Deserialize)]
 pub enum Taille {
     XS,
     S,
@@ -89,7 +109,7 @@ impl Taille {
 	}
 }
 
-#[derive(Debug, Clone, Default, Hash)]
+#[derive(Debug, Clone, Default, Hash, Serialize, Deserialize)]
 pub struct BoolJustifie {
     pub reponse: bool,
     pub justification: O<String>,
@@ -69,6 +69,7 @@ impl PolarsObject for Tel {
 	}
 }
 */
+serde_par_chaine!(Tel, Tel::parse);
 impl str::FromStr for Tel {
 	type Err = ParsingError;
 	fn from_str(s: &str) -> Result<Self, Self::Err> {
@@ -0,0 +1,411 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+use console::{style, Term};
+
+use crate::{groupes::{comptes::{Compte, CompteID, CompteReg, NULL_COMPTE}, groupes::{GroupeID, GroupeReg, NULL_GROUPE}, membres::{Difference, Membre, MembreID, MembreReg, NULL_MEMBRE}}, prelude::O};
+
+/// Les registres d'un export (ou d'une session) à comparer.
+#[derive(Clone, Copy)]
+pub struct Donnees<'a> {
+    pub groupes: &'a GroupeReg,
+    pub comptes: &'a CompteReg,
+    pub membres: &'a MembreReg,
+}
+impl<'a> Donnees<'a> {
+    pub fn new(groupes: &'a GroupeReg, comptes: &'a CompteReg, membres: &'a MembreReg) -> Self {
+        Self { groupes, comptes, membres }
+    }
+
+    fn comptes(&self) -> impl Iterator<Item = &'a Compte> {
+        self.comptes.comptes().filter(|c| c.id != NULL_COMPTE.id)
+    }
+
+    fn membres(&self) -> impl Iterator<Item = &'a Membre> {
+        self.membres.membres().filter(|m| **m != *NULL_MEMBRE)
+    }
+
+    /// Les groupes de chaque membre.
+    fn groupes_par_membre(&self) -> HashMap<MembreID, BTreeSet<GroupeID>> {
+        let mut res: HashMap<MembreID, BTreeSet<GroupeID>> = HashMap::new();
+        for grp in self.groupes.groupes().filter(|g| **g != *NULL_GROUPE) {
+            for mid in grp.participants.iter() {
+                res.entry(*mid).or_default().insert(grp.id);
+            }
+        }
+        res
+    }
+}
+
+/// Un groupe dans l'un ou l'autre des exports.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum CleGroupe {
+    /// Groupe de l'ancien export sans équivalent dans le nouveau
+    Ancien(GroupeID),
+    Nouveau(GroupeID),
+}
+
+/// Changements d'inscription d'un groupe entre deux exports.
+#[derive(Debug, Clone, Default)]
+pub struct DeltaGroupe {
+    pub desc: String,
+    /// Le groupe n'existe que dans le nouvel export
+    pub nouveau: bool,
+    /// Le groupe n'existe que dans l'ancien export
+    pub disparu: bool,
+    pub ajoutes: Vec<String>,
+    pub retires: Vec<String>,
+    /// (enfant, groupes quittés)
+    pub arrives: Vec<(String, Vec<String>)>,
+    /// (enfant, groupes rejoints)
+    pub partis: Vec<(String, Vec<String>)>,
+}
+impl DeltaGroupe {
+    pub fn est_vide(&self) -> bool {
+        self.ajoutes.is_empty() && self.retires.is_empty() && self.arrives.is_empty() && self.partis.is_empty()
+    }
+}
+
+/// Coordonnées d'un compte modifiées entre deux exports.
+#[derive(Debug, Clone)]
+pub struct DeltaCompte {
+    pub mandataire: String,
+    pub changements: Vec<Difference>,
+    /// Enfants du compte, dont les fiches portent ces coordonnées
+    pub enfants: Vec<String>,
+}
+
+/// Différences entre deux exports de Qidigo: inscriptions par groupe, coordonnées des comptes et fiches des enfants.
+#[derive(Debug, Clone, Default)]
+pub struct Delta {
+    pub groupes: Vec<DeltaGroupe>,
+    pub comptes: Vec<DeltaCompte>,
+    /// (enfant, champs modifiés)
+    pub fiches: Vec<(String, Vec<Difference>)>,
+}
+impl Delta {
+    pub fn calculer(ancien: Donnees, nouveau: Donnees) -> Self {
+        let mut delta = Delta::default();
+
+        // 1. Comptes: identiques (equiv), sinon même mandataire avec des coordonnées modifiées
+        let mut map_comptes: HashMap<CompteID, CompteID> = HashMap::new();
+        for c in ancien.comptes() {
+            if let Some(n) = nouveau.comptes().find(|n| n.equiv(c)) {
+                map_comptes.insert(c.id, n.id);
+            } else if let Some(n) = nouveau.comptes().find(|n| meme_famille(c, &ancien, n, &nouveau)) {
+                map_comptes.insert(c.id, n.id);
+                let changements = [
+                    ("Courriel", texte(&c.email), texte(&n.email)),
+                    ("Téléphone", texte(&c.tel), texte(&n.tel)),
+                    ("Adresse", texte(&c.adresse), texte(&n.adresse)),
+                ].into_iter().filter(|(_, a, b)| a != b).collect::<Vec<_>>();
+                let mut enfants = n.membres.iter().filter_map(|mid| nouveau.membres.get(*mid).ok()).collect::<Vec<&Membre>>();
+                enfants.sort_by(|a, b| a.cmp_nom(b));
+                delta.comptes.push(DeltaCompte {
+                    mandataire: n.mandataire.clone(),
+                    changements,
+                    enfants: enfants.into_iter().map(nom).collect(),
+                });
+            }
+        }
+        delta.comptes.sort_by(|a, b| a.mandataire.cmp(&b.mandataire));
+
+        // 2. Membres: equiv, une fois le compte ramené à celui du nouvel export
+        let mut map_membres: HashMap<MembreID, MembreID> = HashMap::new();
+        for m in ancien.membres() {
+            let mut m_nouveau_compte = m.clone();
+            m_nouveau_compte.compte = m.compte.and_then(|c| map_comptes.get(&c).copied());
+            if let Some(n) = nouveau.membres().find(|n| n.equiv(&m_nouveau_compte)) {
+                map_membres.insert(m.id, n.id);
+                let differences = m.differences(n);
+                if !differences.is_empty() {
+                    delta.fiches.push((nom(n), differences));
+                }
+            }
+        }
+        delta.fiches.sort();
+
+        // 3. Groupes: equiv
+        let map_groupes: HashMap<GroupeID, GroupeID> = ancien.groupes.groupes()
+            .filter(|g| **g != *NULL_GROUPE)
+            .filter_map(|g| nouveau.groupes.groupes().find(|n| n.equiv(g)).map(|n| (g.id, n.id)))
+            .collect();
+        let cle_ancien = |gid: GroupeID| match map_groupes.get(&gid) {
+            Some(n) => CleGroupe::Nouveau(*n),
+            None => CleGroupe::Ancien(gid),
+        };
+        let desc = |cle: CleGroupe| match cle {
+            CleGroupe::Ancien(gid) => ancien.groupes.get(gid).map(|g| g.desc()).unwrap_or_default(),
+            CleGroupe::Nouveau(gid) => nouveau.groupes.get(gid).map(|g| g.desc()).unwrap_or_default(),
+        };
+
+        let mut groupes: BTreeMap<CleGroupe, DeltaGroupe> = BTreeMap::new();
+        for g in ancien.groupes.groupes().filter(|g| **g != *NULL_GROUPE) {
+            let cle = cle_ancien(g.id);
+            groupes.entry(cle).or_insert_with(|| DeltaGroupe {
+                desc: desc(cle),
+                disparu: matches!(cle, CleGroupe::Ancien(_)),
+                ..Default::default()
+            });
+        }
+        for g in nouveau.groupes.groupes().filter(|g| **g != *NULL_GROUPE) {
+            groupes.entry(CleGroupe::Nouveau(g.id)).or_insert_with(|| DeltaGroupe {
+                desc: g.desc(),
+                nouveau: true,
+                ..Default::default()
+            });
+        }
+
+        // 4. Pour chaque enfant, les groupes quittés et rejoints
+        let grp_ancien = ancien.groupes_par_membre();
+        let grp_nouveau = nouveau.groupes_par_membre();
+        let vide = BTreeSet::new();
+        let mut enfants: Vec<(String, BTreeSet<CleGroupe>, BTreeSet<CleGroupe>)> = Vec::new();
+        for m in ancien.membres() {
+            let avant: BTreeSet<CleGroupe> = grp_ancien.get(&m.id).unwrap_or(&vide).iter().map(|g| cle_ancien(*g)).collect();
+            let apres: BTreeSet<CleGroupe> = match map_membres.get(&m.id) {
+                Some(n) => grp_nouveau.get(n).unwrap_or(&vide).iter().map(|g| CleGroupe::Nouveau(*g)).collect(),
+                None => BTreeSet::new(),
+            };
+            enfants.push((nom(m), avant, apres));
+        }
+        let deja_connus: BTreeSet<MembreID> = map_membres.values().copied().collect();
+        for m in nouveau.membres().filter(|m| !deja_connus.contains(&m.id)) {
+            let apres = grp_nouveau.get(&m.id).unwrap_or(&vide).iter().map(|g| CleGroupe::Nouveau(*g)).collect();
+            enfants.push((nom(m), BTreeSet::new(), apres));
+        }
+
+        for (enfant, avant, apres) in enfants {
+            let quittes: Vec<CleGroupe> = avant.difference(&apres).copied().collect();
+            let rejoints: Vec<CleGroupe> = apres.difference(&avant).copied().collect();
+            let desc_quittes: Vec<String> = quittes.iter().map(|c| desc(*c)).collect();
+            let desc_rejoints: Vec<String> = rejoints.iter().map(|c| desc(*c)).collect();
+            let deplace = !quittes.is_empty() && !rejoints.is_empty();
+            for cle in quittes.iter() {
+                let grp = groupes.get_mut(cle).unwrap();
+                if deplace {
+                    grp.partis.push((enfant.clone(), desc_rejoints.clone()));
+                } else {
+                    grp.retires.push(enfant.clone());
+                }
+            }
+            for cle in rejoints.iter() {
+                let grp = groupes.get_mut(cle).unwrap();
+                if deplace {
+                    grp.arrives.push((enfant.clone(), desc_quittes.clone()));
+                } else {
+                    grp.ajoutes.push(enfant.clone());
+                }
+            }
+        }
+
+        delta.groupes = groupes.into_values()
+            .filter(|g| !g.est_vide() || g.nouveau || g.disparu)
+            .map(|mut g| {
+                g.ajoutes.sort();
+                g.retires.sort();
+                g.arrives.sort();
+                g.partis.sort();
+                g
+            })
+            .collect();
+        delta.groupes.sort_by(|a, b| a.desc.cmp(&b.desc));
+        delta
+    }
+
+    pub fn est_vide(&self) -> bool {
+        self.groupes.is_empty() && self.comptes.is_empty() && self.fiches.is_empty()
+    }
+
+    /// Listes de présences (groupes) et fiches santé (enfants) à réimprimer.
+    pub fn a_reimprimer(&self) -> (Vec<&str>, BTreeSet<&str>) {
+        let listes = self.groupes.iter().filter(|g| !g.disparu).map(|g| g.desc.as_str()).collect();
+        let mut fiches: BTreeSet<&str> = BTreeSet::new();
+        for g in self.groupes.iter() {
+            fiches.extend(g.ajoutes.iter().map(String::as_str));
+            fiches.extend(g.arrives.iter().map(|(e, _)| e.as_str()));
+        }
+        for c in self.comptes.iter() {
+            fiches.extend(c.enfants.iter().map(String::as_str));
+        }
+        fiches.extend(self.fiches.iter().map(|(e, _)| e.as_str()));
+        (listes, fiches)
+    }
+
+    pub fn afficher(&self, term: &Term) {
+        if self.est_vide() {
+            let _ = term.write_line(&format!("{}", style("Aucun changement entre les deux exports").green()));
+            return;
+        }
+
+        if !self.groupes.is_empty() {
+            let _ = term.write_line(&format!("{}", style("Groupes").bold().cyan()));
+        }
+        for g in self.groupes.iter() {
+            let etat = if g.nouveau {
+                format!(" {}", style("(nouveau groupe)").yellow())
+            } else if g.disparu {
+                format!(" {}", style("(groupe disparu)").red())
+            } else {
+                String::new()
+            };
+            let _ = term.write_line(&format!("{}{}", style(&g.desc).bold(), etat));
+            for e in g.ajoutes.iter() {
+                let _ = term.write_line(&format!("\t{} {}", style("+").green(), e));
+            }
+            for e in g.retires.iter() {
+                let _ = term.write_line(&format!("\t{} {}", style("-").red(), e));
+            }
+            for (e, de) in g.arrives.iter() {
+                let _ = term.write_line(&format!("\t{} {} (de {})", style(">").yellow(), e, de.join(", ")));
+            }
+            for (e, vers) in g.partis.iter() {
+                let _ = term.write_line(&format!("\t{} {} (vers {})", style("<").yellow(), e, vers.join(", ")));
+            }
+        }
+
+        if !self.comptes.is_empty() {
+            let _ = term.write_line(&format!("{}", style("Comptes modifiés").bold().cyan()));
+            for c in self.comptes.iter() {
+                let _ = term.write_line(&format!("{} ({})", style(&c.mandataire).bold(), c.enfants.join(", ")));
+                for (champ, avant, apres) in c.changements.iter() {
+                    let _ = term.write_line(&format!("\t{}: {} -> {}", champ, avant, style(apres).yellow()));
+                }
+            }
+        }
+
+        if !self.fiches.is_empty() {
+            let _ = term.write_line(&format!("{}", style("Fiches modifiées").bold().cyan()));
+            for (enfant, changements) in self.fiches.iter() {
+                let _ = term.write_line(&format!("{}", style(enfant).bold()));
+                for (champ, avant, apres) in changements.iter() {
+                    let _ = term.write_line(&format!("\t{}: {} -> {}", champ, avant, style(apres).yellow()));
+                }
+            }
+        }
+
+        let (listes, fiches) = self.a_reimprimer();
+        let _ = term.write_line(&format!("{}", style("À réimprimer").bold().cyan()));
+        let _ = term.write_line(&format!("Listes de présences ({}):", listes.len()));
+        for l in listes {
+            let _ = term.write_line(&format!("\t{}", l));
+        }
+        let _ = term.write_line(&format!("Fiches santé ({}):", fiches.len()));
+        for f in fiches {
+            let _ = term.write_line(&format!("\t{}", f));
+        }
+    }
+}
+
+/// Même mandataire et au moins un enfant en commun (nom, prénom et date de naissance).
+fn meme_famille(c: &Compte, donnees_c: &Donnees, n: &Compte, donnees_n: &Donnees) -> bool {
+    if !c.meme_mandataire(n) {
+        return false;
+    }
+    let enfants_n: Vec<&Membre> = n.membres.iter().filter_map(|mid| donnees_n.membres.get(*mid).ok()).collect();
+    c.membres.iter().filter_map(|mid| donnees_c.membres.get(*mid).ok()).any(|m| enfants_n.iter().any(|e| e.meme_enfant(m)))
+}
+
+fn nom(m: &Membre) -> String {
+    format!("{} {}", m.prenom, m.nom)
+}
+
+fn texte<T: ToString>(o: &O<T>) -> String {
+    o.as_ref().map(|t| t.to_string()).unwrap_or("-".into())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use crate::{data::tel::Tel, groupes::groupes::Groupe, prelude::Date};
+    use super::*;
+
+    struct Export {
+        groupes: GroupeReg,
+        comptes: CompteReg,
+        membres: MembreReg,
+    }
+    impl Export {
+        fn donnees(&self) -> Donnees<'_> {
+            Donnees::new(&self.groupes, &self.comptes, &self.membres)
+        }
+    }
+
+    /// Les enfants Léa (1) et Noé (2) d'un même compte. `decalage` change les identifiants, comme deux exports
+    /// différents; chaque groupe (discriminant, participants) est dans la même semaine et le même site.
+    fn export(decalage: u32, tel: &str, groupes: &[(&str, &[u32])]) -> Export {
+        let mut compte = Compte::new(CompteID(decalage + 50), "Julie Tremblay".into());
+        compte.tel = Some(Tel::from_str(tel).unwrap());
+        let mut membres = MembreReg::default();
+        for (i, prenom) in [(1, "Léa"), (2, "Noé")] {
+            let mut m = Membre::new(MembreID(decalage + i), "Tremblay".into(), prenom.into(), Date::from_ymd_opt(2016, 1, i).unwrap());
+            m.compte = Some(compte.id);
+            compte.membres.insert(m.id);
+            membres.add(m).unwrap();
+        }
+        let mut comptes = CompteReg::default();
+        comptes.add(compte).unwrap();
+        let mut reg = GroupeReg::default();
+        for (n, (disc, participants)) in groupes.iter().enumerate() {
+            reg.add(Groupe {
+                id: GroupeID(decalage + 10 + n as u32),
+                saison: Some("Été 2025".into()),
+                site: Some("Parc Lafontaine".into()),
+                category: Some("Crocus".into()),
+                discriminant: Some(disc.to_string()),
+                semaine: Some("1".into()),
+                participants: participants.iter().map(|i| MembreID(decalage + i)).collect(),
+                ..Groupe::default()
+            }).unwrap();
+        }
+        Export { groupes: reg, comptes, membres }
+    }
+
+    fn groupe<'a>(delta: &'a Delta, disc: &str) -> &'a DeltaGroupe {
+        delta.groupes.iter().find(|g| g.desc.ends_with(&format!("- {}", disc))).unwrap()
+    }
+
+    #[test]
+    fn enfant_change_de_groupe_arrive_et_part() {
+        let ancien = export(0, "514-555-1234", &[("A", &[1, 2]), ("B", &[])]);
+        let nouveau = export(100, "514-555-1234", &[("A", &[2]), ("B", &[1])]);
+        let delta = Delta::calculer(ancien.donnees(), nouveau.donnees());
+
+        let a = groupe(&delta, "A");
+        let b = groupe(&delta, "B");
+        assert!(a.ajoutes.is_empty() && a.retires.is_empty() && b.ajoutes.is_empty() && b.retires.is_empty());
+        assert_eq!(a.partis, vec![("Léa Tremblay".to_string(), vec![b.desc.clone()])]);
+        assert_eq!(b.arrives, vec![("Léa Tremblay".to_string(), vec![a.desc.clone()])]);
+        assert!(delta.comptes.is_empty() && delta.fiches.is_empty());
+    }
+
+    #[test]
+    fn telephone_modifie_garde_la_famille() {
+        let ancien = export(0, "514-555-1234", &[("A", &[1, 2])]);
+        let nouveau = export(100, "438-555-9876", &[("A", &[1, 2])]);
+        let delta = Delta::calculer(ancien.donnees(), nouveau.donnees());
+
+        assert_eq!(delta.comptes.len(), 1);
+        let compte = &delta.comptes[0];
+        assert_eq!(compte.changements.len(), 1);
+        assert_eq!(compte.changements[0].0, "Téléphone");
+        assert_eq!(compte.enfants, vec!["Léa Tremblay".to_string(), "Noé Tremblay".to_string()]);
+        // les enfants sont reconnus: aucun changement d'inscription
+        assert!(delta.groupes.is_empty());
+        assert!(delta.fiches.is_empty());
+    }
+
+    #[test]
+    fn groupe_disparu_retire_ses_enfants() {
+        let ancien = export(0, "514-555-1234", &[("A", &[1]), ("B", &[2])]);
+        let nouveau = export(100, "514-555-1234", &[("A", &[1])]);
+        let delta = Delta::calculer(ancien.donnees(), nouveau.donnees());
+
+        assert_eq!(delta.groupes.len(), 1);
+        let b = groupe(&delta, "B");
+        assert!(b.disparu && !b.nouveau);
+        assert_eq!(b.retires, vec!["Noé Tremblay".to_string()]);
+        let (listes, _) = delta.a_reimprimer();
+        assert!(listes.is_empty());
+    }
+}
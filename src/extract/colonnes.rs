@@ -0,0 +1,126 @@
+use std::collections::HashMap;
+
+use console::{style, Term};
+use serde::{Deserialize, Serialize};
+
+use crate::{config::ConfigError, prelude::normaliser};
+
+/// Entêtes reconnues par défaut pour chaque champ des listes de présences (modèle Qidigo "2025 - Complet").
+pub static COLONNES_DEFAUT: &[(&str, &[&str])] = &[
+    ("nom", &["Nom"]),
+    ("prenom", &["Prénom"]),
+    ("genre", &["Genre"]),
+    ("naissance", &["Date de naissance"]),
+    ("mandataire", &["Mandataire du compte"]),
+    ("courriel", &["Courriel"]),
+    ("tel", &["Principal", "Numéro de téléphone principal"]),
+    ("adresse", &["Adresse princ.", "Adresse principale"]),
+    ("accompagnement", &["Accompagnement"]),
+    ("cam", &["assurance maladie"]),
+    ("auth_soins", &["Autorisation de soigner"]),
+    ("prob_comportement", &["Problèmes de comportement?"]),
+    ("prise_med", &["Prise de Médicament"]),
+    ("med_acetaminophene", &["Med Acétaminophène"]),
+    ("med_antibio", &["Med Antibiotique"]),
+    ("med_antiinfl", &["Med Anti-Inflamatoires"]),
+    ("med_ibu", &["Med Ibuprophène"]),
+    ("med_sirop_toux", &["Med Sirop Toux"]),
+    ("med_antieme", &["Med Antiémétique"]),
+    ("mal_asthme", &["MC Asthme"]),
+    ("mal_diabete", &["MC Diabète"]),
+    ("mal_emo", &["MC Émophilie"]),
+    ("mal_epi", &["MC Épilépsie"]),
+    ("mal_autre", &["MC Autre"]),
+    ("all_alim", &["Allergie Alimentaire"]),
+    ("all_anim", &["Allergie Animaux"]),
+    ("all_insecte", &["Allergie Insecte"]),
+    ("all_peni", &["Allergie Pénicilline"]),
+    ("all_autre", &["Allergie Autre"]),
+    ("contact_1_nom", &["contact d'urgence - Nom"]),
+    ("contact_1_tel", &["contact d'urgence - Téléphone"]),
+    ("contact_1_lien", &["contact d'urgence - Lien de parenté"]),
+    ("contact_2_nom", &["Contact Urgence 2 - Nom"]),
+    ("contact_2_tel", &["Contact Urgence 2 - Téléphone"]),
+    ("contact_2_lien", &["Contact Urgence 2 - Lien de parenté"]),
+    ("quit_parent", &["Quitte Parent"]),
+    ("quit_seul", &["Quitte Seul"]),
+    ("quit_acceuil", &["Quitte Service d'accueil"]),
+    ("quit_autre", &["Quitte Autre"]),
+    ("mdp", &["Mdp"]),
+    ("auth_partage_sauveteur", &["Autorisation Partage aux Sauveteurs"]),
+    ("vfi", &["VFI"]),
+    ("tete_sous_eau", &["Tête Sous l'Eau"]),
+    ("taille", &["Taille"]),
+    ("interet_1", &["Interet_1"]),
+    ("interet_2", &["Interet_2"]),
+    ("interet_3", &["Interet_3"]),
+    ("interet_4", &["Interet_4"]),
+    ("auth_photo", &["Autorisation Photo"]),
+    ("commentaire", &["Commentaires"]),
+];
+
+/// Entêtes supplémentaires acceptées pour chaque champ, lues d'un fichier de correspondance.
+/// Les entêtes du fichier sont essayées avant celles par défaut.
+///
+/// ```toml
+/// med_ibu = ["Med Ibuprofène", "Ibuprofène"]
+/// contact_1_nom = ["Contact d'urgence 1 - Nom"]
+/// ```
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct AliasColonnes(HashMap<String, Vec<String>>);
+impl AliasColonnes {
+    pub fn charger(filepath: &str) -> Result<Self, ConfigError> {
+        let contenu = std::fs::read_to_string(filepath).map_err(ConfigError::Lecture)?;
+        let alias: AliasColonnes = toml::from_str(&contenu).map_err(|e| ConfigError::Format(e.to_string()))?;
+        // refuser les champs inconnus, pour ne pas ignorer silencieusement une faute de frappe
+        for champ in alias.0.keys() {
+            if !COLONNES_DEFAUT.iter().any(|(c, _)| c == champ) {
+                return Err(ConfigError::Format(format!("Champ de colonne inconnu '{}' dans {}", champ, filepath)));
+            }
+        }
+        Ok(alias)
+    }
+
+    /// Toutes les entêtes acceptées pour un champ, dans l'ordre où elles doivent être essayées.
+    pub fn entetes(&self, champ: &str) -> Vec<&str> {
+        let mut entetes: Vec<&str> = self.0.get(champ).map(|v| v.iter().map(String::as_str).collect()).unwrap_or_default();
+        if let Some((_, defaut)) = COLONNES_DEFAUT.iter().find(|(c, _)| *c == champ) {
+            entetes.extend(defaut.iter());
+        }
+        entetes
+    }
+}
+
+/// Forme d'une entête utilisée pour la comparaison: sans accents, en minuscules et avec un seul espace entre les mots.
+pub fn normaliser_entete(entete: &str) -> String {
+    normaliser(&entete.split_whitespace().collect::<Vec<&str>>().join(" "))
+}
+
+/// Résultat de la recherche des colonnes dans la ligne d'entêtes d'une feuille.
+#[derive(Debug, Clone, Default)]
+pub struct RapportColonnes {
+    /// (champ, entête trouvée, numéro de colonne)
+    pub trouves: Vec<(&'static str, String, usize)>,
+    pub manquants: Vec<&'static str>,
+    /// (entête, numéro de colonne)
+    pub inutilisees: Vec<(String, usize)>,
+}
+impl RapportColonnes {
+    pub fn afficher(&self, out_term: &Term, err_term: &Term, verbose: bool) {
+        let total = self.trouves.len() + self.manquants.len();
+        let _ = out_term.write_line(&format!("Colonnes trouvées: {}/{}", self.trouves.len(), total));
+        if verbose {
+            for (champ, entete, col) in self.trouves.iter() {
+                let _ = out_term.write_line(&format!("\t{} <- \"{}\" (colonne {})", champ, entete, col + 1));
+            }
+        }
+        if !self.manquants.is_empty() {
+            let _ = err_term.write_line(&format!("{}", style(format!("Champs sans colonne: {}", self.manquants.join(", "))).yellow()));
+        }
+        if !self.inutilisees.is_empty() {
+            let cols = self.inutilisees.iter().map(|(e, c)| format!("\"{}\" ({})", e, c + 1)).collect::<Vec<String>>().join(", ");
+            let _ = err_term.write_line(&format!("{}", style(format!("Colonnes non utilisées: {}", cols)).yellow()));
+        }
+    }
+}
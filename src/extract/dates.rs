@@ -0,0 +1,152 @@
+use lazy_static::lazy_static;
+use regex::Regex;
+
+use crate::prelude::{normaliser, Date, O, NOMS_MOIS};
+use super::source::DataType;
+
+lazy_static! {
+    static ref DATE_ISO_RE: Regex = Regex::new(r"(?P<an>\d{4})[-/.](?P<mois>\d{1,2})[-/.](?P<jour>\d{1,2})").unwrap();
+    static ref DATE_JMA_RE: Regex = Regex::new(r"(?P<jour>\d{1,2})[-/.](?P<mois>\d{1,2})[-/.](?P<an>\d{4})").unwrap();
+    static ref DATE_TEXTE_RE: Regex = Regex::new(r"(?i)(?P<jour>\d{1,2})\s*(?:er)?\s+(?P<mois>\p{L}+)\.?\s+(?P<an>\d{4})").unwrap();
+    static ref PARENTHESES_RE: Regex = Regex::new(r"\([^)]*\)").unwrap();
+}
+
+/// Plus grand numéro de série accepté par Excel (31 décembre 9999).
+const SERIE_EXCEL_MAX: f64 = 2_958_465.0;
+
+/// Lit une date dans une cellule: numéro de série d'Excel ou texte (voir [`lire_date_texte`]).
+pub fn into_date(data: &DataType) -> O<Date> {
+    match data {
+        DataType::Int(n) => depuis_serie_excel(*n as f64),
+        DataType::Float(f) => depuis_serie_excel(*f),
+        DataType::String(s) => lire_date_texte(s),
+        DataType::Bool(_) | DataType::Error(_) | DataType::Empty => None,
+    }
+}
+
+/// Lit une date écrite à la main ou exportée en texte:
+/// `2019-03-14`, `2019/03/14`, `14/03/2019`, `14-03-2019`, `14 mars 2019`, `1er juil. 2025`, `lundi 30 juin 2025`.
+/// Ce qui est entre parenthèses est ignoré, comme l'âge dans `2019-03-14 (6 ans)`.
+/// Un nombre seul est lu comme un numéro de série d'Excel (fichiers csv).
+pub fn lire_date_texte(s: &str) -> O<Date> {
+    let s = PARENTHESES_RE.replace_all(s, " ");
+    let s = s.trim();
+    if s.is_empty() {
+        return None;
+    }
+    if let Ok(n) = s.parse::<f64>() {
+        // une année seule (2019) n'est pas une date
+        return if n >= 10_000.0 {depuis_serie_excel(n)} else {None};
+    }
+    if let Some(cap) = DATE_ISO_RE.captures(s).or_else(|| DATE_JMA_RE.captures(s)) {
+        let an = cap.name("an").unwrap().as_str().parse().ok()?;
+        let mois = cap.name("mois").unwrap().as_str().parse().ok()?;
+        let jour = cap.name("jour").unwrap().as_str().parse().ok()?;
+        return Date::from_ymd_opt(an, mois, jour);
+    }
+    let cap = DATE_TEXTE_RE.captures(s)?;
+    let mois = numero_mois(cap.name("mois").unwrap().as_str())?;
+    let an = cap.name("an").unwrap().as_str().parse().ok()?;
+    let jour = cap.name("jour").unwrap().as_str().parse().ok()?;
+    Date::from_ymd_opt(an, mois, jour)
+}
+
+/// Numéro (1 à 12) d'un mois de [`NOMS_MOIS`], écrit au long ou abrégé (`janv`, `fév`, `sept`):
+/// au moins trois lettres, qui ne commencent le nom que d'un seul mois (`jui` est refusé).
+fn numero_mois(nom: &str) -> O<u32> {
+    let nom = normaliser(nom);
+    if nom.chars().count() < 3 {
+        return None;
+    }
+    let mut trouves = NOMS_MOIS.iter().enumerate().filter(|(_, m)| normaliser(m).starts_with(&nom));
+    match (trouves.next(), trouves.next()) {
+        (Some((i, _)), None) => Some(i as u32 + 1),
+        _ => None,
+    }
+}
+
+/// Excel compte 1 pour le 1er janvier 1900 mais compte aussi un faux 29 février 1900 (60):
+/// les jours depuis le 30 décembre 1899 à partir du 1er mars 1900 (61), depuis le 31 décembre avant.
+fn depuis_serie_excel(n: f64) -> O<Date> {
+    if !(1.0..=SERIE_EXCEL_MAX).contains(&n) {
+        return None;
+    }
+    let jours = n.floor() as u64;
+    let origine = match jours {
+        ..=59 => Date::from_ymd_opt(1899, 12, 31)?,
+        60 => return None,
+        _ => Date::from_ymd_opt(1899, 12, 30)?,
+    };
+    origine.checked_add_days(chrono::Days::new(jours))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(an: i32, mois: u32, jour: u32) -> O<Date> {
+        Date::from_ymd_opt(an, mois, jour)
+    }
+
+    #[test]
+    fn dates_chiffrees() {
+        assert_eq!(lire_date_texte("2019-03-14"), date(2019, 3, 14));
+        assert_eq!(lire_date_texte("2019/03/14"), date(2019, 3, 14));
+        assert_eq!(lire_date_texte("14/03/2019"), date(2019, 3, 14));
+        assert_eq!(lire_date_texte("14-3-2019"), date(2019, 3, 14));
+        assert_eq!(lire_date_texte("31/02/2019"), None);
+    }
+
+    #[test]
+    fn dates_en_lettres() {
+        assert_eq!(lire_date_texte("14 mars 2019"), date(2019, 3, 14));
+        assert_eq!(lire_date_texte("1er juil. 2025"), date(2025, 7, 1));
+        assert_eq!(lire_date_texte("lundi 30 juin 2025"), date(2025, 6, 30));
+        assert_eq!(lire_date_texte("3 Févr. 2020"), date(2020, 2, 3));
+        assert_eq!(lire_date_texte("15 août 2018"), date(2018, 8, 15));
+        assert_eq!(lire_date_texte("2 mar 2021"), date(2021, 3, 2));
+        assert_eq!(lire_date_texte("2 mai 2021"), date(2021, 5, 2));
+        // abréviation ambiguë (juin ou juillet) ou inconnue
+        assert_eq!(lire_date_texte("2 jui 2021"), None);
+        assert_eq!(lire_date_texte("2 ma 2021"), None);
+        assert_eq!(lire_date_texte("2 brumaire 2021"), None);
+    }
+
+    #[test]
+    fn age_entre_parentheses_ignore() {
+        assert_eq!(lire_date_texte("2019-03-14 (6 ans)"), date(2019, 3, 14));
+        assert_eq!(lire_date_texte("14 mars 2019 (10 ans)"), date(2019, 3, 14));
+        assert_eq!(lire_date_texte("(6 ans)"), None);
+    }
+
+    #[test]
+    fn nombres() {
+        assert_eq!(lire_date_texte(""), None);
+        assert_eq!(lire_date_texte("2019"), None);
+        assert_eq!(lire_date_texte("43538"), date(2019, 3, 14));
+        assert_eq!(lire_date_texte("43538.5"), date(2019, 3, 14));
+    }
+
+    #[test]
+    fn series_excel() {
+        assert_eq!(depuis_serie_excel(1.0), date(1900, 1, 1));
+        assert_eq!(depuis_serie_excel(59.0), date(1900, 2, 28));
+        // faux 29 février 1900
+        assert_eq!(depuis_serie_excel(60.0), None);
+        assert_eq!(depuis_serie_excel(61.0), date(1900, 3, 1));
+        assert_eq!(depuis_serie_excel(43538.0), date(2019, 3, 14));
+        assert_eq!(depuis_serie_excel(45292.75), date(2024, 1, 1));
+        assert_eq!(depuis_serie_excel(SERIE_EXCEL_MAX), date(9999, 12, 31));
+        assert_eq!(depuis_serie_excel(0.0), None);
+        assert_eq!(depuis_serie_excel(SERIE_EXCEL_MAX + 1.0), None);
+    }
+
+    #[test]
+    fn cellules() {
+        assert_eq!(into_date(&DataType::Int(43538)), date(2019, 3, 14));
+        assert_eq!(into_date(&DataType::Float(43538.25)), date(2019, 3, 14));
+        assert_eq!(into_date(&DataType::String("14/03/2019".into())), date(2019, 3, 14));
+        assert_eq!(into_date(&DataType::Empty), None);
+        assert_eq!(into_date(&DataType::Bool(true)), None);
+    }
+}
@@ -0,0 +1,136 @@
+use std::fmt::Display;
+
+use console::{style, Term};
+
+use crate::prelude::*;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Gravite {
+    /// La ligne (ou la feuille) n'a pas pu être lue
+    Erreur,
+    /// La ligne a été lue, mais une valeur a été ignorée
+    Avertissement,
+}
+impl Display for Gravite {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Erreur => write!(f, "Erreur"),
+            Self::Avertissement => write!(f, "Avertissement"),
+        }
+    }
+}
+
+/// Endroit du fichier en cours de lecture.
+#[derive(Debug, Clone, Copy)]
+pub struct Position<'a> {
+    pub fichier: &'a str,
+    pub feuille: &'a str,
+    /// Numéro de ligne tel qu'affiché dans Excel (à partir de 1)
+    pub ligne: O<usize>,
+}
+
+/// Un problème rencontré lors de la lecture d'un fichier de Qidigo.
+#[derive(Debug, Clone)]
+pub struct Diagnostic {
+    pub fichier: String,
+    pub feuille: String,
+    pub ligne: O<usize>,
+    /// Entête de la colonne fautive
+    pub colonne: O<String>,
+    /// Valeur brute de la cellule
+    pub valeur: O<String>,
+    pub gravite: Gravite,
+    pub message: String,
+}
+impl Display for Diagnostic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "[{}]", self.feuille)?;
+        if let Some(ligne) = self.ligne {
+            write!(f, " ligne {}", ligne)?;
+        }
+        if let Some(colonne) = &self.colonne {
+            write!(f, ", colonne \"{}\"", colonne)?;
+        }
+        write!(f, ": {}", self.message)?;
+        if let Some(valeur) = &self.valeur {
+            write!(f, " (valeur: \"{}\")", valeur.replace('\n', " "))?;
+        }
+        Ok(())
+    }
+}
+
+/// Tous les problèmes d'une importation, à corriger dans Qidigo.
+#[derive(Debug, Clone, Default)]
+pub struct RapportImport {
+    diagnostics: Vec<Diagnostic>,
+}
+impl RapportImport {
+    pub fn signaler(&mut self, pos: &Position, gravite: Gravite, colonne: O<&str>, valeur: O<&str>, message: impl Into<String>) {
+        self.diagnostics.push(Diagnostic {
+            fichier: pos.fichier.into(),
+            feuille: pos.feuille.into(),
+            ligne: pos.ligne,
+            colonne: colonne.map(String::from),
+            valeur: valeur.map(String::from),
+            gravite,
+            message: message.into(),
+        });
+    }
+
+    pub fn diagnostics(&self) -> impl Iterator<Item = &Diagnostic> {
+        self.diagnostics.iter()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.diagnostics.is_empty()
+    }
+
+    pub fn nb(&self, gravite: Gravite) -> usize {
+        self.diagnostics.iter().filter(|d| d.gravite == gravite).count()
+    }
+
+    pub fn fusionner(&mut self, autre: RapportImport) {
+        self.diagnostics.extend(autre.diagnostics);
+    }
+
+    pub fn afficher(&self, term: &Term) {
+        if self.is_empty() {
+            let _ = term.write_line(&format!("{}", style("Aucun problème d'importation").green()));
+            return;
+        }
+        let _ = term.write_line(&format!("{}", style(format!("Rapport d'importation: {} erreur(s), {} avertissement(s)",
+            self.nb(Gravite::Erreur),
+            self.nb(Gravite::Avertissement),
+        )).bold().cyan()));
+        let mut fichier = None;
+        for d in self.diagnostics.iter() {
+            if fichier != Some(&d.fichier) {
+                let _ = term.write_line(&format!("{}", style(&d.fichier).bold()));
+                fichier = Some(&d.fichier);
+            }
+            let ligne = match d.gravite {
+                Gravite::Erreur => style(d.to_string()).red(),
+                Gravite::Avertissement => style(d.to_string()).yellow(),
+            };
+            let _ = term.write_line(&format!("\t{}", ligne));
+        }
+    }
+
+    pub fn exporter_csv(&self, filepath: &str) -> Result<(), csv::Error> {
+        let mut wtr = csv::WriterBuilder::new().delimiter(b';').from_path(filepath)?;
+        wtr.write_record(["Gravité", "Fichier", "Feuille", "Ligne", "Colonne", "Valeur", "Problème"])?;
+        for d in self.diagnostics.iter() {
+            wtr.write_record([
+                d.gravite.to_string(),
+                d.fichier.clone(),
+                d.feuille.clone(),
+                d.ligne.map(|l| l.to_string()).unwrap_or_default(),
+                d.colonne.clone().unwrap_or_default(),
+                d.valeur.clone().unwrap_or_default(),
+                d.message.clone(),
+            ])?;
+        }
+        wtr.flush()?;
+        Ok(())
+    }
+}
@@ -1,30 +1,66 @@
-use std::str::FromStr;
+use std::{collections::{HashMap, HashSet}, str::FromStr};
 
 use console::{style, Term};
-use office::{DataType, Excel, Range};
 
-use crate::{data::{adresse::Adresse, cam::CAM, email::Email, tel::Tel, BoolJustifie, Genre, Taille}, groupes::{comptes::{Compte, CompteID, CompteReg}, fiche_sante::{ALL_ALIMENTAIRE, ALL_ANIMAUX, ALL_INSECTES, ALL_PENICILINE, MAL_ASTHME, MAL_DIABETE, MAL_EMOPHILIE, MAL_EPILEPSIE}, groupes::{Groupe, GroupeID, GroupeReg}, membres::{Contact, Interet, Membre, MembreID, MembreReg}}, prelude::{print_option, Date, O}};
+use crate::{data::{adresse::Adresse, cam::CAM, email::Email, tel::Tel, BoolJustifie, Genre, Taille}, groupes::{comptes::{Compte, CompteID, CompteReg}, fiche_sante::{ALL_ALIMENTAIRE, ALL_ANIMAUX, ALL_INSECTES, ALL_PENICILINE, MAL_ASTHME, MAL_DIABETE, MAL_EMOPHILIE, MAL_EPILEPSIE}, groupes::{Groupe, GroupeID, GroupeReg, TrancheAge}, membres::{ChampsLus, Contact, Interet, Membre, MembreID, MembreReg}}, prelude::O};
 use crate::config::Config;
 
-use super::{ExtractError, BOOL_W_COMMENT_DATA_RE, DATE_NAISSANCE_RE, FALSE_DATA_RE, GROUPE_PROG_RE, GROUPE_RE, TRUE_DATA_RE};
+use super::{colonnes::{normaliser_entete, AliasColonnes, RapportColonnes}, dates::into_date, diagnostics::{Gravite, Position, RapportImport}, source::{self, DataType, Range}, ExtractError, BOOL_W_COMMENT_DATA_RE, FALSE_DATA_RE, GROUPE_PROG_RE, TRUE_DATA_RE, remplir_nom_groupe};
 
-pub fn fill_regs(comptes: &mut CompteReg, membres: &mut MembreReg, groupes: &mut GroupeReg, config: &Config, filepath: &str, out_term: &Term, err_term: &Term) -> Result<(), ExtractError>{
-    let mut wb = match Excel::open(filepath) {
-        Ok(wb) => wb,
-        Err(_) => return Err(ExtractError::CouldNotReadFile),
-    };
+/// Ce qu'un fichier de listes de présences a apporté aux registres.
+#[derive(Debug, Clone, Default)]
+pub struct Lecture {
+    /// Problèmes de lecture, à corriger dans Qidigo
+    pub rapport: RapportImport,
+    /// Participants lus pour chaque groupe des feuilles de présences (sans les groupes vides)
+    pub groupes: HashMap<GroupeID, HashSet<MembreID>>,
+    /// Nombre de membres ajoutés
+    pub nouveaux: usize,
+    /// Nombre de membres existants dont au moins un champ a changé
+    pub mis_a_jour: usize,
+}
+
+/// Lit un fichier de listes de présences. Les problèmes de lecture sont retournés dans le rapport d'importation.
+pub fn fill_regs(comptes: &mut CompteReg, membres: &mut MembreReg, groupes: &mut GroupeReg, config: &Config, filepath: &str, out_term: &Term, err_term: &Term) -> Result<Lecture, ExtractError>{
+    let mut wb = source::ouvrir(filepath)?;
     let _ = out_term.write_line(&format!("Lecture de \"{}\"", style(filepath).green()));
-    let sheets = wb.sheet_names().unwrap();
-    let mut dc = None;
-    for sheet in sheets.into_iter().filter(|s| s.to_lowercase() != "groupes vides") {
-        let rng = wb.worksheet_range(&sheet).unwrap();
-        let g = extract_group_info(&rng);
+    let sheets = wb.feuilles()?;
+    let mut colonnes_affichees = false;
+    let mut lus = HashSet::new();
+    let mut lecture = Lecture::default();
+    let rapport = &mut lecture.rapport;
+    let mut saisons = HashSet::new();
+    // les groupes vides sont lus à la fin, pour leur donner la saison des autres feuilles
+    let (feuilles_vides, sheets): (Vec<String>, Vec<String>) = sheets.into_iter().partition(|s| normaliser_entete(s) == "groupes vides");
+    for sheet in sheets {
+        let rng = match wb.feuille(&sheet) {
+            Ok(rng) => rng,
+            Err(e) => {
+                let pos = Position { fichier: filepath, feuille: &sheet, ligne: None };
+                rapport.signaler(&pos, Gravite::Erreur, None, None, format!("Page ignorée: {}", e));
+                continue;
+            },
+        };
+        // 0. Trouver la ligne d'entêtes; les informations du groupe sont au-dessus
+        let (ligne_entetes, dcc, rapport_colonnes) = match trouver_entetes(&rng, config) {
+            Ok(trouve) => trouve,
+            Err(message) => {
+                let pos = Position { fichier: filepath, feuille: &sheet, ligne: None };
+                rapport.signaler(&pos, Gravite::Erreur, None, None, format!("Feuille ignorée: {}", message));
+                continue;
+            },
+        };
+        let g = extract_group_info(&rng, ligne_entetes, config);
         //println!("{} = {}", g.id, g.desc());
 
         // 0. S'assurer qu'il n'y a pas eu d'erreur
         match g {
             Ok(mut grp) => {
-                let _ = out_term.write_line(&format!("LECTURE {desc}", desc=grp.desc()));
+                if config.verbose {
+                    let _ = out_term.write_line(&format!("LECTURE {desc}", desc=grp.desc()));
+                }
+
+                let tranche = TrancheAge::de(&grp);
 
                 // 1. Voir si le groupe existe déjà. Chq. groupe devrait avoir une description unique
                 let existing_grp = groupes.groupes().filter(|g| g.equiv(&grp)).map(|g| g.id).collect::<Vec<GroupeID>>();
@@ -33,35 +69,67 @@ pub fn fill_regs(comptes: &mut CompteReg, membres: &mut MembreReg, groupes: &mut
                     let id = groupes.get_new_id_from_seed(grp.id.0);
                     grp.id = id;
                     let _ = groupes.add(grp);
-                    let _ = out_term.write_line(&format!("Groupe ajouté sous l'ID {}", id));
+                    if config.verbose {
+                        let _ = out_term.write_line(&format!("Groupe ajouté sous l'ID {}", id));
+                    }
                     id
                 } else {
                     // 1.2 Si oui, prendre le premier groupe (devrait être le seul)
-                    let _ = out_term.write_line("Groupe déjà existant!!");
+                    if config.verbose {
+                        let _ = out_term.write_line("Groupe déjà existant!!");
+                    }
                     existing_grp[0]
                 };
                 let grp = groupes.get_mut(gid).unwrap();
+                grp.sources.presence = Some(tranche);
+                let lus_groupe = lecture.groupes.entry(gid).or_default();
+                if let Some(saison) = &grp.saison {
+                    saisons.insert(saison.clone());
+                }
 
-                // Construire la configuration des colones si ce n'est pas déjà fait
-                if dc.is_none() {
-                    dc = Some(DataColConfig::new(&rng, config.excel.data_ln));
-                    //println!("{:?}", dc.as_ref().unwrap());
+                // Afficher les colonnes trouvées pour la première feuille seulement
+                if !colonnes_affichees {
+                    rapport_colonnes.afficher(out_term, err_term, config.verbose);
+                    colonnes_affichees = true;
                 }
-                let dcc = dc.as_ref().unwrap();
+                let dcc = &dcc;
 
                 // 2. Boucler sur le reste des lignes pour rajouter les membres
-                let rows = rng.rows().skip(config.excel.ln_skip);
-                for ln in rows {
+                let rows = rng.rows().enumerate().skip(config.excel.ln_skip.unwrap_or(ligne_entetes + 1));
+                for (i, ln) in rows {
+                    if ln.iter().all(|cell| into_string(cell).is_none()) {
+                        continue;
+                    }
+                    let pos = Position { fichier: filepath, feuille: &sheet, ligne: Some(i + 1) };
+
                     // 2.1 Trouver le compte
-                    match extract_compte_info(ln, dcc) {
-                        Err(e) => {
-                            let _ = err_term.write_line(&format!("Erreur en lisant le compte '{}': {}", print_option(&into_string(&ln[0])), e));
-                        },
+                    let mut rapport_compte = RapportImport::default();
+                    match extract_compte_info(ln, dcc, &mut rapport_compte, &pos) {
+                        Err(_) => rapport.fusionner(rapport_compte),
                         Ok(mut c) => {
+                            let enfant = extract_membre_info(ln, dcc, rapport, &pos);
                             let cid = {
                                 let existing_compte = comptes.comptes().filter(|cc| cc.equiv(&c)).map(|c| c.id).collect::<Vec<CompteID>>();
+                                // un parent qui corrige ses coordonnées dans Qidigo garde son compte: même mandataire et un enfant en commun
+                                let famille = match (&enfant, existing_compte.is_empty()) {
+                                    (Ok(e), true) => comptes.comptes()
+                                        .filter(|cc| cc.meme_mandataire(&c))
+                                        .find(|cc| cc.membres.iter().filter_map(|mid| membres.get(*mid).ok()).any(|m| m.meme_enfant(e)))
+                                        .map(|cc| cc.id),
+                                    _ => None,
+                                };
                                 if !existing_compte.is_empty() {existing_compte[0]}
+                                else if let Some(id) = famille {
+                                    let existant = comptes.get_mut(id).unwrap();
+                                    let nb = existant.mettre_a_jour(&c, filepath);
+                                    if nb > 0 && config.verbose {
+                                        let _ = out_term.write_line(&format!("Compte de {}: {} coordonnée(s) mise(s) à jour", existant.mandataire, nb));
+                                    }
+                                    id
+                                }
                                 else {
+                                    // les problèmes d'un compte ne sont signalés qu'à sa première ligne
+                                    rapport.fusionner(rapport_compte);
                                     let id = comptes.get_new_id_from_seed(c.id.0);
                                     c.id = id;
                                     //let _ = out_term.write_line(&format!("{} = {} {} #{}", c.id, c.mandataire, print_option(&c.email), print_option(&c.tel)));
@@ -72,19 +140,34 @@ pub fn fill_regs(comptes: &mut CompteReg, membres: &mut MembreReg, groupes: &mut
                             };
                             let compte = comptes.get_mut(cid).unwrap();
 
-                            match extract_membre_info(ln, dcc) {
-                                Err(e) => {
-                                    let _ = err_term.write_line(&format!("Erreur en lisant le membre '{}': {}", print_option(&into_string(&ln[0])), e));
-                                },
+                            match enfant {
+                                Err(_) => {},
                                 Ok(mut mbr) => {
                                     mbr.compte = Some(cid);
                                     let mid = {
                                         let existing_membre = membres.membres().filter(|m| m.equiv(&mbr)).map(|m| m.id).collect::<Vec<MembreID>>();
-                                        if !existing_membre.is_empty() {existing_membre[0]}
+                                        if !existing_membre.is_empty() {
+                                            let id = existing_membre[0];
+                                            // un enfant inscrit à plusieurs groupes apparaît sur plusieurs pages du même fichier
+                                            if lus.insert(id) {
+                                                fill_membre_info(ln, dcc, &mut mbr, rapport, &pos);
+                                                let existant = membres.get_mut(id).unwrap();
+                                                let nb = existant.mettre_a_jour(mbr, filepath, &dcc.champs_lus(ln));
+                                                if nb > 0 {
+                                                    lecture.mis_a_jour += 1;
+                                                    if config.verbose {
+                                                        let _ = out_term.write_line(&format!("{} {}: {} champ(s) mis à jour", existant.prenom, existant.nom, nb));
+                                                    }
+                                                }
+                                            }
+                                            id
+                                        }
                                         else {
                                             let id = membres.get_new_id_from_seed(mbr.id.0);
                                             mbr.id = id;
-                                            fill_membre_info(ln, dcc, &mut mbr, err_term);
+                                            fill_membre_info(ln, dcc, &mut mbr, rapport, &pos);
+                                            lus.insert(id);
+                                            lecture.nouveaux += 1;
                                             //println!("{:?}", mbr);
                                             let _ = membres.add(mbr);
                                             id
@@ -97,117 +180,215 @@ pub fn fill_regs(comptes: &mut CompteReg, membres: &mut MembreReg, groupes: &mut
 
                                     // ajouter au groupe
                                     grp.add_participant(mid);
+                                    grp.vide = false;
+                                    lus_groupe.insert(mid);
                                 }
                             }
                         },
                     }
                 }
             },
+            Err(ExtractError::InvalidGroupNameFormat) => {
+                let pos = Position { fichier: filepath, feuille: &sheet, ligne: None };
+                match titre_feuille(&rng, ligne_entetes) {
+                    Some((ligne, titre)) => {
+                        let pos = Position { ligne: Some(ligne + 1), ..pos };
+                        rapport.signaler(&pos, Gravite::Erreur, None, Some(&titre), "Feuille ignorée: nom de groupe non reconnu \
+                            (format \"Activité | Site | Catégorie | Sem. N\" ou règle de noms_groupes)");
+                    },
+                    None => {
+                        let message = format!("Feuille ignorée: aucun nom de groupe au-dessus des entêtes (ligne {})", ligne_entetes + 1);
+                        rapport.signaler(&pos, Gravite::Erreur, None, None, message);
+                    },
+                }
+            },
             Err(e) => {
-                let _ = err_term.write_line(&format!("Erreur en lisant la page '{}': {}", sheet, e));
+                let pos = Position { fichier: filepath, feuille: &sheet, ligne: None };
+                rapport.signaler(&pos, Gravite::Erreur, None, None, format!("Page ignorée: {}", e));
+            },
+        }
+    }
+
+    // 3. Ajouter les groupes qui n'ont encore aucune inscription
+    let saison = if saisons.len() == 1 {saisons.into_iter().next()} else {None};
+    for sheet in feuilles_vides {
+        let pos = Position { fichier: filepath, feuille: &sheet, ligne: None };
+        match wb.feuille(&sheet) {
+            Ok(rng) => {
+                let nb = lire_groupes_vides(&rng, groupes, config, saison.as_deref(), rapport, &pos);
+                if config.verbose {
+                    let _ = out_term.write_line(&format!("{} groupe(s) vide(s)", nb));
+                }
             },
+            Err(e) => rapport.signaler(&pos, Gravite::Erreur, None, None, format!("Page ignorée: {}", e)),
         }
     }
 
-    Ok(())
+    // les dates viennent de la programmation, si elle a été lue avant
+    groupes.completer_dates();
+
+    if lecture.mis_a_jour > 0 {
+        let _ = out_term.write_line(&format!("{}", style(format!("{} membre(s) existant(s) mis à jour", lecture.mis_a_jour)).yellow()));
+    }
+    Ok(lecture)
 }
 
-fn extract_group_info(ws: &Range) -> Result<Groupe, ExtractError> {
-    let mut g = Groupe::default();
-    let grp_desc = into_string(ws.get_value(0, 0));
-    if grp_desc.is_none() {return Err(ExtractError::InvalidFormat);}
-    let grp_desc = grp_desc.unwrap();
-    if let Some(cap) = GROUPE_RE.captures(&grp_desc) {
-        g.activite = Some(cap.name("activite").unwrap().as_str().into());
-        g.site = Some(cap.name("site").unwrap().as_str().into());
-        g.semaine = Some(cap.name("semaine").unwrap().as_str().into());
-        match (cap.name("grage_min"), cap.name("grage_max"), cap.name("crocus"), cap.name("balaous"), cap.name("basaltes")) {
-            (Some(mn), Some(mx), _, _, _) => { // tranche d'âge
-                let grage_min = mn.as_str().parse().unwrap();
-                let grage_max = mx.as_str().parse().unwrap();
-                g.age_min = Some(grage_min);
-                g.age_max = Some(grage_max);
-                g.category = Some(Groupe::guess_category(g.age_min, g.age_max));
-            },
-            (_, _, Some(_), _, _) => { // Crocus
-                g.age_min = Some(5);
-                g.age_max = Some(6);
-                g.category = Some("Crocus".into());
-            },
-            (_, _, _, Some(_), _) => { // Balaous
-                g.age_min = Some(7);
-                g.age_max = Some(8);
-                g.category = Some("Balaous".into());
+/// Lit la feuille "groupes vides": un nom de groupe par ligne, pour les groupes qui n'ont encore aucune inscription.
+/// Une ligne "Programmation:" remplace la saison des autres feuilles. Retourne le nombre de groupes lus.
+fn lire_groupes_vides(ws: &Range, groupes: &mut GroupeReg, config: &Config, saison: O<&str>, rapport: &mut RapportImport, pos: &Position) -> usize {
+    let mut saison = saison.map(String::from);
+    let mut nb = 0;
+    for (i, ln) in ws.rows().enumerate() {
+        let titre = match ln.iter().find_map(into_string) {
+            Some(titre) => titre,
+            None => continue,
+        };
+        if let Some(cap) = GROUPE_PROG_RE.captures(&titre) {
+            saison = Some(cap.name("prog").unwrap().as_str().into());
+            continue;
+        }
+        let mut grp = Groupe::default();
+        if !remplir_nom_groupe(&mut grp, &titre, config) {
+            let pos = Position { ligne: Some(i + 1), ..*pos };
+            rapport.signaler(&pos, Gravite::Avertissement, None, Some(&titre), "Groupe vide ignoré: nom de groupe non reconnu");
+            continue;
+        }
+        grp.saison = saison.clone();
+        let existant = groupes.groupes().find(|g| g.equiv(&grp)).map(|g| g.id);
+        match existant {
+            // déjà connu (programmation ou autre fichier): il n'est vide que s'il n'a toujours personne
+            Some(gid) => {
+                let existant = groupes.get_mut(gid).unwrap();
+                existant.vide = existant.participants.is_empty();
+                existant.sources.presence = Some(TrancheAge::de(&grp));
             },
-            (_, _, _, _, Some(_)) => { // Basaltes
-                g.age_min = Some(9);
-                g.age_max = Some(12);
-                g.category = Some("Basaltes".into());
+            None => {
+                grp.vide = true;
+                grp.sources.presence = Some(TrancheAge::de(&grp));
+                grp.id = groupes.get_new_id_from_seed(grp.get_id_seed());
+                let _ = groupes.add(grp);
             },
-            _ => { // autre
-                g.category = Some(cap.name("category").unwrap().as_str().trim().into());
-            }
         }
+        nb += 1;
     }
-    let (h, _) = ws.get_size();
-    if h < 6 {
-        return Err(ExtractError::InvalidFormat);
+    nb
+}
+
+/// Première cellule non vide au-dessus des entêtes (hors "Programmation:"), qui devrait être le nom du groupe.
+fn titre_feuille(ws: &Range, ligne_entetes: usize) -> O<(usize, String)> {
+    let (_, w) = ws.get_size();
+    (0..ligne_entetes)
+        .flat_map(|i| (0..w).map(move |j| (i, j)))
+        .filter_map(|(i, j)| into_string(ws.get_value(i, j)).map(|s| (i, s)))
+        .find(|(_, s)| !GROUPE_PROG_RE.is_match(s))
+}
+
+/// Nombre de lignes du haut de la feuille où chercher la ligne d'entêtes.
+const LIGNES_ENTETES_MAX: usize = 30;
+
+/// Trouve la ligne d'entêtes d'une feuille: celle qui contient le plus de colonnes connues, parmi celles
+/// qui ont au moins le nom, le prénom et la date de naissance. `[excel] data_ln` impose la ligne.
+fn trouver_entetes(rng: &Range, config: &Config) -> Result<(usize, DataColConfig, RapportColonnes), String> {
+    let (h, _) = rng.get_size();
+    let candidates = match config.excel.data_ln {
+        Some(ln) if ln < h => ln..ln + 1,
+        Some(ln) => return Err(format!("la ligne d'entêtes {} imposée par la configuration est hors de la feuille", ln + 1)),
+        None => 0..h.min(LIGNES_ENTETES_MAX),
+    };
+    let mut meilleure: O<(usize, DataColConfig, RapportColonnes)> = None;
+    for ln in candidates {
+        let (dcc, rapport) = DataColConfig::new(rng, ln, &config.colonnes);
+        if dcc.nom.is_none() || dcc.prenom.is_none() || dcc.naissance.is_none() {
+            continue;
+        }
+        if meilleure.as_ref().is_none_or(|(_, _, r)| rapport.trouves.len() > r.trouves.len()) {
+            meilleure = Some((ln, dcc, rapport));
+        }
     }
-    let grp_prog = into_string(ws.get_value(2, 0));
+    meilleure.ok_or_else(|| "ligne d'entêtes introuvable, aucune ligne n'a les colonnes Nom, Prénom et Date de naissance".into())
+}
+
+/// Lit les informations du groupe dans les lignes au-dessus des entêtes: le nom du groupe
+/// (première cellule de la forme "Activité | Site | Catégorie | Sem. N"), le discriminant juste
+/// en dessous et la ligne "Programmation:", peu importe leur colonne.
+fn extract_group_info(ws: &Range, ligne_entetes: usize, config: &Config) -> Result<Groupe, ExtractError> {
+    let mut g = Groupe::default();
+    let (_, w) = ws.get_size();
+    let cellule = |i: usize, j: usize| into_string(ws.get_value(i, j));
+    let est_prog = |s: &str| GROUPE_PROG_RE.is_match(s);
+
+    let (ln_nom, col_nom) = (0..ligne_entetes)
+        .flat_map(|i| (0..w).map(move |j| (i, j)))
+        .find(|&(i, j)| cellule(i, j).is_some_and(|desc| !est_prog(&desc) && remplir_nom_groupe(&mut Groupe::default(), &desc, config)))
+        .ok_or(ExtractError::InvalidGroupNameFormat)?;
+    remplir_nom_groupe(&mut g, &cellule(ln_nom, col_nom).unwrap(), config);
+
+    let grp_prog = (0..ligne_entetes)
+        .flat_map(|i| (0..w).map(move |j| (i, j)))
+        .filter_map(|(i, j)| cellule(i, j))
+        .find(|s| est_prog(s));
     if let Some(grp_prog) = grp_prog {
         if let Some(cap) = GROUPE_PROG_RE.captures(&grp_prog) {
             g.saison = Some(cap.name("prog").unwrap().as_str().into());
         }
     }
-    g.discriminant = into_string(ws.get_value(1, 0));
+    if ln_nom + 1 < ligne_entetes {
+        g.discriminant = cellule(ln_nom + 1, col_nom).filter(|s| !est_prog(s));
+    }
     //g.animateur = into_string(ws.get_value(3, 0));
     g.id = GroupeID(g.get_id_seed());
     Ok(g)
 }
 
-fn extract_membre_info(ln: &[DataType], dcc: &DataColConfig) -> Result<Membre, ExtractError> {
+fn extract_membre_info(ln: &[DataType], dcc: &DataColConfig, rapport: &mut RapportImport, pos: &Position) -> Result<Membre, ExtractError> {
     let mut mbr = Membre::default();
+    let mut manquant = |col: O<usize>, champ: &'static str| {
+        let message = format!("Membre ignoré: {} manquant", champ.to_lowercase());
+        match col {
+            None => rapport.signaler(pos, Gravite::Erreur, None, None, message),
+            Some(col) => signaler(rapport, pos, dcc, ln, col, Gravite::Erreur, &message),
+        }
+        Err(ExtractError::MissingInformations(champ))
+    };
     let col_nom = match dcc.nom {
-        None => return Err(ExtractError::MissingInformations("Nom")),
+        None => return manquant(None, "Nom"),
         Some(n) => n,
     };
     let col_prenom = match dcc.prenom {
-        None => return Err(ExtractError::MissingInformations("Prénom")),
+        None => return manquant(None, "Prénom"),
         Some(n) => n,
     };
     let col_naissance = match dcc.naissance {
-        None => return Err(ExtractError::MissingInformations("Naissance")),
+        None => return manquant(None, "Naissance"),
         Some(n) => n,
     };
     mbr.nom = match into_string(&ln[col_nom]) {
-        None => return Err(ExtractError::MissingInformations("Nom")),
+        None => return manquant(Some(col_nom), "Nom"),
         Some(n) => n,
     };
     mbr.prenom = match into_string(&ln[col_prenom]) {
-        None => return Err(ExtractError::MissingInformations("Prénom")),
+        None => return manquant(Some(col_prenom), "Prénom"),
         Some(n) => n,
     };
-    mbr.naissance = match into_string(&ln[col_naissance]) {
-        None => return Err(ExtractError::MissingInformations("Naissance")),
-        Some(n) => {
-            if let Some(cap) = DATE_NAISSANCE_RE.captures(&n) {
-                let an = cap.name("an").unwrap().as_str().parse().unwrap();
-                let mois = cap.name("mois").unwrap().as_str().parse().unwrap();
-                let jour = cap.name("jour").unwrap().as_str().parse().unwrap();
-                match Date::from_ymd_opt(an, mois, jour) {
-                    None => return Err(ExtractError::MissingInformations("Naissance")),
-                    Some(d) => d,
-                }
-            }
-            else {
-                return Err(ExtractError::MissingInformations("Naissance"));
-            }
+    if into_string(&ln[col_naissance]).is_none() {
+        return manquant(Some(col_naissance), "Naissance");
+    }
+    mbr.naissance = match into_date(&ln[col_naissance]) {
+        Some(d) => d,
+        None => {
+            signaler(rapport, pos, dcc, ln, col_naissance, Gravite::Erreur, "Membre ignoré: date de naissance invalide");
+            return Err(ExtractError::InvalidFormat);
         },
     };
     mbr.id = MembreID(mbr.get_id_seed());
     Ok(mbr)
 }
-fn fill_membre_info(ln: &[DataType], dcc: &DataColConfig, membre: &mut Membre, err_term: &Term) {
+
+/// Ajoute au rapport un problème sur une cellule de la ligne en cours, avec l'entête et la valeur brute.
+fn signaler(rapport: &mut RapportImport, pos: &Position, dcc: &DataColConfig, ln: &[DataType], col: usize, gravite: Gravite, message: &str) {
+    rapport.signaler(pos, gravite, dcc.entete(col), into_string(&ln[col]).as_deref(), message);
+}
+fn fill_membre_info(ln: &[DataType], dcc: &DataColConfig, membre: &mut Membre, rapport: &mut RapportImport, pos: &Position) {
     // allergies
     if let Some(col) = dcc.all_alim {
         if let Some((b, c)) = into_bool_with_comment(&ln[col]) {
@@ -355,7 +536,7 @@ fn fill_membre_info(ln: &[DataType], dcc: &DataColConfig, membre: &mut Membre, e
         if let Some(s) = into_string(&ln[col]) {
             match CAM::from_str(&s) {
                 Ok(cam) => membre.fiche_sante.cam = Some(cam),
-                Err(_e) => {let _ = err_term.write_line(&format!("Erreur en lisant le CAM: {} ({})", s, _e.to_string()));},
+                Err(e) => signaler(rapport, pos, dcc, ln, col, Gravite::Avertissement, &format!("Carte d'assurance maladie invalide ({})", e)),
             }
         }
     }
@@ -365,7 +546,7 @@ fn fill_membre_info(ln: &[DataType], dcc: &DataColConfig, membre: &mut Membre, e
         if let Some(s) = into_string(&ln[col]) {
             match Genre::from_str(&s) {
                 Ok(genre) => membre.genre = Some(genre),
-                Err(_) => {let _ = err_term.write_line(&format!("Erreur en lisant le genre: {}", s));},
+                Err(_) => signaler(rapport, pos, dcc, ln, col, Gravite::Avertissement, "Genre inconnu"),
             }
         }
     }
@@ -375,7 +556,7 @@ fn fill_membre_info(ln: &[DataType], dcc: &DataColConfig, membre: &mut Membre, e
         if let Some(s) = into_string(&ln[col]) {
             match Interet::from_str(&s) {
                 Ok(interet) => membre.interets[0] = Some(interet),
-                Err(_) => { let _ = err_term.write_line("Erreur en lisant le 1er interet"); }
+                Err(_) => signaler(rapport, pos, dcc, ln, col, Gravite::Avertissement, "Intérêt inconnu"),
             }
         }
     }
@@ -383,7 +564,7 @@ fn fill_membre_info(ln: &[DataType], dcc: &DataColConfig, membre: &mut Membre, e
         if let Some(s) = into_string(&ln[col]) {
             match Interet::from_str(&s) {
                 Ok(interet) => membre.interets[1] = Some(interet),
-                Err(_) => { let _ = err_term.write_line("Erreur en lisant le 1er interet"); }
+                Err(_) => signaler(rapport, pos, dcc, ln, col, Gravite::Avertissement, "Intérêt inconnu"),
             }
         }
     }
@@ -391,7 +572,7 @@ fn fill_membre_info(ln: &[DataType], dcc: &DataColConfig, membre: &mut Membre, e
         if let Some(s) = into_string(&ln[col]) {
             match Interet::from_str(&s) {
                 Ok(interet) => membre.interets[2] = Some(interet),
-                Err(_) => { let _ = err_term.write_line("Erreur en lisant le 1er interet"); }
+                Err(_) => signaler(rapport, pos, dcc, ln, col, Gravite::Avertissement, "Intérêt inconnu"),
             }
         }
     }
@@ -399,7 +580,7 @@ fn fill_membre_info(ln: &[DataType], dcc: &DataColConfig, membre: &mut Membre, e
         if let Some(s) = into_string(&ln[col]) {
             match Interet::from_str(&s) {
                 Ok(interet) => membre.interets[3] = Some(interet),
-                Err(_) => { let _ = err_term.write_line("Erreur en lisant le 1er interet"); }
+                Err(_) => signaler(rapport, pos, dcc, ln, col, Gravite::Avertissement, "Intérêt inconnu"),
             }
         }
     }
@@ -410,8 +591,9 @@ fn fill_membre_info(ln: &[DataType], dcc: &DataColConfig, membre: &mut Membre, e
             let mut contact = Contact {nom, tel: None, lien: None};
             if let Some(col) = dcc.contact_1_tel {
                 if let Some(s) = into_string(&ln[col]) {
-                    if let Ok(tel) = Tel::from_str(s.trim()) {
-                        contact.tel = Some(tel);
+                    match Tel::from_str(s.trim()) {
+                        Ok(tel) => contact.tel = Some(tel),
+                        Err(_) => signaler(rapport, pos, dcc, ln, col, Gravite::Avertissement, "Téléphone du contact d'urgence invalide"),
                     }
                 }
             }
@@ -428,8 +610,9 @@ fn fill_membre_info(ln: &[DataType], dcc: &DataColConfig, membre: &mut Membre, e
             let mut contact = Contact {nom, tel: None, lien: None};
             if let Some(col) = dcc.contact_2_tel {
                 if let Some(s) = into_string(&ln[col]) {
-                    if let Ok(tel) = Tel::from_str(s.trim()) {
-                        contact.tel = Some(tel);
+                    match Tel::from_str(s.trim()) {
+                        Ok(tel) => contact.tel = Some(tel),
+                        Err(_) => signaler(rapport, pos, dcc, ln, col, Gravite::Avertissement, "Téléphone du contact d'urgence invalide"),
                     }
                 }
             }
@@ -512,7 +695,7 @@ fn fill_membre_info(ln: &[DataType], dcc: &DataColConfig, membre: &mut Membre, e
         if let Some(s) = into_string(&ln[col]) {
             match Taille::from_str(&s) {
                 Ok(t) => membre.taille = Some(t),
-                Err(_e) => { let _ = err_term.write_line("Erreur en lisant la taille"); }
+                Err(_) => signaler(rapport, pos, dcc, ln, col, Gravite::Avertissement, "Taille inconnue"),
             }
         }
     }
@@ -530,30 +713,46 @@ fn fill_membre_info(ln: &[DataType], dcc: &DataColConfig, membre: &mut Membre, e
     }
 }
 
-fn extract_compte_info(ln: &[DataType], dcc: &DataColConfig) -> Result<Compte, ExtractError> {
+fn extract_compte_info(ln: &[DataType], dcc: &DataColConfig, rapport: &mut RapportImport, pos: &Position) -> Result<Compte, ExtractError> {
     let mut cmpt = Compte::default();
     let col_mandataire = match dcc.mandataire {
-        None => return Err(ExtractError::MissingInformations("Mandataire")),
+        None => {
+            rapport.signaler(pos, Gravite::Erreur, None, None, "Ligne ignorée: aucune colonne de mandataire");
+            return Err(ExtractError::MissingInformations("Mandataire"));
+        },
         Some(n) => n,
     };
     cmpt.mandataire = match into_string(&ln[col_mandataire]) {
-        None => return Err(ExtractError::InvalidFormat),
+        None => {
+            signaler(rapport, pos, dcc, ln, col_mandataire, Gravite::Erreur, "Ligne ignorée: mandataire manquant");
+            return Err(ExtractError::InvalidFormat);
+        },
         Some(m) => m,
     };
     if let Some(col_email) = dcc.courriel {
-        let email = into_string(&ln[col_email]).map(|s| Email::from_str(&s).ok());
-        cmpt.email = email.unwrap_or_default();
+        if let Some(s) = into_string(&ln[col_email]) {
+            match Email::from_str(&s) {
+                Ok(email) => cmpt.email = Some(email),
+                Err(_) => signaler(rapport, pos, dcc, ln, col_email, Gravite::Avertissement, "Courriel invalide"),
+            }
+        }
     }
     if let Some(col_tel) = dcc.tel {
-        let t = into_string(&ln[col_tel]).map(|s| Tel::from_str(&s).ok());
-        cmpt.tel = t.unwrap_or_default();
-        if cmpt.tel.is_none() {
-            println!("N'a pu lire le numéro de téléphone pour le compte '{}'", cmpt.mandataire);
+        match into_string(&ln[col_tel]) {
+            None => signaler(rapport, pos, dcc, ln, col_tel, Gravite::Avertissement, "Téléphone du compte manquant"),
+            Some(s) => match Tel::from_str(&s) {
+                Ok(tel) => cmpt.tel = Some(tel),
+                Err(_) => signaler(rapport, pos, dcc, ln, col_tel, Gravite::Avertissement, "Téléphone du compte invalide"),
+            },
         }
     }
     if let Some(col_adr) = dcc.adresse {
-        let adr = into_string(&ln[col_adr]).map(|s| Adresse::from_full(&s).ok());
-        cmpt.adresse = adr.unwrap_or_default();
+        if let Some(s) = into_string(&ln[col_adr]) {
+            match Adresse::from_full(&s) {
+                Ok(adr) => cmpt.adresse = Some(adr),
+                Err(_) => signaler(rapport, pos, dcc, ln, col_adr, Gravite::Avertissement, "Adresse invalide"),
+            }
+        }
     }
     cmpt.id = CompteID(cmpt.get_id_seed());
     Ok(cmpt)
@@ -625,7 +824,7 @@ pub fn into_bool_with_comment(data: &DataType) -> O<(bool, O<String>)> {
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Default)]
 struct DataColConfig {
     nom: O<usize>,
     prenom: O<usize>,
@@ -677,9 +876,11 @@ struct DataColConfig {
     interet_4: O<usize>,
     auth_photo: O<usize>,
     commentaire: O<usize>,
+    /// Entête de chaque colonne, pour le rapport d'importation
+    entetes: Vec<O<String>>,
 }
 impl DataColConfig {
-    fn new(rng: &Range, ln: usize) -> Self {
+    fn new(rng: &Range, ln: usize, alias: &AliasColonnes) -> (Self, RapportColonnes) {
         // créer la ligne que l'on pourra fouiller
         let mut i = 0;
         let (_, tgt) = rng.get_size();
@@ -688,69 +889,134 @@ impl DataColConfig {
             cols.push(rng.get_value(ln, i));
             i += 1;
         }
-        Self {
-            nom: DataColConfig::search(&cols, "Nom"),
-            prenom: DataColConfig::search(&cols, "Prénom"),
-            genre: DataColConfig::search(&cols, "Genre"),
-            naissance: DataColConfig::search(&cols, "Date de naissance"),
-            mandataire: DataColConfig::search(&cols, "Mandataire du compte"),
-            courriel: DataColConfig::search(&cols, "Courriel"),
-            tel: match DataColConfig::search(&cols, "Principal") {
-                Some(tel) => Some(tel),
-                None => DataColConfig::search(&cols, "Numéro de téléphone principal"),
-            },
-            adresse: match DataColConfig::search(&cols, "Adresse princ.") {
-                Some(adr) => Some(adr),
-                None => DataColConfig::search(&cols, "Adresse principale")
-            },
-            accompagnement: DataColConfig::search(&cols, "Accompagnement"),
-            cam: DataColConfig::search(&cols, "assurance maladie"),
-            auth_soins: DataColConfig::search(&cols, "Autorisation de soigner"),
-            prob_comportement: DataColConfig::search(&cols, "Problèmes de comportement?"),
-            prise_med: DataColConfig::search(&cols, "Prise de Médicament"),
-            med_acetaminophene: DataColConfig::search(&cols, "Med Acétaminophène"),
-            med_antibio: DataColConfig::search(&cols, "Med Antibiotique"),
-            med_antiinfl: DataColConfig::search(&cols, "Med Anti-Inflamatoires"),
-            med_ibu: DataColConfig::search(&cols, "Med Ibuprophène"),
-            med_sirop_toux: DataColConfig::search(&cols, "Med Sirop Toux"),
-            med_antieme: DataColConfig::search(&cols, "Med Antiémétique"),
-            mal_asthme: DataColConfig::search(&cols, "MC Asthme"),
-            mal_diabete: DataColConfig::search(&cols, "MC Diabète"),
-            mal_emo: DataColConfig::search(&cols, "MC Émophilie"),
-            mal_epi: DataColConfig::search(&cols, "MC Épilépsie"),
-            mal_autre: DataColConfig::search(&cols, "MC Autre"),
-            all_alim: DataColConfig::search(&cols, "Allergie Alimentaire"),
-            all_anim: DataColConfig::search(&cols, "Allergie Animaux"),
-            all_insecte: DataColConfig::search(&cols, "Allergie Insecte"),
-            all_peni: DataColConfig::search(&cols, "Allergie Pénicilline"),
-            all_autre: DataColConfig::search(&cols, "Allergie Autre"),
-            contact_1_nom: DataColConfig::search(&cols, "contact d'urgence - Nom"),
-            contact_1_tel: DataColConfig::search(&cols, "contact d'urgence - Téléphone"),
-            contact_1_lien: DataColConfig::search(&cols, "contact d'urgence - Lien de parenté"),
-            contact_2_nom: DataColConfig::search(&cols, "Contact Urgence 2 - Nom"),
-            contact_2_tel: DataColConfig::search(&cols, "Contact Urgence 2 - Téléphone"),
-            contact_2_lien: DataColConfig::search(&cols, "Contact Urgence 2 - Lien de parenté"),
-            quit_parent: DataColConfig::search(&cols, "Quitte Parent"),
-            quit_seul: DataColConfig::search(&cols, "Quitte Seul"),
-            quit_acceuil: DataColConfig::search(&cols, "Quitte Service d'accueil"),
-            quit_autre: DataColConfig::search(&cols, "Quitte Autre"),
-            mdp: DataColConfig::search(&cols, "Mdp"),
-            auth_partage_sauveteur: DataColConfig::search(&cols, "Autorisation Partage aux Sauveteurs"),
-            vfi: DataColConfig::search(&cols, "VFI"),
-            tete_sous_eau: DataColConfig::search(&cols, "Tête Sous l'Eau"),
-            taille: DataColConfig::search(&cols, "Taille"),
-            interet_1: DataColConfig::search(&cols, "Interet_1"),
-            interet_2: DataColConfig::search(&cols, "Interet_2"),
-            interet_3: DataColConfig::search(&cols, "Interet_3"),
-            interet_4: DataColConfig::search(&cols, "Interet_4"),
-            auth_photo: DataColConfig::search(&cols, "Autorisation Photo"),
-            commentaire: DataColConfig::search(&cols, "Commentaires"),
+        let mut dcc = Self {
+            entetes: cols.iter().map(|c| into_string(c)).collect(),
+            ..Self::default()
+        };
+        let mut rapport = RapportColonnes::default();
+        for (champ, slot) in dcc.champs_mut() {
+            let trouve = alias.entetes(champ).into_iter().find_map(|entete| DataColConfig::search(&cols, entete).map(|n| (entete, n)));
+            match trouve {
+                Some((entete, n)) => {
+                    *slot = Some(n);
+                    rapport.trouves.push((champ, entete.into(), n));
+                },
+                None => rapport.manquants.push(champ),
+            }
         }
+        for (n, elem) in cols.iter().enumerate() {
+            if let Some(entete) = into_string(elem) {
+                if !rapport.trouves.iter().any(|(_, _, c)| *c == n) {
+                    rapport.inutilisees.push((entete, n));
+                }
+            }
+        }
+        (dcc, rapport)
+    }
+    fn entete(&self, col: usize) -> O<&str> {
+        self.entetes.get(col).and_then(|e| e.as_deref())
+    }
+    /// Champs de la ligne `ln` qui remplacent la valeur connue d'un membre même vides: une colonne trouvée dont
+    /// la cellule est vide, ou une liste dont toutes les colonnes sont dans la feuille. Une cellule invalide n'en fait pas partie.
+    fn champs_lus(&self, ln: &[DataType]) -> ChampsLus {
+        let vide = |col: O<usize>| col.is_some_and(|c| into_string(&ln[c]).is_none());
+        let mut lus: Vec<&'static str> = [
+            ("Genre", self.genre),
+            ("Taille", self.taille),
+            ("Contact d'urgence 1", self.contact_1_nom),
+            ("Contact d'urgence 2", self.contact_2_nom),
+            ("Accompagnement", self.accompagnement),
+            ("Mot de passe", self.mdp),
+            ("Piscine - partage aux sauveteurs", self.auth_partage_sauveteur),
+            ("Piscine - VFI", self.vfi),
+            ("Piscine - tête sous l'eau", self.tete_sous_eau),
+            ("Autorisation photo", self.auth_photo),
+            ("Commentaire", self.commentaire),
+            ("Autorisation de soigner", self.auth_soins),
+            ("Problèmes de comportement", self.prob_comportement),
+            ("Carte d'assurance maladie", self.cam),
+            ("Prise de médicament", self.prise_med),
+            ("Med Sirop pour la toux", self.med_sirop_toux),
+            ("Med Antiémétique", self.med_antieme),
+            ("Med Ibuprofène", self.med_ibu),
+            ("Med Anti-inflammatoire", self.med_antiinfl),
+            ("Med Antibiotique", self.med_antibio),
+            ("Med Acétaminophène", self.med_acetaminophene),
+        ].into_iter().filter(|(_, col)| vide(*col)).map(|(champ, _)| champ).collect();
+        if [self.interet_1, self.interet_2, self.interet_3, self.interet_4].into_iter().all(vide) {
+            lus.push("Intérêts");
+        }
+        if [self.all_alim, self.all_anim, self.all_insecte, self.all_peni, self.all_autre].iter().all(Option::is_some) {
+            lus.push("Allergies");
+        }
+        if [self.mal_asthme, self.mal_diabete, self.mal_emo, self.mal_epi, self.mal_autre].iter().all(Option::is_some) {
+            lus.push("Maladies");
+        }
+        if [self.quit_parent, self.quit_seul, self.quit_acceuil, self.quit_autre].iter().all(Option::is_some) {
+            lus.push("Quitte avec");
+        }
+        ChampsLus(lus)
+    }
+    /// Chaque champ avec son nom, tel qu'utilisé dans le fichier de correspondance des colonnes.
+    fn champs_mut(&mut self) -> Vec<(&'static str, &mut O<usize>)> {
+        vec![
+            ("nom", &mut self.nom),
+            ("prenom", &mut self.prenom),
+            ("genre", &mut self.genre),
+            ("naissance", &mut self.naissance),
+            ("mandataire", &mut self.mandataire),
+            ("courriel", &mut self.courriel),
+            ("tel", &mut self.tel),
+            ("adresse", &mut self.adresse),
+            ("accompagnement", &mut self.accompagnement),
+            ("cam", &mut self.cam),
+            ("auth_soins", &mut self.auth_soins),
+            ("prob_comportement", &mut self.prob_comportement),
+            ("prise_med", &mut self.prise_med),
+            ("med_acetaminophene", &mut self.med_acetaminophene),
+            ("med_antibio", &mut self.med_antibio),
+            ("med_antiinfl", &mut self.med_antiinfl),
+            ("med_ibu", &mut self.med_ibu),
+            ("med_sirop_toux", &mut self.med_sirop_toux),
+            ("med_antieme", &mut self.med_antieme),
+            ("mal_asthme", &mut self.mal_asthme),
+            ("mal_diabete", &mut self.mal_diabete),
+            ("mal_emo", &mut self.mal_emo),
+            ("mal_epi", &mut self.mal_epi),
+            ("mal_autre", &mut self.mal_autre),
+            ("all_alim", &mut self.all_alim),
+            ("all_anim", &mut self.all_anim),
+            ("all_insecte", &mut self.all_insecte),
+            ("all_peni", &mut self.all_peni),
+            ("all_autre", &mut self.all_autre),
+            ("contact_1_nom", &mut self.contact_1_nom),
+            ("contact_1_tel", &mut self.contact_1_tel),
+            ("contact_1_lien", &mut self.contact_1_lien),
+            ("contact_2_nom", &mut self.contact_2_nom),
+            ("contact_2_tel", &mut self.contact_2_tel),
+            ("contact_2_lien", &mut self.contact_2_lien),
+            ("quit_parent", &mut self.quit_parent),
+            ("quit_seul", &mut self.quit_seul),
+            ("quit_acceuil", &mut self.quit_acceuil),
+            ("quit_autre", &mut self.quit_autre),
+            ("mdp", &mut self.mdp),
+            ("auth_partage_sauveteur", &mut self.auth_partage_sauveteur),
+            ("vfi", &mut self.vfi),
+            ("tete_sous_eau", &mut self.tete_sous_eau),
+            ("taille", &mut self.taille),
+            ("interet_1", &mut self.interet_1),
+            ("interet_2", &mut self.interet_2),
+            ("interet_3", &mut self.interet_3),
+            ("interet_4", &mut self.interet_4),
+            ("auth_photo", &mut self.auth_photo),
+            ("commentaire", &mut self.commentaire),
+        ]
     }
     fn search(cols: &[&DataType], trgt: &str) -> O<usize> {
+        let trgt = normaliser_entete(trgt);
         for (n, elem) in cols.iter().enumerate() {
             if let DataType::String(s) = elem {
-                if s.as_str().trim() == trgt {
+                if normaliser_entete(s) == trgt {
                     return Some(n);
                 }
             }
@@ -0,0 +1,210 @@
+use std::{collections::{HashMap, HashSet}, path::Path, time::SystemTime};
+
+use console::{style, Term};
+
+use crate::{config::Config, groupes::{comptes::CompteReg, groupes::{GroupeID, GroupeReg}, membres::{MembreID, MembreReg}}, prelude::O};
+
+use super::{diagnostics::{Gravite, RapportImport}, excel::fill_regs, source::EXTENSIONS};
+
+/// Remplace les dossiers par les exports qu'ils contiennent (sans les sous-dossiers)
+/// et trie les fichiers du plus ancien au plus récent, pour que les données les plus récentes l'emportent.
+pub fn lister_fichiers(chemins: &[String]) -> Result<Vec<String>, String> {
+    let mut fichiers = Vec::new();
+    for chemin in chemins {
+        let path = Path::new(chemin);
+        if !path.is_dir() {
+            fichiers.push(chemin.clone());
+            continue;
+        }
+        let entrees = std::fs::read_dir(path).map_err(|e| format!("N'a pu lire le dossier \"{}\": {}", chemin, e))?;
+        for entree in entrees.flatten() {
+            let p = entree.path();
+            if p.is_file() && est_export(&p) {
+                fichiers.push(p.to_string_lossy().into_owned());
+            }
+        }
+    }
+    // un fichier donné deux fois (ou aussi par son dossier) n'est lu qu'une fois
+    let mut vus = HashSet::new();
+    fichiers.retain(|f| vus.insert(std::fs::canonicalize(f).unwrap_or_else(|_| f.into())));
+    if fichiers.is_empty() {
+        return Err("Aucun fichier de présences (excel, ods ou csv) à lire".into());
+    }
+    fichiers.sort_by_cached_key(|f| (date_modification(f), f.clone()));
+    Ok(fichiers)
+}
+
+/// Un export lisible, sans les fichiers temporaires d'Excel ("~$...") ni les fichiers cachés.
+fn est_export(path: &Path) -> bool {
+    let nom = path.file_name().and_then(|n| n.to_str()).unwrap_or("");
+    let extension = path.extension().and_then(|e| e.to_str()).map(str::to_lowercase);
+    !nom.starts_with("~$") && !nom.starts_with('.') && extension.is_some_and(|e| EXTENSIONS.contains(&e.as_str()))
+}
+
+fn date_modification(filepath: &str) -> O<SystemTime> {
+    std::fs::metadata(filepath).and_then(|m| m.modified()).ok()
+}
+
+fn nom_fichier(filepath: &str) -> String {
+    Path::new(filepath).file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or(filepath.into())
+}
+
+/// Ce qu'un fichier du lot a apporté.
+#[derive(Debug, Clone, Default)]
+pub struct ResumeFichier {
+    pub fichier: String,
+    pub groupes: usize,
+    pub inscriptions: usize,
+    pub nouveaux: usize,
+    pub mis_a_jour: usize,
+    pub erreurs: usize,
+    pub avertissements: usize,
+    /// Raison pour laquelle le fichier n'a pas pu être lu
+    pub echec: O<String>,
+}
+
+/// Un groupe lu dans plusieurs fichiers. La liste du fichier le plus récent est gardée:
+/// un enfant qui n'apparaît que dans les fichiers plus anciens est retiré du groupe.
+#[derive(Debug, Clone, Default)]
+pub struct Reconciliation {
+    pub groupe: String,
+    pub fichiers: Vec<String>,
+    /// Inscriptions présentes dans plusieurs fichiers, comptées une seule fois
+    pub communes: usize,
+    /// Enfants retirés du groupe (absents du fichier le plus récent)
+    pub retires: Vec<String>,
+}
+
+/// Résumé d'une importation de plusieurs fichiers de présences.
+#[derive(Debug, Clone, Default)]
+pub struct ResumeLot {
+    pub fichiers: Vec<ResumeFichier>,
+    pub reconciliations: Vec<Reconciliation>,
+    pub groupes: usize,
+    /// Inscriptions distinctes, après réconciliation
+    pub inscriptions: usize,
+    pub enfants: usize,
+}
+impl ResumeLot {
+    pub fn nb_echecs(&self) -> usize {
+        self.fichiers.iter().filter(|f| f.echec.is_some()).count()
+    }
+
+    pub fn afficher(&self, term: &Term) {
+        let _ = term.write_line(&format!("{}", style("Résumé de l'importation").bold().cyan()));
+        for f in self.fichiers.iter() {
+            let ligne = match &f.echec {
+                Some(e) => format!("{}", style(format!("échec ({})", e)).red()),
+                None => {
+                    let mut ligne = format!("{} groupe(s), {} inscription(s), {} nouveau(x) membre(s), {} mis à jour",
+                        f.groupes, f.inscriptions, f.nouveaux, f.mis_a_jour);
+                    if f.erreurs > 0 {
+                        ligne.push_str(&format!(", {}", style(format!("{} erreur(s)", f.erreurs)).red()));
+                    }
+                    if f.avertissements > 0 {
+                        ligne.push_str(&format!(", {}", style(format!("{} avertissement(s)", f.avertissements)).yellow()));
+                    }
+                    ligne
+                },
+            };
+            let _ = term.write_line(&format!("\t{}: {}", style(&f.fichier).green(), ligne));
+        }
+
+        if !self.reconciliations.is_empty() {
+            let _ = term.write_line(&format!("{}", style("Groupes présents dans plusieurs fichiers").bold().cyan()));
+        }
+        for r in self.reconciliations.iter() {
+            let _ = term.write_line(&format!("\t{} ({}): {} inscription(s) en commun comptée(s) une fois",
+                style(&r.groupe).bold(),
+                r.fichiers.join(", "),
+                r.communes,
+            ));
+            for e in r.retires.iter() {
+                let _ = term.write_line(&format!("\t\t{} {} (absent de {}, le plus récent)", style("-").red(), e, r.fichiers.last().unwrap()));
+            }
+        }
+
+        let _ = term.write_line(&format!("{}", style("Total").bold().cyan()));
+        let echecs = self.nb_echecs();
+        let fichiers = if echecs > 0 {
+            format!("{} ({})", self.fichiers.len(), style(format!("{} illisible(s)", echecs)).red())
+        } else {
+            self.fichiers.len().to_string()
+        };
+        let _ = term.write_line(&format!("\tFichiers: {}", fichiers));
+        let _ = term.write_line(&format!("\tGroupes: {}", self.groupes));
+        let _ = term.write_line(&format!("\tInscriptions: {}", self.inscriptions));
+        let _ = term.write_line(&format!("\tEnfants: {}", self.enfants));
+    }
+}
+
+/// Lit plusieurs fichiers de présences, dans l'ordre donné (voir [`lister_fichiers`]), puis réconcilie
+/// les groupes lus dans plus d'un fichier. Retourne les problèmes de lecture de tous les fichiers et le résumé.
+pub fn importer(comptes: &mut CompteReg, membres: &mut MembreReg, groupes: &mut GroupeReg, config: &Config, fichiers: &[String], out_term: &Term, err_term: &Term) -> (RapportImport, ResumeLot) {
+    let avant: HashMap<GroupeID, HashSet<MembreID>> = groupes.groupes().map(|g| (g.id, g.participants.clone())).collect();
+    let mut rapport = RapportImport::default();
+    let mut resume = ResumeLot::default();
+    // participants lus pour chaque groupe, par fichier, du plus ancien au plus récent
+    let mut lus: HashMap<GroupeID, Vec<(String, HashSet<MembreID>)>> = HashMap::new();
+    for fichier in fichiers {
+        let mut resume_fichier = ResumeFichier { fichier: fichier.clone(), ..Default::default() };
+        match fill_regs(comptes, membres, groupes, config, fichier, out_term, err_term) {
+            Err(e) => {
+                let _ = err_term.write_line(&format!("{}: {}", fichier, e));
+                resume_fichier.echec = Some(e.to_string());
+            },
+            Ok(lecture) => {
+                resume_fichier.groupes = lecture.groupes.len();
+                resume_fichier.inscriptions = lecture.groupes.values().map(HashSet::len).sum();
+                resume_fichier.nouveaux = lecture.nouveaux;
+                resume_fichier.mis_a_jour = lecture.mis_a_jour;
+                resume_fichier.erreurs = lecture.rapport.nb(Gravite::Erreur);
+                resume_fichier.avertissements = lecture.rapport.nb(Gravite::Avertissement);
+                rapport.fusionner(lecture.rapport);
+                for (gid, participants) in lecture.groupes {
+                    lus.entry(gid).or_default().push((nom_fichier(fichier), participants));
+                }
+            },
+        }
+        resume.fichiers.push(resume_fichier);
+    }
+
+    let mut enfants = HashSet::new();
+    for (gid, par_fichier) in lus.iter() {
+        let (_, gardes) = par_fichier.last().unwrap();
+        let mut inscrits: HashSet<MembreID> = gardes.clone();
+        if par_fichier.len() > 1 {
+            let anciens: HashSet<MembreID> = par_fichier[..par_fichier.len() - 1].iter().flat_map(|(_, p)| p.iter().copied()).collect();
+            let mut r = Reconciliation {
+                groupe: groupes.get(*gid).map(|g| g.desc()).unwrap_or_default(),
+                fichiers: par_fichier.iter().map(|(f, _)| f.clone()).collect(),
+                communes: anciens.intersection(gardes).count(),
+                retires: Vec::new(),
+            };
+            // un enfant déjà inscrit avant cette importation reste, comme pour la lecture d'un seul fichier
+            let deja_inscrits = avant.get(gid);
+            let mut retires: Vec<MembreID> = anciens.iter()
+                .filter(|mid| !gardes.contains(mid) && !deja_inscrits.is_some_and(|p| p.contains(mid)))
+                .copied()
+                .collect();
+            retires.sort();
+            if let Ok(grp) = groupes.get_mut(*gid) {
+                for mid in retires.iter() {
+                    grp.remove_participant(*mid);
+                }
+            }
+            r.retires = retires.iter()
+                .filter_map(|mid| membres.get(*mid).ok())
+                .map(|m| format!("{} {}", m.prenom, m.nom))
+                .collect();
+            inscrits.extend(anciens.into_iter().filter(|mid| !retires.contains(mid)));
+            resume.reconciliations.push(r);
+        }
+        resume.inscriptions += inscrits.len();
+        enfants.extend(inscrits);
+    }
+    resume.reconciliations.sort_by(|a, b| a.groupe.cmp(&b.groupe));
+    resume.groupes = lus.len();
+    resume.enfants = enfants.len();
+    (rapport, resume)
+}
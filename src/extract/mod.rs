@@ -3,9 +3,17 @@ use std::{error::Error, fmt::Display};
 use lazy_static::lazy_static;
 use regex::Regex;
 
+use crate::{config::Config, groupes::groupes::Groupe};
 
+
+pub mod colonnes;
+pub mod dates;
+pub mod diagnostics;
 pub mod excel;
+pub mod lot;
+pub mod noms;
 pub mod prog;
+pub mod source;
 
 #[derive(Debug, Clone, Copy)]
 pub enum ExtractError {
@@ -35,8 +43,45 @@ lazy_static! {
     pub static ref TRUE_DATA_RE: Regex = Regex::new("^(?i)(?:oui|true|vrai|yes|o|y|v|t)$").unwrap();
     pub static ref FALSE_DATA_RE: Regex = Regex::new("^(?i)(?:non|false|no|faux|n|f)$").unwrap();
     pub static ref BOOL_W_COMMENT_DATA_RE: Regex = Regex::new(r"^(?i)(?P<bool>oui|true|vrai|yes|o|y|v|t|non|false|no|faux|n|f)(:?\s*,\s*(?P<comment>(?:.|\n)+)\s*)?").unwrap();
-    pub static ref GROUPE_RE: Regex = Regex::new(r"^(?i)\s*(?P<activite>[^|]+?)\s*\|\s*(?P<site>[^|]+?)\s*\|\s*(?P<category>(?:(?P<grage_min>\d+)\s*(?:-\s*)?(?P<grage_max>\d+)(?:\s*ans)?)|(?P<crocus>crocus)|(?P<balaous>balaous)|(?P<basaltes>basaltes)|(?:.+?))\s*\|\s*(?:sem|semaine)\.?\s*(?P<semaine>\d+)\s*$").unwrap();
+    pub static ref GROUPE_RE: Regex = Regex::new(r"^(?i)\s*(?P<activite>[^|]+?)\s*\|\s*(?P<site>[^|]+?)\s*\|\s*(?P<category>(?:(?P<grage_min>\d+)\s*(?:-\s*)?(?P<grage_max>\d+)(?:\s*ans)?)|(?:.+?))\s*\|\s*(?:sem|semaine)\.?\s*(?P<semaine>\d+)\s*$").unwrap();
     pub static ref GROUPE_PROG_RE: Regex = Regex::new(r"^(?i)Programmation:\s*(?P<prog>.*)\s*$").unwrap();
-    pub static ref DATE_NAISSANCE_RE: Regex = Regex::new(r"(?P<an>\d{4})-(?P<mois>\d{2})-(?P<jour>\d{2})").unwrap();
 }
 
+
+/// Remplit l'activité, le site, la catégorie, les âges et la semaine d'un groupe à partir de son nom
+/// (ex. "Camp de jour | Parc Lafontaine | Crocus | Sem. 1"), selon les règles de noms de la configuration
+/// puis le format de Qidigo. Retourne `false` si le nom n'est pas reconnu.
+pub fn remplir_nom_groupe(g: &mut Groupe, grp_desc: &str, config: &Config) -> bool {
+    let categories = &config.categories;
+    let cap = match config.noms_groupes.analyser(grp_desc) {
+        Some(cap) => cap,
+        None => return false,
+    };
+    // une capture facultative dans une règle (ex. `(?:...(?P<site>...))?`) peut ne rien avoir lu
+    let (Some(site), Some(semaine), Some(category)) = (cap.name("site"), cap.name("semaine"), cap.name("category")) else {
+        return false;
+    };
+    if let Some(activite) = cap.name("activite") {
+        g.activite = Some(activite.as_str().into());
+    }
+    g.site = Some(site.as_str().into());
+    g.semaine = Some(semaine.as_str().into());
+    let category = category.as_str().trim();
+    let age = |nom: &str| cap.name(nom).and_then(|m| m.as_str().parse::<u32>().ok());
+    match (age("grage_min"), age("grage_max"), categories.get(category)) {
+        (_, _, Some(cat)) => { // catégorie connue
+            g.age_min = Some(cat.age_min);
+            g.age_max = Some(cat.age_max);
+            g.category = Some(cat.nom.clone());
+        },
+        (Some(mn), Some(mx), None) => { // tranche d'âge
+            g.age_min = Some(mn);
+            g.age_max = Some(mx);
+            g.category = Some(categories.guess_category(g.age_min, g.age_max));
+        },
+        _ => { // autre
+            g.category = Some(category.into());
+        }
+    }
+    true
+}
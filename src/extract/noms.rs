@@ -0,0 +1,44 @@
+use regex::{Captures, Regex};
+use serde::{Deserialize, Serialize};
+
+use crate::prelude::O;
+
+use super::GROUPE_RE;
+
+/// Captures que chaque règle doit avoir; `activite` est optionnelle (la programmation a sa propre colonne),
+/// tout comme `grage_min` et `grage_max` pour une catégorie écrite en tranche d'âge.
+static CAPTURES_REQUISES: &[&str] = &["site", "category", "semaine"];
+
+/// Règles de noms de groupes de la configuration, essayées avant le format de Qidigo
+/// ("Activité | Site | Catégorie | Sem. N").
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(try_from = "Vec<String>", into = "Vec<String>")]
+pub struct ReglesNoms(Vec<Regex>);
+impl TryFrom<Vec<String>> for ReglesNoms {
+    type Error = String;
+
+    fn try_from(regles: Vec<String>) -> Result<Self, Self::Error> {
+        let mut res = Vec::new();
+        for regle in regles {
+            let re = Regex::new(&regle).map_err(|e| format!("Règle de nom de groupe invalide '{}': {}", regle, e))?;
+            for capture in CAPTURES_REQUISES {
+                if !re.capture_names().flatten().any(|n| n == *capture) {
+                    return Err(format!("Règle de nom de groupe '{}' sans la capture (?P<{}>...)", regle, capture));
+                }
+            }
+            res.push(re);
+        }
+        Ok(Self(res))
+    }
+}
+impl From<ReglesNoms> for Vec<String> {
+    fn from(regles: ReglesNoms) -> Self {
+        regles.0.iter().map(|re| re.as_str().to_string()).collect()
+    }
+}
+impl ReglesNoms {
+    /// Les captures de la première règle qui reconnaît le nom.
+    pub fn analyser<'a>(&self, grp_desc: &'a str) -> O<Captures<'a>> {
+        self.0.iter().chain(std::iter::once(&*GROUPE_RE)).find_map(|re| re.captures(grp_desc))
+    }
+}
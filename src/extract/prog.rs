@@ -1,80 +1,72 @@
 use std::str::FromStr;
 
 use console::{style, Term};
-use office::{DataType, Excel, Range};
 
-use crate::{data::{adresse::Adresse, cam::CAM, email::Email, tel::Tel, BoolJustifie, Genre, Taille}, groupes::{comptes::{Compte, CompteID, CompteReg}, fiche_sante::{ALL_ALIMENTAIRE, ALL_ANIMAUX, ALL_INSECTES, ALL_PENICILINE, MAL_ASTHME, MAL_DIABETE, MAL_EMOPHILIE, MAL_EPILEPSIE}, groupes::{Groupe, GroupeID, GroupeReg}, membres::{Contact, Interet, Membre, MembreID, MembreReg}}, prelude::{print_option, Date, O}};
+use crate::{data::{adresse::Adresse, cam::CAM, email::Email, tel::Tel, BoolJustifie, Genre, Taille}, groupes::{comptes::{Compte, CompteID, CompteReg}, fiche_sante::{ALL_ALIMENTAIRE, ALL_ANIMAUX, ALL_INSECTES, ALL_PENICILINE, MAL_ASTHME, MAL_DIABETE, MAL_EMOPHILIE, MAL_EPILEPSIE}, groupes::{Groupe, GroupeID, GroupeReg, TrancheAge}, membres::{Contact, Interet, Membre, MembreID, MembreReg}}, prelude::{print_option, Date, O}};
 use crate::config::Config;
 
-use super::{excel::{into_int, into_string}, ExtractError, BOOL_W_COMMENT_DATA_RE, DATE_NAISSANCE_RE, FALSE_DATA_RE, GROUPE_PROG_RE, GROUPE_RE, TRUE_DATA_RE};
+use super::{colonnes::normaliser_entete, dates::into_date, diagnostics::{Gravite, Position, RapportImport}, excel::{into_int, into_string}, source::{DataType, Range}, ExtractError, BOOL_W_COMMENT_DATA_RE, FALSE_DATA_RE, GROUPE_PROG_RE, TRUE_DATA_RE, remplir_nom_groupe};
 
-fn extract_group_info_from_prog(ws: &[DataType], config: &ProgLnConfig) -> Result<Groupe, ExtractError> {
+fn extract_group_info_from_prog(ws: &[DataType], ln_config: &ProgLnConfig, config: &Config) -> Result<Groupe, ExtractError> {
     let mut g = Groupe::default();
-    let grp_desc = match config.nom {
+    let grp_desc = match ln_config.nom {
         Some(pos) => into_string(&ws[pos]),
         None => None,
     };
     if grp_desc.is_none() {return Err(ExtractError::InvalidFormat);}
     let grp_desc = grp_desc.unwrap();
-    if let Some(cap) = GROUPE_RE.captures(&grp_desc) {
-        g.activite = Some(cap.name("activite").unwrap().as_str().into());
-        g.site = Some(cap.name("site").unwrap().as_str().into());
-        g.semaine = Some(cap.name("semaine").unwrap().as_str().into());
-        match (cap.name("grage_min"), cap.name("grage_max"), cap.name("crocus"), cap.name("balaous"), cap.name("basaltes")) {
-            (Some(mn), Some(mx), _, _, _) => { // tranche d'âge
-                let grage_min = mn.as_str().parse().unwrap();
-                let grage_max = mx.as_str().parse().unwrap();
-                g.age_min = Some(grage_min);
-                g.age_max = Some(grage_max);
-                g.category = Some(Groupe::guess_category(g.age_min, g.age_max));
-            },
-            (_, _, Some(_), _, _) => { // Crocus
-                g.age_min = Some(5);
-                g.age_max = Some(6);
-                g.category = Some("Crocus".into());
-            },
-            (_, _, _, Some(_), _) => { // Balaous
-                g.age_min = Some(7);
-                g.age_max = Some(8);
-                g.category = Some("Balaous".into());
-            },
-            (_, _, _, _, Some(_)) => { // Basaltes
-                g.age_min = Some(9);
-                g.age_max = Some(12);
-                g.category = Some("Basaltes".into());
-            },
-            _ => { // autre
-                g.category = Some(cap.name("category").unwrap().as_str().trim().into());
-            }
-        }
+    // un groupe sans site ni semaine serait fusionné avec tous les autres noms non reconnus
+    if !remplir_nom_groupe(&mut g, &grp_desc, config) {
+        return Err(ExtractError::InvalidGroupNameFormat);
     }
-    let grp_prog = match config.programmation {
+    let grp_prog = match ln_config.programmation {
         Some(pos) => into_string(&ws[pos]),
         None => None,
     };
     g.saison = grp_prog;
 
-    g.capacite = match config.capacite {
+    g.capacite = match ln_config.capacite {
         Some(pos) => into_int(&ws[pos]).map(|n| n as usize),
         None => None,
     };
+    g.debut = ln_config.debut.and_then(|pos| into_date(&ws[pos]));
+    g.fin = ln_config.fin.and_then(|pos| into_date(&ws[pos]));
+    // les restrictions d'âge de la programmation ont priorité sur celles de la catégorie
+    if let Some(age) = ln_config.age_min.and_then(|pos| into_int(&ws[pos])) {
+        g.age_min = Some(age as u32);
+    }
+    if let Some(age) = ln_config.age_max.and_then(|pos| into_int(&ws[pos])) {
+        g.age_max = Some(age as u32);
+    }
+    if g.activite.is_none() {
+        g.activite = ln_config.activite.and_then(|pos| into_string(&ws[pos]));
+    }
     g.id = GroupeID(g.get_id_seed());
     Ok(g)
 }
 
-pub fn fill_groupe_reg_from_prog(ws: &Range, reg: &mut GroupeReg, out: &Term, err: &Term) {
-    let mut config = ProgLnConfig::default();
+/// Lit une feuille de programmation. Les lignes dont le nom de groupe n'est pas reconnu sont ignorées et
+/// retournées dans le rapport d'importation.
+pub fn fill_groupe_reg_from_prog(ws: &Range, reg: &mut GroupeReg, config: &Config, pos: &Position, out: &Term, err: &Term) -> RapportImport {
+    let mut rapport = RapportImport::default();
+    let mut ln_config = ProgLnConfig::default();
     for (i, row) in ws.rows().enumerate() {
         //let _ = out.write_line(&format!("Reading {:?}", into_string(ws.get_value(i, 2))));
         if i == 0 {
-            config = ProgLnConfig::guess(row);
-            println!("{:?}", config.programmation)
+            ln_config = ProgLnConfig::guess(row);
+            if config.verbose {
+                println!("{:?}", ln_config.programmation)
+            }
         } else {
-            match extract_group_info_from_prog(row, &config) {
+            match extract_group_info_from_prog(row, &ln_config, config) {
                 Ok(mut grp) => {
-                    let _ = out.write_line(&format!("LECTURE {desc}", desc=grp.desc()));
+                    if config.verbose {
+                        let _ = out.write_line(&format!("LECTURE {desc}", desc=grp.desc()));
+                    }
 
-                    let cap = grp.capacite;
+                    let (cap, debut, fin) = (grp.capacite, grp.debut, grp.fin);
+                    let (age_min, age_max) = (grp.age_min, grp.age_max);
+                    let tranche = TrancheAge::de(&grp);
 
                     // 1. Voir si le groupe existe déjà
                     let existing_grp = reg.groupes().filter(|g| g.equiv(&grp)).map(|g| g.id).collect::<Vec<GroupeID>>();
@@ -89,19 +81,34 @@ pub fn fill_groupe_reg_from_prog(ws: &Range, reg: &mut GroupeReg, out: &Term, er
                         existing_grp[0]
                     };
                     let groupe = reg.get_mut(gid).unwrap();
+                    groupe.sources.prog = Some(tranche);
 
                     // 2. mettre à jour certaines données
                     if !existing_grp.is_empty() {
                         groupe.capacite = match cap {
                             None => groupe.capacite,
                             Some(cap) => Some(cap),
-                        }
+                        };
+                        groupe.debut = debut.or(groupe.debut);
+                        groupe.fin = fin.or(groupe.fin);
+                        groupe.age_min = age_min.or(groupe.age_min);
+                        groupe.age_max = age_max.or(groupe.age_max);
                     }
                 },
+                Err(ExtractError::InvalidGroupNameFormat) => {
+                    let pos = Position { ligne: Some(i + 1), ..*pos };
+                    let titre = ln_config.nom.and_then(|n| into_string(&row[n]));
+                    rapport.signaler(&pos, Gravite::Erreur, Some("Groupe"), titre.as_deref(), "Groupe ignoré: nom de groupe non reconnu");
+                },
                 Err(_) => {},
             }
         }
     }
+    let nb = reg.completer_dates();
+    if config.verbose && nb > 0 {
+        let _ = out.write_line(&format!("Dates ajoutées à {} groupe(s) des listes de présences", nb));
+    }
+    rapport
 }
 
 #[derive(Debug, Clone, Copy, Hash)]
@@ -143,9 +150,10 @@ impl ProgLnConfig {
         }
     }
     fn search(cols: &[DataType], trgt: &str) -> O<usize> {
+        let trgt = normaliser_entete(trgt);
         for (n, elem) in cols.iter().enumerate() {
             if let DataType::String(s) = elem {
-                if s.as_str().trim() == trgt {
+                if normaliser_entete(s) == trgt {
                     return Some(n);
                 }
             }
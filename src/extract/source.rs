@@ -0,0 +1,185 @@
+use std::{collections::HashMap, path::Path};
+
+use calamine::{Data, Reader, Sheets};
+
+use super::ExtractError;
+
+/// Extensions des fichiers que le programme sait lire, pour les boîtes de dialogue.
+pub static EXTENSIONS: &[&str] = &["xlsx", "xlsm", "xls", "xlsb", "ods", "csv"];
+
+/// Valeur d'une cellule, quel que soit le format du fichier. Une date est un nombre de jours, comme dans Excel.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub enum DataType {
+    Int(i64),
+    Float(f64),
+    String(String),
+    Bool(bool),
+    Error(String),
+    #[default]
+    Empty,
+}
+
+static VIDE: DataType = DataType::Empty;
+
+/// Une feuille rectangulaire, à partir de la cellule A1: les lignes plus courtes sont complétées par des cellules vides.
+#[derive(Debug, Clone, Default)]
+pub struct Range {
+    lignes: Vec<Vec<DataType>>,
+    largeur: usize,
+}
+impl Range {
+    pub fn new(lignes: Vec<Vec<DataType>>) -> Self {
+        let largeur = lignes.iter().map(Vec::len).max().unwrap_or(0);
+        let lignes = lignes.into_iter()
+            .map(|mut ligne| {
+                ligne.resize(largeur, DataType::Empty);
+                ligne
+            })
+            .collect();
+        Self { lignes, largeur }
+    }
+
+    /// (nombre de lignes, nombre de colonnes)
+    pub fn get_size(&self) -> (usize, usize) {
+        (self.lignes.len(), self.largeur)
+    }
+
+    /// La cellule à la ligne `i` et à la colonne `j`, vide hors de la feuille.
+    pub fn get_value(&self, i: usize, j: usize) -> &DataType {
+        self.lignes.get(i).and_then(|l| l.get(j)).unwrap_or(&VIDE)
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[DataType]> {
+        self.lignes.iter().map(Vec::as_slice)
+    }
+}
+
+/// Un classeur dont on lit les feuilles ligne par ligne, quel que soit son format.
+/// Chaque feuille est rendue sous forme de `Range`, pour que la lecture des colonnes
+/// soit identique pour tous les formats.
+pub trait SourceLignes {
+    fn feuilles(&mut self) -> Result<Vec<String>, ExtractError>;
+    fn feuille(&mut self, nom: &str) -> Result<Range, ExtractError>;
+}
+
+/// Ouvre un fichier selon son extension: Excel (xlsx, xlsm, xls, xlsb), LibreOffice (ods) ou csv.
+pub fn ouvrir(filepath: &str) -> Result<Box<dyn SourceLignes>, ExtractError> {
+    let extension = Path::new(filepath).extension().and_then(|e| e.to_str()).map(str::to_lowercase);
+    match extension.as_deref() {
+        Some("xlsx") | Some("xlsm") | Some("xls") | Some("xlsb") | Some("ods") => Ok(Box::new(SourceClasseur::ouvrir(filepath)?)),
+        Some("csv") => Ok(Box::new(SourceCsv::ouvrir(filepath)?)),
+        _ => Err(ExtractError::CouldNotReadFile),
+    }
+}
+
+/// Classeur Excel ou LibreOffice.
+pub struct SourceClasseur {
+    classeur: Sheets<std::io::BufReader<std::fs::File>>,
+}
+impl SourceClasseur {
+    pub fn ouvrir(filepath: &str) -> Result<Self, ExtractError> {
+        let classeur = calamine::open_workbook_auto(filepath).map_err(|_| ExtractError::CouldNotReadFile)?;
+        Ok(Self { classeur })
+    }
+}
+impl SourceLignes for SourceClasseur {
+    fn feuilles(&mut self) -> Result<Vec<String>, ExtractError> {
+        Ok(self.classeur.sheet_names())
+    }
+    fn feuille(&mut self, nom: &str) -> Result<Range, ExtractError> {
+        let rng = self.classeur.worksheet_range(nom).map_err(|_| ExtractError::CouldNotReadFile)?;
+        // la plage commence à la première cellule non vide: remettre les lignes et colonnes à leur place
+        let (ligne0, col0) = rng.start().unwrap_or((0, 0));
+        let mut lignes: Vec<Vec<DataType>> = vec![Vec::new(); ligne0 as usize];
+        for ligne in rng.rows() {
+            let mut cellules = vec![DataType::Empty; col0 as usize];
+            cellules.extend(ligne.iter().map(convertir));
+            lignes.push(cellules);
+        }
+        Ok(Range::new(lignes))
+    }
+}
+
+fn convertir(cellule: &Data) -> DataType {
+    match cellule {
+        Data::Int(i) => DataType::Int(*i),
+        Data::Float(f) => DataType::Float(*f),
+        Data::String(s) => DataType::String(s.clone()),
+        Data::Bool(b) => DataType::Bool(*b),
+        // comme dans Excel, une date est un nombre de jours
+        Data::DateTime(d) => DataType::Float(d.as_f64()),
+        Data::DateTimeIso(s) | Data::DurationIso(s) => DataType::String(s.clone()),
+        Data::Error(e) => DataType::Error(e.to_string()),
+        Data::Empty => DataType::Empty,
+    }
+}
+
+/// Fichier csv, vu comme un classeur d'une seule feuille portant le nom du fichier.
+/// L'encodage (UTF-8 ou Windows-1252, etc.) et le séparateur sont détectés.
+pub struct SourceCsv {
+    nom: String,
+    lignes: Vec<Vec<DataType>>,
+}
+impl SourceCsv {
+    pub fn ouvrir(filepath: &str) -> Result<Self, ExtractError> {
+        let octets = std::fs::read(filepath).map_err(|_| ExtractError::CouldNotReadFile)?;
+        let texte = decoder(&octets);
+        let separateur = detecter_separateur(&texte);
+        let mut lecteur = csv::ReaderBuilder::new()
+            .delimiter(separateur)
+            .has_headers(false)
+            .flexible(true)
+            .from_reader(texte.as_bytes());
+        let mut lignes = Vec::new();
+        for enr in lecteur.records() {
+            let enr = enr.map_err(|_| ExtractError::InvalidFormat)?;
+            lignes.push(enr.iter().map(|c| DataType::String(c.into())).collect());
+        }
+        let nom = Path::new(filepath).file_stem().and_then(|s| s.to_str()).unwrap_or("csv").to_string();
+        Ok(Self { nom, lignes })
+    }
+}
+impl SourceLignes for SourceCsv {
+    fn feuilles(&mut self) -> Result<Vec<String>, ExtractError> {
+        Ok(vec![self.nom.clone()])
+    }
+    fn feuille(&mut self, nom: &str) -> Result<Range, ExtractError> {
+        if nom != self.nom {
+            return Err(ExtractError::InvalidFormat);
+        }
+        Ok(Range::new(self.lignes.clone()))
+    }
+}
+
+fn decoder(octets: &[u8]) -> String {
+    if let Some((encodage, taille_bom)) = encoding_rs::Encoding::for_bom(octets) {
+        return encodage.decode_without_bom_handling(&octets[taille_bom..]).0.into_owned();
+    }
+    if let Ok(texte) = std::str::from_utf8(octets) {
+        return texte.into();
+    }
+    let mut detecteur = chardetng::EncodingDetector::new();
+    detecteur.feed(octets, true);
+    let encodage = detecteur.guess(Some(b"ca"), true);
+    encodage.decode_without_bom_handling(octets).0.into_owned()
+}
+
+/// Le séparateur qui donne le plus souvent le même nombre de colonnes (plus d'une) sur les premières lignes.
+fn detecter_separateur(texte: &str) -> u8 {
+    let debut: String = texte.lines().take(30).collect::<Vec<&str>>().join("\n");
+    [b';', b',', b'\t', b'|'].into_iter()
+        .filter_map(|sep| {
+            let mut lecteur = csv::ReaderBuilder::new().delimiter(sep).has_headers(false).flexible(true).from_reader(debut.as_bytes());
+            let mut frequences: HashMap<usize, usize> = HashMap::new();
+            for enr in lecteur.records().map_while(Result::ok) {
+                if enr.len() > 1 {
+                    *frequences.entry(enr.len()).or_default() += 1;
+                }
+            }
+            // (nombre de lignes ayant ce nombre de colonnes, nombre de colonnes)
+            frequences.into_iter().map(|(nb_cols, nb_lignes)| (nb_lignes, nb_cols)).max().map(|score| (score, sep))
+        })
+        .max()
+        .map(|(_, sep)| sep)
+        .unwrap_or(b',')
+}
@@ -0,0 +1,93 @@
+use serde::{Deserialize, Serialize};
+
+use crate::prelude::*;
+
+use super::strategies::NomStrategie;
+
+/// Une catégorie d'âge du camp (ex. Crocus, 5-6 ans).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Categorie {
+    pub nom: String,
+    /// Autres noms sous lesquels la catégorie peut apparaître dans le nom des groupes
+    #[serde(default)]
+    pub alias: Vec<String>,
+    pub age_min: u32,
+    pub age_max: u32,
+    /// Nombre d'enfants visé par sous-groupe
+    pub taille_sous_groupe: usize,
+    /// Nombre d'enfants par animateur. Par défaut, un animateur par sous-groupe.
+    #[serde(default)]
+    pub ratio_animateur: O<usize>,
+    /// Stratégie de formation des sous-groupes. Par défaut, celle de `[sous_groupes]`.
+    #[serde(default)]
+    pub strategie: O<NomStrategie>,
+}
+impl Categorie {
+    fn new(nom: &str, age_min: u32, age_max: u32, taille_sous_groupe: usize) -> Self {
+        Self {
+            nom: nom.into(),
+            alias: Vec::new(),
+            age_min,
+            age_max,
+            taille_sous_groupe,
+            ratio_animateur: None,
+            strategie: None,
+        }
+    }
+
+    pub fn correspond(&self, nom: &str) -> bool {
+        let nom = nom.trim();
+        self.nom.eq_ignore_ascii_case(nom) || self.alias.iter().any(|a| a.trim().eq_ignore_ascii_case(nom))
+    }
+
+    pub fn nb_sous_groupes(&self, nb_enfants: usize) -> usize {
+        nb_enfants.div_ceil(self.taille_sous_groupe.max(1))
+    }
+
+    pub fn nb_animateurs(&self, nb_enfants: usize) -> usize {
+        nb_enfants.div_ceil(self.ratio_animateur.unwrap_or(self.taille_sous_groupe).max(1))
+    }
+}
+
+/// Table des catégories d'âge, lue de la configuration (`[[categories]]`).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Categories(Vec<Categorie>);
+impl Default for Categories {
+    fn default() -> Self {
+        Self(vec![
+            Categorie::new("Crocus", 5, 6, 10),
+            Categorie::new("Balaous", 7, 8, 12),
+            Categorie::new("Basaltes", 9, 12, 15),
+            Categorie::new("12-15 ans", 12, 15, 15),
+        ])
+    }
+}
+impl Categories {
+    pub fn categories(&self) -> impl Iterator<Item = &Categorie> {
+        self.0.iter()
+    }
+
+    /// Trouve une catégorie par son nom ou l'un de ses alias, sans tenir compte de la casse.
+    pub fn get(&self, nom: &str) -> O<&Categorie> {
+        self.0.iter().find(|c| c.correspond(nom))
+    }
+
+    /// Trouve la catégorie qui couvre exactement la tranche d'âge donnée.
+    pub fn selon_ages(&self, min: u32, max: u32) -> O<&Categorie> {
+        self.0.iter().find(|c| c.age_min == min && c.age_max == max)
+    }
+
+    /// Nom de catégorie pour une tranche d'âge: celui de la table s'il y en a un, sinon la tranche elle-même.
+    pub fn guess_category(&self, min: O<u32>, max: O<u32>) -> String {
+        match (min, max) {
+            (Some(mn), Some(mx)) => match self.selon_ages(mn, mx) {
+                Some(cat) => cat.nom.clone(),
+                None => format!("{}-{} ans", mn, mx),
+            },
+            (Some(mn), None) => format!(">{} ans", mn),
+            (None, Some(mx)) => format!("<{} ans", mx),
+            (None, None) => "Tous âge".into(),
+        }
+    }
+}
@@ -1,16 +1,17 @@
 use std::{collections::{hash_map::Values, HashMap, HashSet}, fmt::Display, hash::{DefaultHasher, Hash, Hasher}, iter::{Filter, Map}};
 
 use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
 
 use crate::data::adresse::Adresse;
 use crate::data::tel::Tel;
 use crate::prelude::*;
 use crate::data::email::Email;
 
-use super::{membres::{Membre, MembreID}, RegError};
+use super::{membres::{Membre, MembreID, Modification}, RegError};
 
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default, Serialize, Deserialize)]
 pub struct CompteID(pub u32);
 impl Display for CompteID {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
@@ -22,14 +23,18 @@ lazy_static!{
     pub static ref NULL_COMPTE: Compte = Compte::default();
 }
 
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
 pub struct Compte {
     pub id: CompteID,
     pub mandataire: String,
     pub email: O<Email>,
     pub tel: O<Tel>,
     pub adresse: O<Adresse>,
+    #[serde(serialize_with = "serialiser_trie")]
     pub membres: HashSet<MembreID>,
+    /// Coordonnées modifiées par des listes de présences plus récentes
+    pub historique: Vec<Modification>,
 }
 impl Compte {
     pub fn new(id: CompteID, mandataire: String) -> Self {
@@ -64,6 +69,40 @@ impl Compte {
         self.tel == other.tel &&
         self.adresse == other.adresse
     }
+    /// Même mandataire, sans tenir compte de la casse ni des espaces autour.
+    pub fn meme_mandataire(&self, other: &Self) -> bool {
+        self.mandataire.trim().eq_ignore_ascii_case(other.mandataire.trim())
+    }
+
+    fn champs(&self) -> Vec<(&'static str, String)> {
+        vec![
+            ("Mandataire", self.mandataire.clone()),
+            ("Courriel", print_option(&self.email)),
+            ("Téléphone", print_option(&self.tel)),
+            ("Adresse", print_option(&self.adresse)),
+        ]
+    }
+
+    /// Prend les coordonnées d'une liste de présences plus récente (un parent peut les corriger dans Qidigo)
+    /// et note chaque champ modifié dans l'historique. Une coordonnée absente ne remplace pas une valeur connue.
+    /// Retourne le nombre de champs modifiés.
+    pub fn mettre_a_jour(&mut self, nouveau: &Compte, source: &str) -> usize {
+        let avant = self.champs();
+        self.mandataire = nouveau.mandataire.clone();
+        if nouveau.email.is_some() {
+            self.email = nouveau.email.clone();
+        }
+        if nouveau.tel.is_some() {
+            self.tel = nouveau.tel;
+        }
+        if nouveau.adresse.is_some() {
+            self.adresse = nouveau.adresse.clone();
+        }
+        let modifications = Modification::comparer(avant, self.champs(), source);
+        let nb = modifications.len();
+        self.historique.extend(modifications);
+        nb
+    }
     pub fn get_id_seed(&self) -> u32 {
         let mut hasher = DefaultHasher::new();
         self.mandataire.hash(&mut hasher);
@@ -1,12 +1,12 @@
-use std::{cmp::min, collections::{HashMap, HashSet}, fmt::Display, hash::{DefaultHasher, Hash, Hasher}};
+use std::{collections::{HashMap, HashSet}, fmt::Display, hash::{DefaultHasher, Hash, Hasher}};
 
 use lazy_static::lazy_static;
-use strum::IntoEnumIterator;
+use serde::{Deserialize, Serialize};
 
 use crate::{prelude::*, print::typst::PresenceSDJInfo};
-use super::{membres::{Interet, Membre, MembreID, MembreReg}, RegError};
+use super::{membres::{Interet, MembreID, MembreReg}, sous_groupes::{completer, repartir, ConfigSousGroupes, Explication}, strategies::NomStrategie, RegError};
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default, Serialize, Deserialize)]
 pub struct GroupeID(pub u32);
 impl Display for GroupeID {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
@@ -18,7 +18,8 @@ lazy_static!{
     pub static ref NULL_GROUPE: Groupe = Groupe::default();
 }
 
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
 pub struct Groupe {
     pub id: GroupeID,
     pub saison: O<String>,
@@ -26,12 +27,53 @@ pub struct Groupe {
     pub category: O<String>,
     pub discriminant: O<String>,
     pub semaine: O<String>,
+    /// Premier et dernier jour du groupe, selon la programmation
+    pub debut: O<Date>,
+    pub fin: O<Date>,
     pub activite: O<String>,
+    #[serde(serialize_with = "serialiser_trie")]
     pub participants: HashSet<MembreID>,
     pub sous_groupe: Vec<SousGroupe>,
     pub capacite: O<usize>,
     pub age_min: O<u32>,
     pub age_max: O<u32>,
+    /// Listé dans la feuille "groupes vides" de Qidigo: aucune inscription au moment de l'export
+    pub vide: bool,
+    /// Ce que la programmation et les listes de présences ont dit du groupe
+    pub sources: SourcesGroupe,
+}
+
+/// Tranche d'âge d'un groupe, telle que lue dans une source.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct TrancheAge {
+    pub min: O<u32>,
+    pub max: O<u32>,
+}
+impl TrancheAge {
+    pub fn de(g: &Groupe) -> Self {
+        Self { min: g.age_min, max: g.age_max }
+    }
+}
+impl Display for TrancheAge {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match (self.min, self.max) {
+            (Some(min), Some(max)) => write!(f, "{}-{} ans", min, max),
+            (Some(min), None) => write!(f, "{} ans et plus", min),
+            (None, Some(max)) => write!(f, "{} ans et moins", max),
+            (None, None) => write!(f, "âge inconnu"),
+        }
+    }
+}
+
+/// Sources dans lesquelles un groupe a été lu, pour le rapport de concordance.
+/// Chaque source garde sa tranche d'âge, puisque la programmation remplace celle de la catégorie.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SourcesGroupe {
+    /// Restrictions d'âge de la programmation (sinon la catégorie); `None` si le groupe n'y a pas été lu
+    pub prog: O<TrancheAge>,
+    /// Âges de la catégorie du nom dans les listes de présences; `None` si le groupe n'y a pas été lu
+    pub presence: O<TrancheAge>,
 }
 impl PartialEq for Groupe {
     fn eq(&self, other: &Self) -> bool {
@@ -81,57 +123,113 @@ impl Groupe {
         hasher.finish() as u32
     }
 
-    pub fn mk_sous_groupes(&mut self, nb_sg: usize, membres: &MembreReg) -> Result<(), ()> {
-        let old_sg = self.sous_groupe.clone();
-        self.sous_groupe = Vec::new();
-        if nb_sg == 0 { return Ok(()); }
+    /// Remplace les sous-groupes par `nb_sg` nouveaux sous-groupes (voir [`Groupe::proposer_sous_groupes`]) et explique la répartition.
+    pub fn mk_sous_groupes(&mut self, nb_sg: usize, membres: &MembreReg, config: &ConfigSousGroupes, strategie: NomStrategie) -> Result<Explication, ()> {
+        // membre inexistant: les anciens sous-groupes sont gardés
+        let (sous_groupes, explication) = self.proposer_sous_groupes(nb_sg, membres, config, strategie).map_err(|_| ())?;
+        self.sous_groupe = sous_groupes;
+        Ok(explication)
+    }
 
-        // Faire la liste des candidats
+    /// Les sous-groupes que donnerait la stratégie (voir [`repartir`]), sans remplacer ceux du groupe.
+    pub fn proposer_sous_groupes(&self, nb_sg: usize, membres: &MembreReg, config: &ConfigSousGroupes, strategie: NomStrategie) -> Result<(Vec<SousGroupe>, Explication), RegError<MembreID>> {
         let mut candidats = Vec::new();
-        for mbr in self.participants.iter() {
-            match membres.get(*mbr) {
-                Ok(mbr) => {
-                    candidats.push(mbr);
-                },
-                Err(_e) => { // membre inexistant
-                    self.sous_groupe = old_sg;
-                    return Err(())
-                }, 
-            }
+        for mid in self.participants.iter() {
+            candidats.push(membres.get(*mid)?);
+        }
+        candidats.sort_by_key(|m| m.id);
+        if nb_sg == 0 {
+            return Ok((Vec::new(), Explication { strategie, ..Explication::default() }));
         }
 
-        // nombre de participants par sous_groupe
-        let sg_size = ((candidats.len() as f32)/(nb_sg as f32)).ceil() as usize;
+        // les sous-groupes verrouillés (ou avec des membres verrouillés) sont gardés, même s'ils sont de trop
+        let nb_sg = self.sous_groupe.iter().rposition(SousGroupe::a_verrous).map_or(nb_sg, |i| nb_sg.max(i + 1));
+        let (sous_groupes, explication) = repartir(&candidats, nb_sg, self.debut.unwrap_or_else(today), config, strategie, &self.sous_groupe);
+        let sous_groupes = sous_groupes.into_iter().enumerate()
+            .map(|(i, sg)| SousGroupe { groupe: self.id, disc: i as u32 + 1, ..sg })
+            .collect();
+        Ok((sous_groupes, explication))
+    }
+
+    /// Garde les sous-groupes existants: retire les enfants qui ne sont plus inscrits et place seulement
+    /// les nouveaux inscrits. Retourne l'explication et les sous-groupes dont la liste a changé.
+    pub fn completer_sous_groupes(&mut self, membres: &MembreReg, config: &ConfigSousGroupes, strategie: NomStrategie) -> Result<(Explication, Vec<ChangementSousGroupe>), RegError<MembreID>> {
+        let mut candidats = Vec::new();
+        for mid in self.participants.iter() {
+            candidats.push(membres.get(*mid)?);
+        }
+        candidats.sort_by_key(|m| m.id);
+        let (sous_groupes, explication) = completer(&candidats, self.debut.unwrap_or_else(today), config, strategie, &self.sous_groupe);
+        let anciens = std::mem::replace(&mut self.sous_groupe, sous_groupes.into_iter().enumerate()
+            .map(|(i, sg)| SousGroupe { groupe: self.id, disc: i as u32 + 1, ..sg })
+            .collect());
 
-        println!("Forme {} sous-groupe de {} pour {}", nb_sg, sg_size, self.short_desc());
+        let noms = |mids: Vec<&MembreID>| {
+            let mut mbrs: Vec<_> = mids.into_iter().filter_map(|mid| membres.get(*mid).ok()).collect();
+            mbrs.sort_by(|a, b| a.cmp_nom(b));
+            mbrs.iter().map(|m| format!("{} {}", m.prenom, m.nom)).collect::<Vec<String>>()
+        };
+        let changements = self.sous_groupe.iter().zip(anciens.iter())
+            .map(|(sg, ancien)| ChangementSousGroupe {
+                titre: sg.titre(),
+                ajoutes: noms(sg.participants.difference(&ancien.participants).collect()),
+                retires: noms(ancien.participants.difference(&sg.participants).collect()),
+            })
+            .filter(|c| !c.est_vide())
+            .collect();
+        Ok((explication, changements))
+    }
 
-        // création des sous groupes
-        for disc in 1..=nb_sg {
-            let mut sg = mk_sous_groupe(&candidats, sg_size);
-            sg.groupe = self.id;
-            sg.disc = disc as u32;
-            // retirer des candidats les membres ajoutés au sg
-            candidats.retain(|mbr| !sg.contains(*mbr));
+    pub fn get_sous_groupe_mut(&mut self, disc: u32) -> Option<&mut SousGroupe> {
+        self.sous_groupe.iter_mut().find(|sg| sg.disc == disc)
+    }
 
-            self.sous_groupe.push(sg);
+    /// Déplace un participant dans le sous-groupe `disc`. Un membre verrouillé le reste dans son nouveau sous-groupe.
+    pub fn deplacer_membre(&mut self, mid: MembreID, disc: u32) -> bool {
+        if !self.participants.contains(&mid) || !self.sous_groupe.iter().any(|sg| sg.disc == disc) {
+            return false;
         }
-
-        // Ajouter au sg les candidats restants
+        let mut verrouille = false;
         for sg in self.sous_groupe.iter_mut() {
-            while sg.participants.len() < sg_size && candidats.len() > 0 {
-                sg.participants.insert(candidats.pop().unwrap().id);
-            }
+            sg.participants.remove(&mid);
+            verrouille |= sg.membres_verrouilles.remove(&mid);
         }
-
-        // S'il reste encore des candidats après ça, on envoie une erreur...
-        if !candidats.is_empty() {
-            self.sous_groupe = old_sg;
-            return Err(());
+        let sg = self.get_sous_groupe_mut(disc).unwrap();
+        sg.participants.insert(mid);
+        if verrouille {
+            sg.membres_verrouilles.insert(mid);
         }
+        true
+    }
 
+    /// Échange deux participants de sous-groupes différents.
+    pub fn echanger_membres(&mut self, a: MembreID, b: MembreID) -> Result<(), ErreurEchange> {
+        let sous_groupe = |mid: MembreID| {
+            if !self.participants.contains(&mid) {
+                return Err(ErreurEchange::NonParticipant(mid));
+            }
+            self.get_sous_groupe_for(mid).map(|sg| sg.disc).ok_or(ErreurEchange::SansSousGroupe(mid))
+        };
+        let (sg_a, sg_b) = (sous_groupe(a)?, sous_groupe(b)?);
+        if sg_a == sg_b {
+            return Err(ErreurEchange::MemeSousGroupe);
+        }
+        self.deplacer_membre(a, sg_b);
+        self.deplacer_membre(b, sg_a);
         Ok(())
     }
 
+    /// Verrouille ou déverrouille un participant dans son sous-groupe. Retourne le nouvel état.
+    pub fn basculer_verrou_membre(&mut self, mid: MembreID) -> O<bool> {
+        let sg = self.sous_groupe.iter_mut().find(|sg| sg.participants.contains(&mid))?;
+        if sg.membres_verrouilles.remove(&mid) {
+            Some(false)
+        } else {
+            sg.membres_verrouilles.insert(mid);
+            Some(true)
+        }
+    }
+
     pub fn desc(&self) -> String {
         format!("{}: {} | {} | {} | Sem. {} - {}", 
             print_option(&self.saison),
@@ -160,6 +258,21 @@ impl Groupe {
         }
     }
 
+    /// Période du groupe en toutes lettres, p. ex. "du 30 juin au 4 juillet" ou "du 7 au 11 juillet".
+    pub fn periode(&self) -> O<String> {
+        use chrono::Datelike;
+        let (debut, fin) = (self.debut?, self.fin?);
+        if debut == fin {
+            return Some(format!("le {}", jour_mois(debut)));
+        }
+        let debut = if debut.month() == fin.month() && debut.year() == fin.year() && debut.day() != 1 {
+            debut.day().to_string()
+        } else {
+            jour_mois(debut)
+        };
+        Some(format!("du {} au {}", debut, jour_mois(fin)))
+    }
+
     pub fn estime_cap(&self) -> usize {
         match &self.capacite {
             None => self.participants.len(),
@@ -175,18 +288,6 @@ impl Groupe {
         }
     }
 
-    pub fn guess_category(min: O<u32>, max: O<u32>) -> String {
-        match (min, max) {
-            (Some(5), Some(6)) => "Crocus".into(),
-            (Some(7), Some(8)) => "Balaous".into(),
-            (Some(9), Some(12)) => "Basaltes".into(),
-            (Some(mn), Some(mx)) => format!("{}-{} ans", mn, mx),
-            (Some(mn), None) => format!(">{} ans", mn),
-            (None, Some(mx)) => format!("<{} ans", mx),
-            (None, None) => "Tous âge".into(),
-        }
-    }
-
     pub fn get_sous_groupe_for(&self, mid: MembreID) -> Option<&SousGroupe> {
         for sg in self.sous_groupe.iter() {
             if sg.participants.contains(&mid) {
@@ -252,92 +353,38 @@ impl GroupeReg {
     pub fn len(&self) -> usize {
         self.reg.len()
     }
-}
-
-pub fn rank_points(rank: usize) -> u32 {
-    match rank {
-        x if x < 1 => 8,
-        1 => 4,
-        2 => 2,
-        _ => 0,
-    }
-}
 
-pub fn mk_sous_groupe(membres: &[&Membre], nb_participants: usize) -> SousGroupe {
-    // 1. Trouver si le groupe à un profil
-    // Il faut premièrement cumuler tous les points des interêts des membres et voir si le plus grand couvre un certain pourcentage
-    let profil ={
-        let mut pts: HashMap<Interet, u32> = HashMap::new();
-        for mbr in membres {
-            for (rank, interet) in mbr.interets.iter().enumerate() {
-                if let Some(it) = interet {
-                    *pts.entry(*it).or_insert(0) += rank_points(rank);
-                }
-            }
-        }
-        let pts_sum = pts.values().sum::<u32>();
-        let mut percent = HashMap::new();
-        for (it, pt) in pts.iter() {
-            percent.insert(it, *pt as f32 / pts_sum as f32);
-        }
-        let mut pro = None;
-        for it in Interet::iter() {
-            if let Some(p) = percent.get(&it) {
-                if *p > 0.4 {
-                    pro = Some(it);
-                    break
-                }
+    /// Donne aux groupes sans dates celles d'un groupe de la programmation de la même saison, activité et semaine
+    /// (du même site de préférence). Les listes de présences n'ont que le numéro de semaine; un groupe dont le nom
+    /// ne donne pas l'activité prend les dates de n'importe quelle activité de la semaine, si elles concordent.
+    /// Retourne le nombre de groupes complétés.
+    pub fn completer_dates(&mut self) -> usize {
+        let sources: Vec<(&Groupe, Date, Date)> = self.reg.values()
+            .filter_map(|g| Some((g, g.debut?, g.fin?)))
+            .filter(|(g, _, _)| g.semaine.is_some())
+            .collect();
+        let mut completes = Vec::new();
+        for g in self.reg.values().filter(|g| g.debut.is_none() && g.fin.is_none() && g.semaine.is_some()) {
+            // deux groupes correspondants avec des dates différentes: on ne peut pas choisir
+            let dates = |meme_site: bool| {
+                let mut dates = sources.iter()
+                    .filter(|(s, _, _)| s.saison == g.saison && s.semaine == g.semaine)
+                    .filter(|(s, _, _)| g.activite.is_none() || s.activite == g.activite)
+                    .filter(|(s, _, _)| !meme_site || s.site == g.site)
+                    .map(|(_, debut, fin)| (*debut, *fin));
+                let premieres = dates.next()?;
+                dates.all(|d| d == premieres).then_some(premieres)
+            };
+            if let Some(d) = dates(true).or_else(|| dates(false)) {
+                completes.push((g.id, d));
             }
         }
-        pro
-    };
-    // 2. Trouver les membres qui vont dans le groupe
-    // 2 situations: a. on a un profil, on prend les membres qui ont cet interêt
-    //               b. on a pas de profil, donc on regroupe par age
-    let mut part = HashSet::new();
-    match profil {
-        None => {
-            let mut parts = Vec::from(membres);
-            parts.sort_by(|a, b| a.naissance.cmp(&b.naissance));
-            for i in 0..min(nb_participants, parts.len()) {
-                part.insert(parts[i].id);
-            }
-        },
-        Some(pro) => {
-            // Dans ce cas, on veut rajouter les membres qui ont l'interet dans leur premier emplacement, puis ceux dans qui l'ont dans le deuxième, jusqu'à ce qu'on ait le bon nombre
-            // On ne rajoute pas ceux qui l'ont dans le troisième ou quatrième
-            let filter_fn = filter_by_interet(pro);
-            let sort_fn = sort_by_interet(pro);
-            let mut parts: Vec<&Membre> = membres.iter().filter(|mbr| filter_fn(mbr)).map(|mbr| *mbr).collect();
-            parts.sort_by(|a, b| sort_fn(a, b));
-            for i in 0..(nb_participants.min(parts.len())) {
-                part.insert(parts[i].id);
-            }
-        },
-    }
-
-    SousGroupe { 
-        profil,
-        participants: part,
-        ..SousGroupe::default()
-     }
-}
-
-fn filter_by_interet(interet: Interet) -> impl Fn(&Membre) -> bool {
-    move |mbr: &Membre| {
-        mbr.interets[0] == Some(interet) || mbr.interets[1] == Some(interet)
-    }
-}
-fn sort_by_interet(interet: Interet) -> impl Fn(&Membre, &Membre) -> std::cmp::Ordering {
-    move |mbr1: &Membre, mbr2: &Membre| {
-        let i1 = mbr1.interets.iter().position(|int| *int == Some(interet));
-        let i2 = mbr2.interets.iter().position(|int| *int == Some(interet));
-        match (i1, i2) {
-            (Some(i1), Some(i2)) => i1.cmp(&i2),
-            (Some(_), None) => std::cmp::Ordering::Less,
-            (None, Some(_)) => std::cmp::Ordering::Greater,
-            (None, None) => std::cmp::Ordering::Equal,
+        for (gid, (debut, fin)) in completes.iter() {
+            let g = self.reg.get_mut(gid).unwrap();
+            g.debut = Some(*debut);
+            g.fin = Some(*fin);
         }
+        completes.len()
     }
 }
 
@@ -360,17 +407,59 @@ impl<'a, Src: Iterator<Item=&'a mut Groupe>> Iterator for GroupeIterMut<'a, Src>
     
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Default)]
+/// Enfants ajoutés ou retirés d'un sous-groupe: sa liste de présences est à réimprimer.
+#[derive(Debug, Clone, Default)]
+pub struct ChangementSousGroupe {
+    pub titre: String,
+    pub ajoutes: Vec<String>,
+    pub retires: Vec<String>,
+}
+impl ChangementSousGroupe {
+    pub fn est_vide(&self) -> bool {
+        self.ajoutes.is_empty() && self.retires.is_empty()
+    }
+}
+
+/// Pourquoi deux participants n'ont pu être échangés (voir [`Groupe::echanger_membres`]).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErreurEchange {
+    /// Les deux sont dans le même sous-groupe
+    MemeSousGroupe,
+    /// Ce membre est dans un sous-groupe, mais n'est plus inscrit au groupe
+    NonParticipant(MembreID),
+    /// Ce participant n'est dans aucun sous-groupe
+    SansSousGroupe(MembreID),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(default)]
 pub struct SousGroupe {
     pub profil: O<Interet>,
     pub disc: u32,
+    #[serde(serialize_with = "serialiser_trie")]
     pub participants: HashSet<MembreID>,
     pub groupe: GroupeID,
     pub animateur: O<String>,
+    /// Nom donné à la main
+    pub nom: O<String>,
+    /// Gardé tel quel quand les sous-groupes sont refaits
+    pub verrouille: bool,
+    /// Participants gardés dans ce sous-groupe quand les sous-groupes sont refaits
+    #[serde(serialize_with = "serialiser_trie")]
+    pub membres_verrouilles: HashSet<MembreID>,
 }
-
 impl SousGroupe {
-    fn contains(&self, mbr: &Membre) -> bool {
-        self.participants.iter().any(|mid| mbr.id == *mid)
+    /// "Sous-groupe 2 Les castors (Sport)"
+    pub fn titre(&self) -> String {
+        format!("Sous-groupe {}{} ({})",
+            self.disc,
+            self.nom.as_ref().map(|n| format!(" {}", n)).unwrap_or_default(),
+            self.profil.map_or("sans profil".into(), |p| p.to_string()),
+        )
+    }
+
+    /// Le sous-groupe, ou l'un de ses participants, est-il verrouillé?
+    pub fn a_verrous(&self) -> bool {
+        self.verrouille || !self.membres_verrouilles.is_empty()
     }
-}
\ No newline at end of file
+}
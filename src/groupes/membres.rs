@@ -1,12 +1,13 @@
 use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
 use strum_macros::EnumIter;
 
-use crate::{data::{tel::Tel, Genre, ParsingError, Taille}, prelude::*};
+use crate::{data::{tel::Tel, BoolJustifie, Genre, ParsingError, Taille}, prelude::*};
 use std::{cmp::Ordering, collections::HashMap, fmt::Display, hash::{DefaultHasher, Hash, Hasher}, str::FromStr};
 
 use super::{comptes::CompteID, fiche_sante::FicheSante, RegError};
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default, Serialize, Deserialize)]
 pub struct MembreID(pub u32);
 impl Display for MembreID {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
@@ -18,7 +19,8 @@ lazy_static!{
     pub static ref NULL_MEMBRE: Membre = Membre::default();
 }
 
-#[derive(Clone, Debug, Default)]
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
 pub struct Membre {
     pub id: MembreID,
     pub nom: String,
@@ -35,6 +37,8 @@ pub struct Membre {
     pub taille: O<Taille>,
     pub commentaire: O<String>,
     pub compte: O<CompteID>,
+    /// Champs modifiés par des listes de présences plus récentes
+    pub historique: Vec<Modification>,
 }
 impl PartialEq for Membre {
     fn eq(&self, other: &Self) -> bool {
@@ -53,10 +57,14 @@ impl Membre {
         }
     }
     pub fn equiv(&self, other: &Self) -> bool {
+        self.meme_enfant(other) &&
+        self.compte == other.compte
+    }
+    /// Même nom, prénom et date de naissance, peu importe le compte.
+    pub fn meme_enfant(&self, other: &Self) -> bool {
         self.nom == other.nom &&
         self.prenom == other.prenom &&
-        self.naissance == other.naissance &&
-        self.compte == other.compte
+        self.naissance == other.naissance
     }
     pub fn get_id_seed(&self) -> u32 {
         let mut hasher = DefaultHasher::new();
@@ -73,12 +81,159 @@ impl Membre {
             c
         }
     }
+
+    /// Valeur affichable de chaque champ que les listes de présences peuvent modifier.
+    fn champs(&self) -> Vec<(&'static str, String)> {
+        fn texte<T: ToString>(o: &O<T>) -> String {
+            o.as_ref().map(|t| t.to_string()).unwrap_or("-".into())
+        }
+        fn texte_justifie(o: &O<BoolJustifie>) -> String {
+            match o {
+                None => "-".into(),
+                Some(bj) => format!("{}{}", if bj.reponse {"Oui"} else {"Non"}, bj.justification.as_ref().map(|j| format!(", {}", j)).unwrap_or_default()),
+            }
+        }
+        fn texte_contact(o: &O<Contact>) -> String {
+            o.as_ref().map(|c| format!("{} ({}) {}", c.nom, texte(&c.lien), texte(&c.tel))).unwrap_or("-".into())
+        }
+        let fiche = &self.fiche_sante;
+        let med = &fiche.auth_medicaments;
+        vec![
+            ("Genre", texte(&self.genre)),
+            ("Taille", texte(&self.taille)),
+            ("Intérêts", self.interets.iter().map(texte).collect::<Vec<String>>().join(", ")),
+            ("Contact d'urgence 1", texte_contact(&self.contacts[0])),
+            ("Contact d'urgence 2", texte_contact(&self.contacts[1])),
+            ("Accompagnement", texte(&self.accompagnement)),
+            ("Quitte avec", self.quitte.avec.join(", ")),
+            ("Mot de passe", texte(&self.quitte.mdp)),
+            ("Piscine - partage aux sauveteurs", texte(&self.piscine.partage)),
+            ("Piscine - VFI", texte(&self.piscine.vfi)),
+            ("Piscine - tête sous l'eau", texte(&self.piscine.tete_sous_eau)),
+            ("Autorisation photo", texte(&self.auth_photo)),
+            ("Commentaire", texte(&self.commentaire)),
+            ("Allergies", fiche.allergies.join(", ")),
+            ("Maladies", fiche.maladies.join(", ")),
+            ("Autorisation de soigner", texte(&fiche.auth_soins)),
+            ("Problèmes de comportement", texte_justifie(&fiche.probleme_comportement)),
+            ("Carte d'assurance maladie", texte(&fiche.cam)),
+            ("Prise de médicament", texte_justifie(&fiche.prise_med)),
+            ("Med Sirop pour la toux", texte(&med.sirop_toux)),
+            ("Med Antiémétique", texte(&med.anti_emetique)),
+            ("Med Ibuprofène", texte(&med.ibuprofene)),
+            ("Med Anti-inflammatoire", texte(&med.anti_inflamatoire)),
+            ("Med Antibiotique", texte(&med.anti_biotique)),
+            ("Med Acétaminophène", texte(&med.acetaminophene)),
+        ]
+    }
+
+    /// Prend les informations d'une liste de présences plus récente et note chaque champ modifié dans l'historique.
+    /// Une nouvelle valeur remplace toujours l'ancienne. Une valeur absente ne l'efface que si le champ est dans `lus`:
+    /// sa cellule était vide (pas invalide) ou, pour une liste, toutes ses colonnes étaient dans la feuille.
+    /// Retourne le nombre de champs modifiés.
+    pub fn mettre_a_jour(&mut self, nouveau: Membre, source: &str, lus: &ChampsLus) -> usize {
+        fn maj_liste(ancienne: &mut Vec<String>, nouvelle: Vec<String>, lue: bool) {
+            if lue {
+                *ancienne = nouvelle;
+            }
+        }
+        fn maj<T>(ancien: &mut O<T>, nouveau: O<T>, lu: bool) {
+            if lu || nouveau.is_some() {
+                *ancien = nouveau;
+            }
+        }
+        let avant = self.champs();
+
+        maj(&mut self.genre, nouveau.genre, lus.contient("Genre"));
+        maj(&mut self.taille, nouveau.taille, lus.contient("Taille"));
+        if lus.contient("Intérêts") || nouveau.interets.iter().any(Option::is_some) {
+            self.interets = nouveau.interets;
+        }
+        let [c1, c2] = nouveau.contacts;
+        maj(&mut self.contacts[0], c1, lus.contient("Contact d'urgence 1"));
+        maj(&mut self.contacts[1], c2, lus.contient("Contact d'urgence 2"));
+        maj(&mut self.accompagnement, nouveau.accompagnement, lus.contient("Accompagnement"));
+        maj_liste(&mut self.quitte.avec, nouveau.quitte.avec, lus.contient("Quitte avec"));
+        maj(&mut self.quitte.mdp, nouveau.quitte.mdp, lus.contient("Mot de passe"));
+        maj(&mut self.piscine.partage, nouveau.piscine.partage, lus.contient("Piscine - partage aux sauveteurs"));
+        maj(&mut self.piscine.vfi, nouveau.piscine.vfi, lus.contient("Piscine - VFI"));
+        maj(&mut self.piscine.tete_sous_eau, nouveau.piscine.tete_sous_eau, lus.contient("Piscine - tête sous l'eau"));
+        maj(&mut self.auth_photo, nouveau.auth_photo, lus.contient("Autorisation photo"));
+        maj(&mut self.commentaire, nouveau.commentaire, lus.contient("Commentaire"));
+
+        let fiche = *nouveau.fiche_sante;
+        maj_liste(&mut self.fiche_sante.allergies, fiche.allergies, lus.contient("Allergies"));
+        maj_liste(&mut self.fiche_sante.maladies, fiche.maladies, lus.contient("Maladies"));
+        maj(&mut self.fiche_sante.auth_soins, fiche.auth_soins, lus.contient("Autorisation de soigner"));
+        maj(&mut self.fiche_sante.probleme_comportement, fiche.probleme_comportement, lus.contient("Problèmes de comportement"));
+        maj(&mut self.fiche_sante.cam, fiche.cam, lus.contient("Carte d'assurance maladie"));
+        maj(&mut self.fiche_sante.prise_med, fiche.prise_med, lus.contient("Prise de médicament"));
+        let med = &mut self.fiche_sante.auth_medicaments;
+        maj(&mut med.sirop_toux, fiche.auth_medicaments.sirop_toux, lus.contient("Med Sirop pour la toux"));
+        maj(&mut med.anti_emetique, fiche.auth_medicaments.anti_emetique, lus.contient("Med Antiémétique"));
+        maj(&mut med.ibuprofene, fiche.auth_medicaments.ibuprofene, lus.contient("Med Ibuprofène"));
+        maj(&mut med.anti_inflamatoire, fiche.auth_medicaments.anti_inflamatoire, lus.contient("Med Anti-inflammatoire"));
+        maj(&mut med.anti_biotique, fiche.auth_medicaments.anti_biotique, lus.contient("Med Antibiotique"));
+        maj(&mut med.acetaminophene, fiche.auth_medicaments.acetaminophene, lus.contient("Med Acétaminophène"));
+
+        let modifications = Modification::comparer(avant, self.champs(), source);
+        let nb = modifications.len();
+        self.historique.extend(modifications);
+        nb
+    }
+
+    /// Champs dont la valeur diffère dans `autre`: (champ, valeur ici, valeur dans `autre`).
+    pub fn differences(&self, autre: &Membre) -> Vec<Difference> {
+        self.champs().into_iter().zip(autre.champs())
+            .filter(|((_, a), (_, b))| a != b)
+            .map(|((champ, a), (_, b))| (champ, a, b))
+            .collect()
+    }
+}
+
+/// Champs (tels que nommés dans l'historique) qu'une feuille de présences a lus pour un membre,
+/// même vides: leur valeur remplace l'ancienne lors d'une mise à jour.
+#[derive(Debug, Clone, Default)]
+pub struct ChampsLus(pub Vec<&'static str>);
+impl ChampsLus {
+    pub fn contient(&self, champ: &str) -> bool {
+        self.0.contains(&champ)
+    }
+}
+
+/// (champ, avant, après)
+pub type Difference = (&'static str, String, String);
+
+/// Un champ modifié lors de la lecture d'une liste de présences.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Modification {
+    pub date: Date,
+    /// Fichier d'où vient la nouvelle valeur
+    pub source: String,
+    pub champ: String,
+    pub avant: String,
+    pub apres: String,
+}
+impl Modification {
+    /// Les champs dont la valeur a changé, datés d'aujourd'hui.
+    pub fn comparer(avant: Vec<(&'static str, String)>, apres: Vec<(&'static str, String)>, source: &str) -> Vec<Self> {
+        let date = today();
+        avant.into_iter().zip(apres)
+            .filter(|((_, ancien), (_, valeur))| ancien != valeur)
+            .map(|((champ, ancien), (_, valeur))| Self {
+                date,
+                source: source.into(),
+                champ: champ.into(),
+                avant: ancien,
+                apres: valeur,
+            })
+            .collect()
+    }
 }
 
 
 pub type Interets = [O<Interet>; 4];
-pub static INTERETS_POINTS: [u32; 4] = [8, 4, 2, 0];
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, EnumIter, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, EnumIter, Hash, Serialize, Deserialize)]
 pub enum Interet {
     Science,
     Sport,
@@ -118,20 +273,20 @@ impl Interet {
     }
 }
 
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct Contact {
     pub nom: String,
     pub tel: O<Tel>,
     pub lien: O<String>,
 }
 
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct Quitte {
     pub avec: Vec<String>,
     pub mdp: O<String>,
 }
 
-#[derive(Debug, Clone, Copy, Default)]
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
 pub struct Piscine {
     pub partage: O<bool>,
     pub vfi: O<bool>,
@@ -209,4 +364,47 @@ impl<'a, Src: Iterator<Item=&'a mut Membre>> Iterator for MembreIterMut<'a, Src>
         self.0.next()
     }
     
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn enfant() -> Membre {
+        let mut m = Membre::new(MembreID(1), "Tremblay".into(), "Léa".into(), Date::from_ymd_opt(2016, 3, 2).unwrap());
+        m.fiche_sante.allergies = vec!["Arachides".into()];
+        m.quitte.avec = vec!["Parent".into(), "Seul".into()];
+        m.contacts[1] = Some(Contact {nom: "Marc Roy".into(), tel: None, lien: Some("Oncle".into())});
+        m.commentaire = Some("Porte des lunettes".into());
+        m
+    }
+
+    fn nouveau() -> Membre {
+        Membre::new(MembreID(1), "Tremblay".into(), "Léa".into(), Date::from_ymd_opt(2016, 3, 2).unwrap())
+    }
+
+    #[test]
+    fn champs_lus_vides_effacent_et_sont_notes() {
+        let mut m = enfant();
+        let lus = ChampsLus(vec!["Allergies", "Quitte avec", "Contact d'urgence 2", "Commentaire"]);
+        let nb = m.mettre_a_jour(nouveau(), "b.xlsx", &lus);
+        assert_eq!(nb, 4);
+        assert!(m.fiche_sante.allergies.is_empty());
+        assert!(m.quitte.avec.is_empty());
+        assert!(m.contacts[1].is_none());
+        assert!(m.commentaire.is_none());
+        let allergies = m.historique.iter().find(|h| h.champ == "Allergies").unwrap();
+        assert_eq!((allergies.avant.as_str(), allergies.apres.as_str()), ("Arachides", ""));
+    }
+
+    #[test]
+    fn champs_non_lus_gardent_leur_valeur() {
+        let mut m = enfant();
+        let nb = m.mettre_a_jour(nouveau(), "b.xlsx", &ChampsLus::default());
+        assert_eq!(nb, 0);
+        assert_eq!(m.fiche_sante.allergies, vec!["Arachides".to_string()]);
+        assert_eq!(m.quitte.avec.len(), 2);
+        assert!(m.contacts[1].is_some());
+        assert!(m.commentaire.is_some());
+        assert!(m.historique.is_empty());
+    }
+}
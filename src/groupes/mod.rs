@@ -1,8 +1,11 @@
+pub mod categories;
 
 pub mod comptes;
 pub mod fiche_sante;
 pub mod groupes;
 pub mod membres;
+pub mod sous_groupes;
+pub mod strategies;
 
 #[derive(Debug)]
 pub enum RegError<Key> {
@@ -0,0 +1,782 @@
+use std::collections::{BTreeMap, HashMap};
+
+use console::{style, Term};
+use serde::{Deserialize, Serialize};
+
+use crate::{data::Genre, prelude::*};
+use super::{comptes::CompteID, groupes::SousGroupe, membres::{Interet, Membre, MembreID}, strategies::{NomStrategie, Strategie}};
+
+/// Nombre maximal de passes d'amélioration par déplacements et échanges.
+const PASSES_MAX: usize = 200;
+/// Amélioration minimale pour qu'un déplacement soit fait, pour ne pas tourner en rond sur des arrondis.
+const EPSILON: f32 = 1e-4;
+
+/// Ce qu'il faut faire des enfants d'un même compte inscrits au même groupe.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Fratries {
+    /// Dans le même sous-groupe
+    #[default]
+    Ensemble,
+    /// Dans des sous-groupes différents
+    Separees,
+    /// Sans contrainte
+    Indifferent,
+}
+
+/// Règles de formation des sous-groupes (`[sous_groupes]` dans la configuration).
+/// Les contraintes (taille, fratries, enfants à surveiller) sont toujours respectées si c'est possible;
+/// les poids règlent l'importance de chaque objectif (intérêts, mixité, âges, amitiés) les uns par rapport aux autres.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ConfigSousGroupes {
+    /// Stratégie des catégories qui n'en choisissent pas une
+    pub strategie: NomStrategie,
+    pub fratries: Fratries,
+    /// Écart permis entre la taille d'un sous-groupe et la taille équilibrée (0: au plus un enfant de différence)
+    pub ecart_taille: usize,
+    /// Importance de placer les enfants dans un sous-groupe dont le profil est l'un de leurs premiers intérêts
+    pub poids_interets: f32,
+    /// Importance d'avoir la même proportion de chaque genre dans chaque sous-groupe
+    pub poids_genres: f32,
+    /// Importance de regrouper les enfants d'âges proches
+    pub poids_ages: f32,
+    /// Importance de garder les amis ensemble (stratégie "amities")
+    pub poids_amities: f32,
+    /// Points d'un intérêt choisi en 1er, 2e, 3e et 4e
+    pub points_interets: [u32; 4],
+    /// Part minimale des points d'intérêt du groupe pour qu'un intérêt donne son profil à un sous-groupe
+    pub seuil_profil: f32,
+}
+impl Default for ConfigSousGroupes {
+    fn default() -> Self {
+        Self {
+            strategie: NomStrategie::Interets,
+            fratries: Fratries::Ensemble,
+            ecart_taille: 0,
+            poids_interets: 1.0,
+            poids_genres: 1.0,
+            poids_ages: 1.0,
+            poids_amities: 1.0,
+            points_interets: [8, 4, 2, 0],
+            seuil_profil: 0.4,
+        }
+    }
+}
+
+/// Pourquoi les sous-groupes sont ce qu'ils sont: contraintes appliquées, contraintes qui n'ont pas pu l'être
+/// et résumé de chaque sous-groupe.
+#[derive(Debug, Clone, Default)]
+pub struct Explication {
+    pub strategie: NomStrategie,
+    pub contraintes: Vec<String>,
+    pub relachees: Vec<String>,
+    pub sous_groupes: Vec<String>,
+    /// Nombre de déplacements et d'échanges qui ont amélioré la première répartition
+    pub ameliorations: usize,
+}
+impl Explication {
+    pub fn afficher(&self, term: &Term) {
+        let _ = term.write_line(&format!("\tStratégie: {}", self.strategie));
+        for c in self.contraintes.iter() {
+            let _ = term.write_line(&format!("\t{}", c));
+        }
+        for c in self.relachees.iter() {
+            let _ = term.write_line(&format!("\t{}", style(format!("Contrainte relâchée: {}", c)).yellow()));
+        }
+        if self.ameliorations > 0 {
+            let _ = term.write_line(&format!("\t{} déplacement(s) ou échange(s) après la première répartition", self.ameliorations));
+        }
+        for sg in self.sous_groupes.iter() {
+            let _ = term.write_line(&format!("\t\t{}", sg));
+        }
+    }
+}
+
+/// Un participant à placer.
+pub struct Candidat<'a> {
+    pub membre: &'a Membre,
+    /// Âge en années au début du groupe
+    pub age: f32,
+    /// Besoin d'accompagnement ou problème de comportement: à répartir entre les sous-groupes
+    pub signale: bool,
+}
+impl<'a> Candidat<'a> {
+    fn new(membre: &'a Membre, reference: Date) -> Self {
+        let comportement = membre.fiche_sante.probleme_comportement.as_ref().is_some_and(|p| p.reponse);
+        Self {
+            membre,
+            age: age_au(membre.naissance, reference),
+            signale: membre.accompagnement == Some(true) || comportement,
+        }
+    }
+
+    /// 0 si le profil est son premier intérêt, 1 s'il ne l'a pas choisi (0,5 sans intérêts connus).
+    pub fn cout_interet(&self, profil: Interet, points: &[u32; 4]) -> f32 {
+        let interets = &self.membre.interets;
+        if interets.iter().all(Option::is_none) || points[0] == 0 {
+            return 0.5;
+        }
+        let pts = interets.iter().position(|i| *i == Some(profil)).map_or(0, |rang| points[rang]);
+        1.0 - pts as f32 / points[0] as f32
+    }
+
+    pub fn genre(&self) -> usize {
+        match self.membre.genre {
+            Some(Genre::Homme) => 0,
+            Some(Genre::Femme) => 1,
+            Some(Genre::Autre) => 2,
+            None => 3,
+        }
+    }
+}
+
+/// Répartit les membres en `nb_sg` sous-groupes (sans numéro ni groupe) selon la stratégie, et explique le résultat.
+/// Les verrous des sous-groupes `anciens` (au même rang) sont respectés: un sous-groupe verrouillé garde
+/// ses participants, son profil et son nom sans en recevoir de nouveaux, et un membre verrouillé reste dans son sous-groupe.
+/// Le nom donné à la main à un sous-groupe est gardé même sans verrou.
+///
+/// 1. La stratégie donne un profil à chaque sous-groupe (ou aucun).
+/// 2. Les membres verrouillés sont placés, puis les fratries (à garder ensemble ou à séparer) et les enfants à surveiller sont placés en premier, puis les autres
+///    du plus jeune au plus vieux, chacun dans le sous-groupe où il coûte le moins.
+/// 3. Des enfants (ou des fratries) sont déplacés ou échangés tant que la répartition s'améliore.
+///
+/// Le coût d'un sous-groupe est celui de la stratégie (voir [`Strategie::cout`]).
+pub fn repartir(membres: &[&Membre], nb_sg: usize, reference: Date, config: &ConfigSousGroupes, strategie: NomStrategie, anciens: &[SousGroupe]) -> (Vec<SousGroupe>, Explication) {
+    repartir_avec(membres, nb_sg, reference, config, strategie, anciens, false)
+}
+
+/// Place seulement les nouveaux membres dans les sous-groupes `anciens`, qui gardent leurs participants
+/// (sauf ceux qui ne sont plus dans `membres`), leur profil, leur nom et leur animateur.
+/// Un nouveau membre rejoint sa fratrie si elle doit rester ensemble; sinon il va dans le sous-groupe où il coûte
+/// le moins (intérêts, âges, genres), sans dépasser la taille permise si c'est possible.
+pub fn completer(membres: &[&Membre], reference: Date, config: &ConfigSousGroupes, strategie: NomStrategie, anciens: &[SousGroupe]) -> (Vec<SousGroupe>, Explication) {
+    repartir_avec(membres, anciens.len(), reference, config, strategie, anciens, true)
+}
+
+fn repartir_avec(membres: &[&Membre], nb_sg: usize, reference: Date, config: &ConfigSousGroupes, nom_strategie: NomStrategie, anciens: &[SousGroupe], garder: bool) -> (Vec<SousGroupe>, Explication) {
+    let candidats: Vec<Candidat> = membres.iter().map(|m| Candidat::new(m, reference)).collect();
+    let strategie = nom_strategie.creer(&candidats, config);
+    let mut rep = Repartition::new(&candidats, nb_sg, config, strategie.as_ref(), anciens, garder);
+    let mut explication = Explication { strategie: nom_strategie, ..Explication::default() };
+    rep.placer(&mut explication);
+    rep.equilibrer(&mut explication);
+    explication.ameliorations = rep.ameliorer();
+    rep.expliquer(&mut explication);
+    (rep.sous_groupes(), explication)
+}
+
+struct Repartition<'a, 'c> {
+    candidats: &'c [Candidat<'a>],
+    config: &'c ConfigSousGroupes,
+    strategie: &'c dyn Strategie,
+    anciens: &'c [SousGroupe],
+    /// Tous les participants des anciens sous-groupes y restent (placement des nouveaux seulement)
+    garder: bool,
+    profils: Vec<O<Interet>>,
+    /// Sous-groupe imposé à chaque candidat par un verrou
+    epingle: Vec<O<usize>>,
+    /// Sous-groupes verrouillés, qui ne reçoivent pas de nouveaux enfants (contrainte encore active)
+    ferme: Vec<bool>,
+    /// Candidats qui vont toujours ensemble: une fratrie ou un seul enfant
+    blocs: Vec<Vec<usize>>,
+    /// Sous-groupe de chaque bloc
+    place: Vec<O<usize>>,
+    /// Candidats de chaque sous-groupe
+    contenu: Vec<Vec<usize>>,
+    taille_min: usize,
+    taille_max: usize,
+    max_signales: usize,
+    /// Les fratries doivent être séparées (contrainte encore active)
+    separer_fratries: bool,
+    /// Le nombre d'enfants à surveiller par sous-groupe est limité (contrainte encore active)
+    limiter_signales: bool,
+    nb_fratries: usize,
+}
+impl<'a, 'c> Repartition<'a, 'c> {
+    fn new(candidats: &'c [Candidat<'a>], nb_sg: usize, config: &'c ConfigSousGroupes, strategie: &'c dyn Strategie, anciens: &'c [SousGroupe], garder: bool) -> Self {
+        let n = candidats.len();
+        let index: HashMap<MembreID, usize> = candidats.iter().enumerate().map(|(i, c)| (c.membre.id, i)).collect();
+        let mut epingle = vec![None; n];
+        let mut ferme = vec![false; nb_sg];
+        let mut profils = strategie.profils(candidats, nb_sg);
+        for (sg, ancien) in anciens.iter().enumerate().take(nb_sg) {
+            let gardes = if ancien.verrouille || garder {&ancien.participants} else {&ancien.membres_verrouilles};
+            // les membres retirés du groupe depuis ne sont plus candidats
+            for i in gardes.iter().filter_map(|mid| index.get(mid)) {
+                epingle[*i] = Some(sg);
+            }
+            ferme[sg] = ancien.verrouille;
+            if garder || ancien.verrouille || ancien.profil.is_some() && ancien.a_verrous() {
+                profils[sg] = ancien.profil;
+            }
+        }
+        // les enfants sans compte sont chacun leur propre fratrie
+        let mut fratries: BTreeMap<O<CompteID>, Vec<usize>> = BTreeMap::new();
+        let mut seuls = Vec::new();
+        for (i, c) in candidats.iter().enumerate() {
+            match c.membre.compte {
+                Some(cid) => fratries.entry(Some(cid)).or_default().push(i),
+                None => seuls.push(vec![i]),
+            }
+        }
+        let nb_fratries = fratries.values().filter(|f| f.len() > 1).count();
+        if garder && config.fratries == Fratries::Ensemble {
+            // un nouvel enfant rejoint sa fratrie déjà placée
+            for fratrie in fratries.values() {
+                if let Some(sg) = fratrie.iter().find_map(|i| epingle[*i]).filter(|sg| !ferme[*sg]) {
+                    for i in fratrie {
+                        epingle[*i].get_or_insert(sg);
+                    }
+                }
+            }
+        }
+        let blocs: Vec<Vec<usize>> = match config.fratries {
+            Fratries::Ensemble => fratries.into_values().chain(seuls).collect(),
+            Fratries::Separees | Fratries::Indifferent => (0..n).map(|i| vec![i]).collect(),
+        };
+        // un membre verrouillé est placé seul, sans le reste de sa fratrie
+        let (epingles, libres): (Vec<usize>, Vec<usize>) = (0..n).partition(|i| epingle[*i].is_some());
+        let blocs: Vec<Vec<usize>> = epingles.into_iter().map(|i| vec![i])
+            .chain(blocs.into_iter().map(|b| b.into_iter().filter(|i| libres.contains(i)).collect()))
+            .filter(|b: &Vec<usize>| !b.is_empty())
+            .collect();
+        let mut rep = Self {
+            candidats,
+            config,
+            strategie,
+            anciens,
+            garder,
+            profils,
+            epingle,
+            ferme,
+            place: vec![None; blocs.len()],
+            blocs,
+            contenu: vec![Vec::new(); nb_sg],
+            taille_min: 0,
+            taille_max: 0,
+            max_signales: 0,
+            separer_fratries: config.fratries == Fratries::Separees,
+            limiter_signales: candidats.iter().any(|c| c.signale),
+            nb_fratries,
+        };
+        rep.calculer_bornes();
+        rep
+    }
+
+    /// Tailles permises et nombre d'enfants à surveiller par sous-groupe, sans compter les sous-groupes fermés.
+    fn calculer_bornes(&mut self) {
+        let ouverts = self.ferme.iter().filter(|f| !**f).count().max(1);
+        let libres: Vec<&Candidat> = self.candidats.iter().zip(self.epingle.iter())
+            .filter(|(_, e)| e.is_none_or(|sg| !self.ferme[sg]))
+            .map(|(c, _)| c)
+            .collect();
+        let n = libres.len();
+        self.taille_min = (n / ouverts).saturating_sub(self.config.ecart_taille);
+        self.taille_max = n.div_ceil(ouverts) + self.config.ecart_taille;
+        self.max_signales = libres.iter().filter(|c| c.signale).count().div_ceil(ouverts);
+    }
+
+    /// L'ancien sous-groupe dont le nom, l'animateur et les verrous sont gardés.
+    fn conserve(&self, sg: usize) -> O<&SousGroupe> {
+        self.anciens.get(sg).filter(|a| self.garder || a.a_verrous())
+    }
+
+    /// Sous-groupe imposé au bloc `b` (un membre verrouillé ou gardé est toujours seul dans son bloc).
+    fn epingle(&self, b: usize) -> O<usize> {
+        self.epingle[self.blocs[b][0]]
+    }
+
+    fn cout(&self, sg: usize, membres: &[usize]) -> f32 {
+        if membres.is_empty() {
+            return 0.0;
+        }
+        self.strategie.cout(self.candidats, self.profils[sg], membres)
+    }
+
+    /// Les contraintes encore actives, sauf la taille minimale, sont-elles respectées?
+    fn valide(&self, membres: &[usize]) -> bool {
+        if membres.len() > self.taille_max {
+            return false;
+        }
+        if self.limiter_signales && membres.iter().filter(|i| self.candidats[**i].signale).count() > self.max_signales {
+            return false;
+        }
+        if self.separer_fratries {
+            let mut comptes: Vec<_> = membres.iter().filter_map(|i| self.candidats[*i].membre.compte).collect();
+            let nb = comptes.len();
+            comptes.sort();
+            comptes.dedup();
+            if comptes.len() < nb {
+                return false;
+            }
+        }
+        true
+    }
+
+    fn avec(&self, sg: usize, ajout: &[usize], retrait: &[usize]) -> Vec<usize> {
+        self.contenu[sg].iter().copied().filter(|i| !retrait.contains(i)).chain(ajout.iter().copied()).collect()
+    }
+
+    fn deplacer(&mut self, b: usize, vers: usize) {
+        if let Some(de) = self.place[b] {
+            self.contenu[de].retain(|i| !self.blocs[b].contains(i));
+        }
+        self.contenu[vers].extend(self.blocs[b].iter().copied());
+        self.place[b] = Some(vers);
+    }
+
+    /// Première répartition. Une contrainte impossible à respecter est relâchée, et notée dans l'explication.
+    fn placer(&mut self, explication: &mut Explication) {
+        // une fratrie plus grande qu'un sous-groupe est coupée
+        let mut blocs = Vec::new();
+        for bloc in std::mem::take(&mut self.blocs) {
+            if bloc.len() > self.taille_max {
+                explication.relachees.push(format!("la fratrie {} ({} enfants) est plus grande qu'un sous-groupe",
+                    self.candidats[bloc[0]].membre.nom, bloc.len()));
+                blocs.extend(bloc.chunks(self.taille_max).map(<[usize]>::to_vec));
+            } else {
+                blocs.push(bloc);
+            }
+        }
+        self.blocs = blocs;
+        self.place = vec![None; self.blocs.len()];
+
+        for b in 0..self.blocs.len() {
+            if let Some(sg) = self.epingle(b) {
+                self.deplacer(b, sg);
+            }
+        }
+        let a_placer = (0..self.blocs.len()).any(|b| self.place[b].is_none());
+        if a_placer && self.ferme.iter().all(|f| *f) {
+            self.ferme.fill(false);
+            self.calculer_bornes();
+            explication.relachees.push("tous les sous-groupes sont verrouillés: les nouveaux enfants y sont ajoutés".into());
+        }
+
+        let mut ordre: Vec<usize> = (0..self.blocs.len()).filter(|b| self.place[*b].is_none()).collect();
+        let signales = |b: &usize| self.blocs[*b].iter().filter(|i| self.candidats[**i].signale).count();
+        let age = |b: &usize| self.blocs[*b].iter().map(|i| self.candidats[*i].age).fold(f32::MAX, f32::min);
+        // des fratries à séparer, les plus nombreuses d'abord, tant qu'il reste un sous-groupe sans leurs frères et sœurs
+        let fratrie = |b: &usize| match self.candidats[self.blocs[*b][0]].membre.compte {
+            Some(cid) if self.separer_fratries => self.candidats.iter().filter(|c| c.membre.compte == Some(cid)).count(),
+            _ => 1,
+        };
+        ordre.sort_by(|a, b| signales(b).cmp(&signales(a))
+            .then(self.blocs[*b].len().cmp(&self.blocs[*a].len()))
+            .then(fratrie(b).cmp(&fratrie(a)))
+            .then(age(a).total_cmp(&age(b))));
+        for b in ordre {
+            loop {
+                let meilleur = (0..self.contenu.len())
+                    .filter(|sg| !self.ferme[*sg])
+                    .filter_map(|sg| {
+                        let membres = self.avec(sg, &self.blocs[b], &[]);
+                        self.valide(&membres).then(|| (self.cout(sg, &membres) - self.cout(sg, &self.contenu[sg]), self.contenu[sg].len(), sg))
+                    })
+                    .min_by(|(c1, n1, s1), (c2, n2, s2)| c1.total_cmp(c2).then(n1.cmp(n2)).then(s1.cmp(s2)));
+                if let Some((_, _, sg)) = meilleur {
+                    self.deplacer(b, sg);
+                    break;
+                }
+                self.relacher(explication);
+            }
+        }
+    }
+
+    fn relacher(&mut self, explication: &mut Explication) {
+        if self.separer_fratries {
+            self.separer_fratries = false;
+            explication.relachees.push("certaines fratries ont plus d'enfants que de sous-groupes".into());
+        } else if self.limiter_signales {
+            self.limiter_signales = false;
+            explication.relachees.push(format!("plus de {} enfant(s) avec accompagnement ou problème de comportement dans un sous-groupe", self.max_signales));
+        } else {
+            self.taille_max += 1;
+            explication.relachees.push(format!("un sous-groupe a {} enfants", self.taille_max));
+        }
+    }
+
+    /// Remplit les sous-groupes trop petits avec les enfants qui y coûtent le moins.
+    fn equilibrer(&mut self, explication: &mut Explication) {
+        while let Some(petit) = (0..self.contenu.len()).find(|sg| !self.ferme[*sg] && self.contenu[*sg].len() < self.taille_min) {
+            let meilleur = (0..self.blocs.len())
+                .filter(|b| self.epingle(*b).is_none())
+                .filter_map(|b| {
+                    let de = self.place[b]?;
+                    let bloc = &self.blocs[b];
+                    if de == petit || self.contenu[de].len() < self.taille_min + bloc.len() {
+                        return None;
+                    }
+                    let vers = self.avec(petit, bloc, &[]);
+                    let reste = self.avec(de, &[], bloc);
+                    if !self.valide(&vers) {
+                        return None;
+                    }
+                    let delta = self.cout(petit, &vers) + self.cout(de, &reste) - self.cout(petit, &self.contenu[petit]) - self.cout(de, &self.contenu[de]);
+                    Some((delta, b))
+                })
+                .min_by(|(c1, b1), (c2, b2)| c1.total_cmp(c2).then(b1.cmp(b2)));
+            match meilleur {
+                Some((_, b)) => self.deplacer(b, petit),
+                None => {
+                    self.taille_min = self.contenu[petit].len();
+                    explication.relachees.push(format!("un sous-groupe n'a que {} enfant(s)", self.taille_min));
+                },
+            }
+        }
+    }
+
+    /// Déplace ou échange des blocs tant que le coût total diminue. Retourne le nombre de changements.
+    fn ameliorer(&mut self) -> usize {
+        let mut nb = 0;
+        for _ in 0..PASSES_MAX {
+            match self.trouver_amelioration() {
+                Some((b, vers, echange)) => {
+                    let de = self.place[b].unwrap();
+                    self.deplacer(b, vers);
+                    if let Some(c) = echange {
+                        self.deplacer(c, de);
+                    }
+                    nb += 1;
+                },
+                None => break,
+            }
+        }
+        nb
+    }
+
+    /// Premier déplacement (du bloc `b` vers un sous-groupe), ou échange avec un bloc de l'autre sous-groupe,
+    /// qui respecte les contraintes et diminue le coût.
+    fn trouver_amelioration(&self) -> O<(usize, usize, O<usize>)> {
+        for b in (0..self.blocs.len()).filter(|b| self.epingle(*b).is_none()) {
+            let Some(de) = self.place[b] else {continue};
+            let bloc = &self.blocs[b];
+            for vers in (0..self.contenu.len()).filter(|sg| *sg != de && !self.ferme[*sg]) {
+                let avant = self.cout(de, &self.contenu[de]) + self.cout(vers, &self.contenu[vers]);
+                // déplacement
+                if self.contenu[de].len() >= self.taille_min + bloc.len() {
+                    let reste = self.avec(de, &[], bloc);
+                    let arrivee = self.avec(vers, bloc, &[]);
+                    if self.valide(&arrivee) && self.valide(&reste) && self.cout(de, &reste) + self.cout(vers, &arrivee) < avant - EPSILON {
+                        return Some((b, vers, None));
+                    }
+                }
+                // échange
+                for c in (0..self.blocs.len()).filter(|c| self.place[*c] == Some(vers) && self.epingle(*c).is_none()) {
+                    let autre = &self.blocs[c];
+                    let nouveau_de = self.avec(de, autre, bloc);
+                    let nouveau_vers = self.avec(vers, bloc, autre);
+                    if nouveau_de.len() < self.taille_min || nouveau_vers.len() < self.taille_min {
+                        continue;
+                    }
+                    if self.valide(&nouveau_de) && self.valide(&nouveau_vers) && self.cout(de, &nouveau_de) + self.cout(vers, &nouveau_vers) < avant - EPSILON {
+                        return Some((b, vers, Some(c)));
+                    }
+                }
+            }
+        }
+        None
+    }
+
+    fn expliquer(&self, explication: &mut Explication) {
+        explication.contraintes.push(format!("Taille des sous-groupes: {} à {} enfants", self.taille_min, self.taille_max));
+        if self.nb_fratries > 0 {
+            match self.config.fratries {
+                Fratries::Ensemble => explication.contraintes.push(format!("{} fratrie(s) gardée(s) ensemble", self.nb_fratries)),
+                Fratries::Separees if self.separer_fratries => explication.contraintes.push(format!("{} fratrie(s) séparée(s)", self.nb_fratries)),
+                _ => {},
+            }
+        }
+        if self.limiter_signales {
+            explication.contraintes.push(format!("Au plus {} enfant(s) avec accompagnement ou problème de comportement par sous-groupe", self.max_signales));
+        }
+        let nb_fermes = self.ferme.iter().filter(|f| **f).count();
+        if nb_fermes > 0 {
+            explication.contraintes.push(format!("{} sous-groupe(s) verrouillé(s) gardé(s) tel(s) quel(s)", nb_fermes));
+        }
+        let nb_epingles = self.epingle.iter().zip(self.candidats.iter())
+            .filter(|(e, c)| e.is_some_and(|sg| self.anciens[sg].membres_verrouilles.contains(&c.membre.id)))
+            .count();
+        if nb_epingles > 0 {
+            explication.contraintes.push(format!("{} enfant(s) verrouillé(s) dans leur sous-groupe", nb_epingles));
+        }
+        if self.garder {
+            let anciens = self.candidats.iter().filter(|c| self.anciens.iter().any(|a| a.participants.contains(&c.membre.id))).count();
+            explication.contraintes.push(format!("{} enfant(s) gardé(s) dans leur sous-groupe, {} nouveau(x) placé(s)", anciens, self.candidats.len() - anciens));
+        }
+        for (sg, membres) in self.contenu.iter().enumerate() {
+            explication.sous_groupes.push(self.resumer(sg, membres));
+        }
+    }
+
+    fn resumer(&self, sg: usize, membres: &[usize]) -> String {
+        let ages = membres.iter().map(|i| self.candidats[*i].age.floor() as u32);
+        let (age_min, age_max) = (ages.clone().min().unwrap_or(0), ages.max().unwrap_or(0));
+        let mut genres: BTreeMap<O<Genre>, usize> = BTreeMap::new();
+        for i in membres {
+            *genres.entry(self.candidats[*i].membre.genre).or_default() += 1;
+        }
+        let genres = genres.iter()
+            .map(|(g, n)| format!("{} {}", g.map_or("Inconnu".into(), |g| g.to_string()), n))
+            .collect::<Vec<String>>().join(", ");
+        let mut s = format!("Sous-groupe {}{} ({}){}: {} enfants, {}-{} ans, {}",
+            sg + 1,
+            self.anciens.get(sg).and_then(|a| a.nom.as_ref()).map(|n| format!(" {}", n)).unwrap_or_default(),
+            self.profils[sg].map_or("sans profil".into(), |p| p.to_string()),
+            if self.anciens.get(sg).is_some_and(|a| a.verrouille) {" [verrouillé]"} else {""},
+            membres.len(),
+            age_min,
+            age_max,
+            genres,
+        );
+        if let Some(profil) = self.profils[sg] {
+            let satisfaits = membres.iter()
+                .filter(|i| self.candidats[**i].membre.interets[..2].contains(&Some(profil)))
+                .count();
+            s += &format!(", {} avec {} en 1er ou 2e choix", satisfaits, profil);
+        }
+        let signales = membres.iter().filter(|i| self.candidats[**i].signale).count();
+        if signales > 0 {
+            s += &format!(", {} à surveiller", signales);
+        }
+        if let Some(commentaire) = self.strategie.commentaire(self.candidats, membres) {
+            s += &format!(", {}", commentaire);
+        }
+        s
+    }
+
+    /// Les sous-groupes, avec le nom, l'animateur et les verrous des anciens sous-groupes gardés
+    /// (le nom de tous les anciens sous-groupes).
+    fn sous_groupes(&self) -> Vec<SousGroupe> {
+        self.contenu.iter().zip(self.profils.iter()).enumerate()
+            .map(|(sg, (membres, profil))| {
+                let participants = membres.iter().map(|i| self.candidats[*i].membre.id).collect();
+                match self.conserve(sg) {
+                    Some(ancien) => SousGroupe {
+                        profil: *profil,
+                        nom: ancien.nom.clone(),
+                        animateur: ancien.animateur.clone(),
+                        verrouille: ancien.verrouille,
+                        membres_verrouilles: ancien.membres_verrouilles.intersection(&participants).copied().collect(),
+                        participants,
+                        ..SousGroupe::default()
+                    },
+                    None => SousGroupe {
+                        profil: *profil,
+                        nom: self.anciens.get(sg).and_then(|a| a.nom.clone()),
+                        participants,
+                        ..SousGroupe::default()
+                    },
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use strum::IntoEnumIterator;
+
+    use crate::data::BoolJustifie;
+    use super::*;
+
+    const INTERETS: [Interet; 4] = [Interet::Science, Interet::Sport, Interet::Art, Interet::Nature];
+
+    fn debut() -> Date {
+        Date::from_ymd_opt(2025, 6, 30).unwrap()
+    }
+
+    /// Enfant `id` du compte `compte`, de 5 à 11 ans, aux intérêts et au genre variés.
+    fn enfant(id: u32, compte: u32) -> Membre {
+        let naissance = Date::from_ymd_opt(2014 + (id % 7) as i32, 1 + id % 12, 1).unwrap();
+        let mut m = Membre::new(MembreID(id), format!("Famille{}", compte), format!("Enfant{}", id), naissance);
+        m.compte = Some(CompteID(compte));
+        m.genre = Some(if id.is_multiple_of(2) {Genre::Homme} else {Genre::Femme});
+        m.interets = [Some(INTERETS[id as usize % 4]), Some(INTERETS[(id as usize + 1) % 4]), None, None];
+        m
+    }
+
+    /// `n` enfants, chacun de son propre compte.
+    fn enfants(n: u32) -> Vec<Membre> {
+        (1..=n).map(|id| enfant(id, id)).collect()
+    }
+
+    fn sous_groupe_de(sgs: &[SousGroupe], mid: MembreID) -> O<usize> {
+        sgs.iter().position(|sg| sg.participants.contains(&mid))
+    }
+
+    /// Chaque membre est dans un et un seul sous-groupe.
+    fn verifier_tous_places(membres: &[&Membre], sgs: &[SousGroupe]) {
+        let places: usize = sgs.iter().map(|sg| sg.participants.len()).sum();
+        assert_eq!(places, membres.len());
+        let ids: HashSet<MembreID> = sgs.iter().flat_map(|sg| sg.participants.iter().copied()).collect();
+        assert_eq!(ids, membres.iter().map(|m| m.id).collect());
+    }
+
+    fn tailles(sgs: &[SousGroupe]) -> Vec<usize> {
+        sgs.iter().map(|sg| sg.participants.len()).collect()
+    }
+
+    #[test]
+    fn tailles_equilibrees() {
+        let membres = enfants(23);
+        let membres: Vec<&Membre> = membres.iter().collect();
+        for strategie in NomStrategie::iter() {
+            let (sgs, explication) = repartir(&membres, 3, debut(), &ConfigSousGroupes::default(), strategie, &[]);
+            assert_eq!(sgs.len(), 3);
+            verifier_tous_places(&membres, &sgs);
+            assert!(tailles(&sgs).iter().all(|t| (7..=8).contains(t)), "{}: {:?}", strategie, tailles(&sgs));
+            assert!(explication.relachees.is_empty(), "{}: {:?}", strategie, explication.relachees);
+        }
+    }
+
+    #[test]
+    fn ecart_de_taille_permis() {
+        let membres = enfants(23);
+        let membres: Vec<&Membre> = membres.iter().collect();
+        let config = ConfigSousGroupes { ecart_taille: 2, ..ConfigSousGroupes::default() };
+        for strategie in NomStrategie::iter() {
+            let (sgs, _) = repartir(&membres, 3, debut(), &config, strategie, &[]);
+            verifier_tous_places(&membres, &sgs);
+            assert!(tailles(&sgs).iter().all(|t| (5..=10).contains(t)), "{}: {:?}", strategie, tailles(&sgs));
+        }
+    }
+
+    #[test]
+    fn fratries_ensemble() {
+        // trois fratries de deux enfants parmi 15
+        let membres: Vec<Membre> = (1..=15).map(|id| enfant(id, if id <= 6 {id.div_ceil(2)} else {id})).collect();
+        let membres: Vec<&Membre> = membres.iter().collect();
+        for strategie in NomStrategie::iter() {
+            let (sgs, _) = repartir(&membres, 3, debut(), &ConfigSousGroupes::default(), strategie, &[]);
+            verifier_tous_places(&membres, &sgs);
+            for id in [1, 3, 5] {
+                assert_eq!(sous_groupe_de(&sgs, MembreID(id)), sous_groupe_de(&sgs, MembreID(id + 1)), "{}: fratrie de {}", strategie, id);
+            }
+        }
+    }
+
+    #[test]
+    fn fratries_separees() {
+        // une fratrie de trois enfants parmi 12
+        let membres: Vec<Membre> = (1..=12).map(|id| enfant(id, if id <= 3 {1} else {id})).collect();
+        let membres: Vec<&Membre> = membres.iter().collect();
+        let config = ConfigSousGroupes { fratries: Fratries::Separees, ..ConfigSousGroupes::default() };
+        for strategie in NomStrategie::iter() {
+            let (sgs, explication) = repartir(&membres, 3, debut(), &config, strategie, &[]);
+            verifier_tous_places(&membres, &sgs);
+            let sg_fratrie: HashSet<O<usize>> = (1..=3).map(|id| sous_groupe_de(&sgs, MembreID(id))).collect();
+            assert_eq!(sg_fratrie.len(), 3, "{}", strategie);
+            assert!(explication.relachees.is_empty(), "{}: {:?}", strategie, explication.relachees);
+        }
+    }
+
+    #[test]
+    fn fratrie_plus_nombreuse_que_les_sous_groupes() {
+        let membres: Vec<Membre> = (1..=9).map(|id| enfant(id, if id <= 3 {1} else {id})).collect();
+        let membres: Vec<&Membre> = membres.iter().collect();
+        let config = ConfigSousGroupes { fratries: Fratries::Separees, ..ConfigSousGroupes::default() };
+        let (sgs, explication) = repartir(&membres, 2, debut(), &config, NomStrategie::Interets, &[]);
+        verifier_tous_places(&membres, &sgs);
+        assert!(!explication.relachees.is_empty());
+    }
+
+    #[test]
+    fn enfants_a_surveiller_repartis() {
+        let mut membres = enfants(18);
+        for m in membres.iter_mut().take(3) {
+            m.accompagnement = Some(true);
+        }
+        for m in membres.iter_mut().skip(3).take(3) {
+            m.fiche_sante.probleme_comportement = Some(BoolJustifie { reponse: true, justification: None });
+        }
+        let membres: Vec<&Membre> = membres.iter().collect();
+        for strategie in NomStrategie::iter() {
+            let (sgs, _) = repartir(&membres, 3, debut(), &ConfigSousGroupes::default(), strategie, &[]);
+            verifier_tous_places(&membres, &sgs);
+            for sg in sgs.iter() {
+                let signales = sg.participants.iter().filter(|mid| mid.0 <= 6).count();
+                assert_eq!(signales, 2, "{}: {:?}", strategie, tailles(&sgs));
+            }
+        }
+    }
+
+    #[test]
+    fn profils_des_interets() {
+        let membres = enfants(24);
+        let membres: Vec<&Membre> = membres.iter().collect();
+        // intérêts également populaires: aucun n'atteint le seuil par défaut
+        let (sgs, _) = repartir(&membres, 4, debut(), &ConfigSousGroupes::default(), NomStrategie::Interets, &[]);
+        assert!(sgs.iter().all(|sg| sg.profil.is_none()));
+        let config = ConfigSousGroupes { seuil_profil: 0.0, ..ConfigSousGroupes::default() };
+        let (sgs, _) = repartir(&membres, 4, debut(), &config, NomStrategie::Interets, &[]);
+        let profils: HashSet<O<Interet>> = sgs.iter().map(|sg| sg.profil).collect();
+        assert_eq!(profils, INTERETS.iter().map(|i| Some(*i)).collect());
+        for strategie in [NomStrategie::Ages, NomStrategie::Amities] {
+            let (sgs, _) = repartir(&membres, 4, debut(), &ConfigSousGroupes::default(), strategie, &[]);
+            assert!(sgs.iter().all(|sg| sg.profil.is_none()), "{}", strategie);
+        }
+    }
+
+    #[test]
+    fn verrous_respectes() {
+        let membres = enfants(18);
+        let membres: Vec<&Membre> = membres.iter().collect();
+        let (mut anciens, _) = repartir(&membres, 3, debut(), &ConfigSousGroupes::default(), NomStrategie::Interets, &[]);
+        anciens[0].verrouille = true;
+        anciens[0].nom = Some("Les castors".into());
+        let epingle = *anciens[1].participants.iter().min().unwrap();
+        anciens[1].membres_verrouilles.insert(epingle);
+        let (sgs, _) = repartir(&membres, 3, debut(), &ConfigSousGroupes::default(), NomStrategie::Ages, &anciens);
+        verifier_tous_places(&membres, &sgs);
+        assert_eq!(sgs[0].participants, anciens[0].participants);
+        assert_eq!(sgs[0].profil, anciens[0].profil);
+        assert_eq!(sgs[0].nom, anciens[0].nom);
+        assert!(sgs[1].participants.contains(&epingle));
+    }
+
+    #[test]
+    fn nom_garde_sans_verrou() {
+        let membres = enfants(12);
+        let membres: Vec<&Membre> = membres.iter().collect();
+        let (mut anciens, _) = repartir(&membres, 3, debut(), &ConfigSousGroupes::default(), NomStrategie::Interets, &[]);
+        anciens[2].nom = Some("Les hiboux".into());
+        let (sgs, _) = repartir(&membres, 3, debut(), &ConfigSousGroupes::default(), NomStrategie::Ages, &anciens);
+        assert_eq!(sgs[2].nom.as_deref(), Some("Les hiboux"));
+        assert!(sgs.iter().all(|sg| sg.profil.is_none()));
+    }
+
+    #[test]
+    fn completer_garde_les_anciens() {
+        let mut membres = enfants(15);
+        let (anciens, _) = {
+            let avant: Vec<&Membre> = membres.iter().collect();
+            repartir(&avant, 3, debut(), &ConfigSousGroupes::default(), NomStrategie::Interets, &[])
+        };
+        // un nouvel enfant seul et le frère de l'enfant 1
+        membres.push(enfant(16, 16));
+        membres.push(enfant(17, 1));
+        let membres: Vec<&Membre> = membres.iter().collect();
+        let (sgs, _) = completer(&membres, debut(), &ConfigSousGroupes::default(), NomStrategie::Interets, &anciens);
+        verifier_tous_places(&membres, &sgs);
+        for (ancien, sg) in anciens.iter().zip(sgs.iter()) {
+            assert!(ancien.participants.is_subset(&sg.participants));
+            assert_eq!(ancien.profil, sg.profil);
+        }
+        assert_eq!(sous_groupe_de(&sgs, MembreID(17)), sous_groupe_de(&sgs, MembreID(1)));
+        assert!(tailles(&sgs).iter().all(|t| (5..=6).contains(t)), "{:?}", tailles(&sgs));
+    }
+
+    #[test]
+    fn completer_oublie_les_retires() {
+        let membres = enfants(12);
+        let tous: Vec<&Membre> = membres.iter().collect();
+        let (anciens, _) = repartir(&tous, 3, debut(), &ConfigSousGroupes::default(), NomStrategie::Interets, &[]);
+        let restants: Vec<&Membre> = membres.iter().skip(2).collect();
+        let (sgs, _) = completer(&restants, debut(), &ConfigSousGroupes::default(), NomStrategie::Interets, &anciens);
+        verifier_tous_places(&restants, &sgs);
+    }
+}
@@ -0,0 +1,371 @@
+use std::{collections::{BTreeMap, HashSet}, fmt::Display};
+
+use serde::{Deserialize, Serialize};
+use strum_macros::EnumIter;
+
+use crate::prelude::*;
+use super::{membres::{Interet, Membre, MembreID}, sous_groupes::{Candidat, ConfigSousGroupes}};
+
+/// Un numéro de téléphone ou une personne autorisée commune à plus d'enfants que ça (ex. le numéro d'une garderie)
+/// n'indique pas une amitié.
+const PARTAGE_MAX: usize = 4;
+
+/// Stratégies offertes dans la configuration (`strategie = "interets"`, `"ages"` ou `"amities"`).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, EnumIter, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NomStrategie {
+    /// Sous-groupes à profil d'intérêt
+    #[default]
+    Interets,
+    /// Tranches d'âge, sans profil
+    Ages,
+    /// Amis ensemble, sans profil
+    Amities,
+}
+impl Display for NomStrategie {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Interets => write!(f, "Intérêts"),
+            Self::Ages => write!(f, "Tranches d'âge"),
+            Self::Amities => write!(f, "Amitiés"),
+        }
+    }
+}
+impl NomStrategie {
+    pub fn creer(self, candidats: &[Candidat], config: &ConfigSousGroupes) -> Box<dyn Strategie> {
+        match self {
+            Self::Interets => Box::new(ParInterets::new(candidats, config)),
+            Self::Ages => Box::new(ParAges),
+            Self::Amities => Box::new(ParAmities::new(candidats, config)),
+        }
+    }
+}
+
+/// Une façon de former les sous-groupes: le profil de chaque sous-groupe et ce que coûte un sous-groupe.
+/// Les contraintes (taille, fratries, enfants à surveiller, verrous) sont les mêmes pour toutes les stratégies.
+pub trait Strategie {
+    /// Profil de chaque sous-groupe (aucun par défaut).
+    fn profils(&self, _candidats: &[Candidat], nb_sg: usize) -> Vec<O<Interet>> {
+        vec![None; nb_sg]
+    }
+
+    /// Coût d'un sous-groupe de ce profil et de ces membres (rangs dans `candidats`): plus il est bas, mieux c'est.
+    fn cout(&self, candidats: &[Candidat], profil: O<Interet>, membres: &[usize]) -> f32;
+
+    /// Précision ajoutée au résumé d'un sous-groupe, s'il y a lieu.
+    fn commentaire(&self, _candidats: &[Candidat], _membres: &[usize]) -> O<String> {
+        None
+    }
+}
+
+/// Proportion de chaque genre dans le groupe (homme, femme, autre, inconnu).
+fn proportions_genres(candidats: &[Candidat]) -> [f32; 4] {
+    let mut res = [0.0; 4];
+    for c in candidats {
+        res[c.genre()] += 1.0 / candidats.len() as f32;
+    }
+    res
+}
+
+/// Écart entre le nombre d'enfants de chaque genre et la proportion du groupe.
+fn ecart_genres(proportions: &[f32; 4], candidats: &[Candidat], membres: &[usize]) -> f32 {
+    let mut nb = [0.0; 4];
+    for i in membres {
+        nb[candidats[*i].genre()] += 1.0;
+    }
+    let n = membres.len() as f32;
+    nb.iter().zip(proportions.iter()).map(|(nb, part)| (nb - n * part).abs()).sum()
+}
+
+/// Somme des écarts de chaque âge à la moyenne du sous-groupe.
+fn ecart_ages(candidats: &[Candidat], membres: &[usize]) -> f32 {
+    if membres.is_empty() {
+        return 0.0;
+    }
+    let moyenne = membres.iter().map(|i| candidats[*i].age).sum::<f32>() / membres.len() as f32;
+    membres.iter().map(|i| (candidats[*i].age - moyenne).abs()).sum()
+}
+
+/// Sous-groupes à profil: les enfants loin du profil, l'écart à la proportion de chaque genre et l'écart
+/// de chaque âge à la moyenne coûtent, selon les poids de la configuration.
+struct ParInterets {
+    points: [u32; 4],
+    seuil_profil: f32,
+    poids: (f32, f32, f32),
+    genres: [f32; 4],
+}
+impl ParInterets {
+    fn new(candidats: &[Candidat], config: &ConfigSousGroupes) -> Self {
+        Self {
+            points: config.points_interets,
+            seuil_profil: config.seuil_profil,
+            poids: (config.poids_interets, config.poids_genres, config.poids_ages),
+            genres: proportions_genres(candidats),
+        }
+    }
+}
+impl Strategie for ParInterets {
+    /// Les intérêts reçoivent des sous-groupes en proportion de leurs points (méthode du plus fort reste).
+    /// Un intérêt sous le seuil de la configuration ne donne pas de profil; sans aucun intérêt, aucun profil.
+    fn profils(&self, candidats: &[Candidat], nb_sg: usize) -> Vec<O<Interet>> {
+        let mut points: BTreeMap<Interet, u32> = BTreeMap::new();
+        for c in candidats {
+            for (rang, interet) in c.membre.interets.iter().enumerate() {
+                if let Some(i) = interet {
+                    *points.entry(*i).or_default() += self.points[rang];
+                }
+            }
+        }
+        let total: u32 = points.values().sum();
+        if total == 0 {
+            return vec![None; nb_sg];
+        }
+        points.retain(|_, p| *p as f32 / total as f32 >= self.seuil_profil);
+        let quotas: Vec<(O<Interet>, f32)> = points.iter()
+            .map(|(i, p)| (Some(*i), *p as f32 * nb_sg as f32 / total as f32))
+            .chain(std::iter::once((None, (total - points.values().sum::<u32>()) as f32 * nb_sg as f32 / total as f32)))
+            .collect();
+        let mut nb: BTreeMap<O<Interet>, usize> = quotas.iter().map(|(i, q)| (*i, q.floor() as usize)).collect();
+        let mut restes = quotas.clone();
+        restes.sort_by(|(i1, q1), (i2, q2)| (q2 - q2.floor()).total_cmp(&(q1 - q1.floor())).then(i1.cmp(i2)));
+        for (i, _) in restes.iter().take(nb_sg - nb.values().sum::<usize>()) {
+            *nb.get_mut(i).unwrap() += 1;
+        }
+        // les intérêts les plus populaires d'abord, les sous-groupes sans profil à la fin
+        let mut profils: Vec<(O<Interet>, usize)> = nb.into_iter().filter(|(_, n)| *n > 0).collect();
+        let pts = |i: &O<Interet>| i.map_or(0, |i| points[&i]);
+        profils.sort_by(|(i1, _), (i2, _)| i2.is_some().cmp(&i1.is_some()).then(pts(i2).cmp(&pts(i1))).then(i1.cmp(i2)));
+        profils.into_iter().flat_map(|(i, n)| std::iter::repeat_n(i, n)).collect()
+    }
+
+    fn cout(&self, candidats: &[Candidat], profil: O<Interet>, membres: &[usize]) -> f32 {
+        let (poids_interets, poids_genres, poids_ages) = self.poids;
+        let interets: f32 = match profil {
+            Some(profil) => membres.iter().map(|i| candidats[*i].cout_interet(profil, &self.points)).sum(),
+            None => 0.0,
+        };
+        poids_interets * interets + poids_genres * ecart_genres(&self.genres, candidats, membres) + poids_ages * ecart_ages(candidats, membres)
+    }
+}
+
+/// Tranches d'âge: seul l'écart des âges à la moyenne de chaque sous-groupe compte.
+struct ParAges;
+impl Strategie for ParAges {
+    fn cout(&self, candidats: &[Candidat], _profil: O<Interet>, membres: &[usize]) -> f32 {
+        ecart_ages(candidats, membres)
+    }
+}
+
+/// Amis ensemble: chaque ami placé dans un autre sous-groupe coûte, puis la mixité et les âges, selon leurs poids.
+struct ParAmities {
+    /// Amis de chaque candidat (rangs dans les candidats)
+    amis: Vec<Vec<usize>>,
+    poids: (f32, f32, f32),
+    genres: [f32; 4],
+}
+impl ParAmities {
+    fn new(candidats: &[Candidat], config: &ConfigSousGroupes) -> Self {
+        let membres: Vec<&Membre> = candidats.iter().map(|c| c.membre).collect();
+        let mut amis = vec![Vec::new(); candidats.len()];
+        for (a, b) in paires_amis(&membres) {
+            amis[a].push(b);
+            amis[b].push(a);
+        }
+        Self {
+            amis,
+            poids: (config.poids_amities, config.poids_genres, config.poids_ages),
+            genres: proportions_genres(candidats),
+        }
+    }
+
+    fn amis_separes(&self, membres: &[usize]) -> usize {
+        membres.iter().map(|i| self.amis[*i].iter().filter(|a| !membres.contains(a)).count()).sum()
+    }
+}
+impl Strategie for ParAmities {
+    fn cout(&self, candidats: &[Candidat], _profil: O<Interet>, membres: &[usize]) -> f32 {
+        let (poids_amities, poids_genres, poids_ages) = self.poids;
+        poids_amities * self.amis_separes(membres) as f32
+            + poids_genres * ecart_genres(&self.genres, candidats, membres)
+            + poids_ages * ecart_ages(candidats, membres)
+    }
+
+    fn commentaire(&self, _candidats: &[Candidat], membres: &[usize]) -> O<String> {
+        let separes = self.amis_separes(membres);
+        (separes > 0).then(|| format!("{} ami(s) dans un autre sous-groupe", separes))
+    }
+}
+
+/// Enfants de comptes différents qui se connaissent probablement: l'un est nommé dans le commentaire de l'autre,
+/// ou ils quittent avec la même personne, ou ils ont un contact au même numéro de téléphone
+/// (sauf si plus de [`PARTAGE_MAX`] enfants les partagent). Retourne des paires de rangs dans `membres`.
+pub fn paires_amis(membres: &[&Membre]) -> Vec<(usize, usize)> {
+    let noms: Vec<(String, String)> = membres.iter()
+        .map(|m| (normaliser(&format!("{} {}", m.prenom, m.nom)), normaliser(&format!("{} {}", m.nom, m.prenom))))
+        .collect();
+    let commentaires: Vec<String> = membres.iter().map(|m| m.commentaire.as_deref().map(normaliser).unwrap_or_default()).collect();
+    let mut quittent_avec: Vec<HashSet<String>> = membres.iter()
+        .map(|m| m.quitte.avec.iter().map(|a| normaliser(a)).filter(|a| !a.is_empty()).collect())
+        .collect();
+    let mut tels: Vec<HashSet<String>> = membres.iter()
+        .map(|m| m.contacts.iter().flatten().filter_map(|c| c.tel.as_ref().map(|t| t.as_str().to_string())).collect())
+        .collect();
+    retirer_trop_partages(&mut quittent_avec);
+    retirer_trop_partages(&mut tels);
+    let nomme = |a: usize, b: usize| !commentaires[a].is_empty() && (commentaires[a].contains(&noms[b].0) || commentaires[a].contains(&noms[b].1));
+
+    let mut res = Vec::new();
+    for a in 0..membres.len() {
+        for b in (a + 1)..membres.len() {
+            if membres[a].compte.is_some() && membres[a].compte == membres[b].compte {
+                continue; // une fratrie
+            }
+            if nomme(a, b) || nomme(b, a) || !quittent_avec[a].is_disjoint(&quittent_avec[b]) || !tels[a].is_disjoint(&tels[b]) {
+                res.push((a, b));
+            }
+        }
+    }
+    res
+}
+
+fn retirer_trop_partages(valeurs: &mut [HashSet<String>]) {
+    let mut nb: BTreeMap<String, usize> = BTreeMap::new();
+    for v in valeurs.iter().flatten() {
+        *nb.entry(v.clone()).or_default() += 1;
+    }
+    for v in valeurs.iter_mut() {
+        v.retain(|v| nb[v] <= PARTAGE_MAX);
+    }
+}
+
+/// Paires d'amis (voir [`paires_amis`]) placés dans des sous-groupes différents.
+pub fn nb_amis_separes(membres: &[&Membre], sous_groupe_de: impl Fn(MembreID) -> O<u32>) -> usize {
+    paires_amis(membres).into_iter()
+        .filter(|(a, b)| sous_groupe_de(membres[*a].id) != sous_groupe_de(membres[*b].id))
+        .count()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{data::tel::Tel, groupes::{comptes::CompteID, groupes::SousGroupe, membres::Contact, sous_groupes::repartir}};
+    use super::*;
+
+    fn debut() -> Date {
+        Date::from_ymd_opt(2025, 6, 30).unwrap()
+    }
+
+    /// Enfant de 8 ans, seul dans son compte.
+    fn enfant(id: u32, prenom: &str, nom: &str) -> Membre {
+        let mut m = Membre::new(MembreID(id), nom.into(), prenom.into(), Date::from_ymd_opt(2017, 1, 1).unwrap());
+        m.compte = Some(CompteID(id));
+        m
+    }
+
+    fn avec_tel(mut m: Membre, numero: &str) -> Membre {
+        m.contacts[0] = Some(Contact { nom: "Parent".into(), tel: Some(Tel::parse(numero).unwrap()), lien: None });
+        m
+    }
+
+    fn paires(membres: &[Membre]) -> Vec<(usize, usize)> {
+        paires_amis(&membres.iter().collect::<Vec<&Membre>>())
+    }
+
+    #[test]
+    fn ami_nomme_dans_le_commentaire() {
+        let mut membres = vec![enfant(1, "Félix", "Gagnon"), enfant(2, "Léa", "Tremblay"), enfant(3, "Noah", "Roy")];
+        membres[0].commentaire = Some("Veut être avec LEA TREMBLAY".into());
+        assert_eq!(paires(&membres), vec![(0, 1)]);
+        // nom puis prénom, dans le commentaire du second
+        membres[0].commentaire = None;
+        membres[2].commentaire = Some("Ami de Gagnon Félix".into());
+        assert_eq!(paires(&membres), vec![(0, 2)]);
+    }
+
+    #[test]
+    fn meme_personne_autorisee() {
+        let mut membres = vec![enfant(1, "Félix", "Gagnon"), enfant(2, "Léa", "Tremblay"), enfant(3, "Noah", "Roy")];
+        membres[0].quitte.avec = vec!["Mamie Jo".into(), "".into()];
+        membres[1].quitte.avec = vec!["mamie jo".into()];
+        membres[2].quitte.avec = vec!["".into()];
+        assert_eq!(paires(&membres), vec![(0, 1)]);
+    }
+
+    #[test]
+    fn meme_telephone() {
+        let membres = vec![
+            avec_tel(enfant(1, "Félix", "Gagnon"), "514-555-1234"),
+            avec_tel(enfant(2, "Léa", "Tremblay"), "(514) 555 1234"),
+            avec_tel(enfant(3, "Noah", "Roy"), "514-555-9999"),
+        ];
+        assert_eq!(paires(&membres), vec![(0, 1)]);
+    }
+
+    #[test]
+    fn numero_trop_partage_ignore() {
+        let membres: Vec<Membre> = (1..=PARTAGE_MAX as u32).map(|id| avec_tel(enfant(id, "Enfant", &id.to_string()), "514-555-0000")).collect();
+        assert_eq!(paires(&membres).len(), PARTAGE_MAX * (PARTAGE_MAX - 1) / 2);
+        let membres: Vec<Membre> = (1..=PARTAGE_MAX as u32 + 1).map(|id| avec_tel(enfant(id, "Enfant", &id.to_string()), "514-555-0000")).collect();
+        assert!(paires(&membres).is_empty());
+    }
+
+    #[test]
+    fn fratrie_pas_amie() {
+        let mut membres = vec![avec_tel(enfant(1, "Félix", "Gagnon"), "514-555-1234"), avec_tel(enfant(2, "Zoé", "Gagnon"), "514-555-1234")];
+        membres[1].compte = membres[0].compte;
+        assert!(paires(&membres).is_empty());
+        // sans compte, les enfants ne sont pas une fratrie
+        membres[0].compte = None;
+        membres[1].compte = None;
+        assert_eq!(paires(&membres), vec![(0, 1)]);
+    }
+
+    #[test]
+    fn amis_separes() {
+        let membres = [
+            avec_tel(enfant(1, "Félix", "Gagnon"), "514-555-1234"),
+            avec_tel(enfant(2, "Léa", "Tremblay"), "514-555-1234"),
+            avec_tel(enfant(3, "Noah", "Roy"), "514-555-9999"),
+            avec_tel(enfant(4, "Emma", "Côté"), "514-555-9999"),
+        ];
+        let membres: Vec<&Membre> = membres.iter().collect();
+        assert_eq!(nb_amis_separes(&membres, |mid| Some(mid.0 % 2)), 2);
+        assert_eq!(nb_amis_separes(&membres, |mid| Some(mid.0.div_ceil(2))), 0);
+    }
+
+    #[test]
+    fn tranches_d_age() {
+        // 15 enfants de 5 à 12 ans, nés à six mois d'intervalle
+        let membres: Vec<Membre> = (0..15).map(|i| {
+            let mut m = enfant(i + 1, "Enfant", &i.to_string());
+            m.naissance = Date::from_ymd_opt(2013 + i as i32 / 2, 1 + 6 * (i % 2), 15).unwrap();
+            m
+        }).collect();
+        let membres: Vec<&Membre> = membres.iter().collect();
+        let (mut sgs, _) = repartir(&membres, 3, debut(), &ConfigSousGroupes::default(), NomStrategie::Ages, &[]);
+        assert!(sgs.iter().all(|sg| sg.profil.is_none() && sg.participants.len() == 5));
+        let naissances = |sg: &SousGroupe| {
+            let dates: Vec<Date> = membres.iter().filter(|m| sg.participants.contains(&m.id)).map(|m| m.naissance).collect();
+            (*dates.iter().min().unwrap(), *dates.iter().max().unwrap())
+        };
+        sgs.sort_by_key(|sg| naissances(sg));
+        for paire in sgs.windows(2) {
+            assert!(naissances(&paire[0]).1 < naissances(&paire[1]).0, "tranches qui se chevauchent");
+        }
+    }
+
+    #[test]
+    fn seuil_du_profil() {
+        // Sport en 1er choix pour 9 enfants sur 12, Art pour les autres
+        let membres: Vec<Membre> = (1..=12).map(|id| {
+            let mut m = enfant(id, "Enfant", &id.to_string());
+            m.interets[0] = Some(if id <= 9 {Interet::Sport} else {Interet::Art});
+            m
+        }).collect();
+        let candidats: Vec<Candidat> = membres.iter().map(|m| Candidat { membre: m, age: 8.0, signale: false }).collect();
+        let strategie = ParInterets::new(&candidats, &ConfigSousGroupes::default());
+        assert_eq!(strategie.profils(&candidats, 4), vec![Some(Interet::Sport), Some(Interet::Sport), Some(Interet::Sport), None]);
+        let config = ConfigSousGroupes { seuil_profil: 0.0, ..ConfigSousGroupes::default() };
+        let strategie = ParInterets::new(&candidats, &config);
+        assert_eq!(strategie.profils(&candidats, 4), vec![Some(Interet::Sport), Some(Interet::Sport), Some(Interet::Sport), Some(Interet::Art)]);
+    }
+}
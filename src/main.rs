@@ -1,14 +1,21 @@
 //use extract::presence::{GroupeExtractConfig, GroupeExtractData};
 
-use std::{collections::{HashMap, HashSet}, io::Write, sync::RwLock};
+use std::{collections::{BTreeMap, HashMap, HashSet}, io::Write, process::ExitCode, sync::RwLock};
 
-use config::Config;
+use clap::Parser;
+use cli::Cli;
+use config::{Config, Etat};
+use concordance::Concordance;
+use delta::{Delta, Donnees};
+use data::Taille;
 use console::{style, Term};
-use extract::excel::fill_regs;
-use groupes::{comptes::{CompteReg, NULL_COMPTE}, groupes::{Groupe, GroupeReg, NULL_GROUPE}, membres::{MembreID, MembreReg, NULL_MEMBRE}};
-use office::Excel;
-use prelude::read_int_option;
-use print::typst::{print_fiche_med, print_presence_anim, print_presence_sdj};
+use extract::{diagnostics::{Position, RapportImport}, excel::fill_regs, lot, source};
+use groupes::{categories::Categories, comptes::{CompteReg, NULL_COMPTE}, groupes::{Groupe, GroupeReg, SousGroupe, NULL_GROUPE}, membres::{MembreID, MembreReg, NULL_MEMBRE}};
+use prelude::{read_int_option, read_string_option};
+use qualite::RapportQualite;
+use print::typst::{print_fiche_med, print_presence_anim, print_presence_sdj, print_stats};
+use session::Session;
+use stats::{HistoriqueChandails, SaisonChandails, StatsCamp};
 
 use crate::groupes::membres;
 
@@ -20,6 +27,11 @@ pub mod print;
 pub mod ui;
 pub mod config;
 pub mod stats;
+pub mod delta;
+pub mod concordance;
+pub mod qualite;
+pub mod session;
+pub mod cli;
 
 struct ProgramData {
     pub out: Term,
@@ -28,10 +40,14 @@ struct ProgramData {
     pub groupes: GroupeReg,
     pub comptes: CompteReg,
     pub membres: MembreReg,
-    old_out_dir: RwLock<String>,
+    pub interactif: bool,
+    /// Problèmes rencontrés lors de la dernière lecture de listes de présences
+    pub rapport_import: RapportImport,
+    etat: RwLock<Etat>,
 }
 impl ProgramData {
     pub fn new(out: Term, err: Term, config: Config, groupes: GroupeReg, comptes: CompteReg, membres: MembreReg) -> Self {
+        let etat = Etat::charger(&config.chemin_etat());
         ProgramData {
             out,
             err,
@@ -39,11 +55,14 @@ impl ProgramData {
             groupes,
             comptes,
             membres,
-            old_out_dir: RwLock::new("/".into()),
+            interactif: true,
+            rapport_import: RapportImport::default(),
+            etat: RwLock::new(etat),
         }
     }
     pub fn get_out_dir(&self, title: &str) -> Option<String> {
-        let mut old_dir = self.old_out_dir.write().unwrap();
+        let mut etat = self.etat.write().unwrap();
+        let old_dir = etat.dernier_dossier_sortie.clone().unwrap_or(self.config.out_dir.clone());
         let new_dir = rfd::FileDialog::new()
             .set_title(title)
             .set_directory(old_dir.as_str())
@@ -53,9 +72,52 @@ impl ProgramData {
         let path = new_dir.to_str().unwrap().to_string();
         let dir = new_dir.parent().map(|p| p.to_str().unwrap().to_string()).unwrap_or("/".into());
         //println!("{}", dir);
-        *old_dir = dir;
+        etat.dernier_dossier_sortie = Some(dir);
+        self.sauvegarder_etat(&etat);
         Some(path)
     }
+    pub fn get_in_file(&self, title: &str, filter: &str, extensions: &[&str]) -> Option<String> {
+        let mut etat = self.etat.write().unwrap();
+        let old_dir = etat.dernier_dossier_entree.clone().unwrap_or("/".into());
+        let filepath = rfd::FileDialog::new()
+            .set_title(title)
+            .add_filter(filter, extensions)
+            .set_directory(old_dir.as_str())
+            .pick_file()?;
+        let path = filepath.to_str().unwrap().to_string();
+        etat.dernier_dossier_entree = filepath.parent().map(|p| p.to_str().unwrap().to_string());
+        self.sauvegarder_etat(&etat);
+        Some(path)
+    }
+    pub fn get_in_files(&self, title: &str, filter: &str, extensions: &[&str]) -> Option<Vec<String>> {
+        let mut etat = self.etat.write().unwrap();
+        let old_dir = etat.dernier_dossier_entree.clone().unwrap_or("/".into());
+        let filepaths = rfd::FileDialog::new()
+            .set_title(title)
+            .add_filter(filter, extensions)
+            .set_directory(old_dir.as_str())
+            .pick_files()?;
+        etat.dernier_dossier_entree = filepaths.first().and_then(|f| f.parent()).map(|p| p.to_str().unwrap().to_string());
+        self.sauvegarder_etat(&etat);
+        Some(filepaths.iter().map(|f| f.to_str().unwrap().to_string()).collect())
+    }
+    pub fn get_in_dir(&self, title: &str) -> Option<String> {
+        let mut etat = self.etat.write().unwrap();
+        let old_dir = etat.dernier_dossier_entree.clone().unwrap_or("/".into());
+        let dir = rfd::FileDialog::new()
+            .set_title(title)
+            .set_directory(old_dir.as_str())
+            .pick_folder()?;
+        let path = dir.to_str().unwrap().to_string();
+        etat.dernier_dossier_entree = Some(path.clone());
+        self.sauvegarder_etat(&etat);
+        Some(path)
+    }
+    fn sauvegarder_etat(&self, etat: &Etat) {
+        if let Err(e) = etat.sauvegarder(&self.config.chemin_etat()) {
+            let _ = self.err.write_line(&format!("{}", e));
+        }
+    }
 }
 
 #[derive(Debug, Default, Clone, Copy)]
@@ -64,12 +126,16 @@ enum ProgramActions {
     Quitter,
     ChargerDeProg,
     ChargerDePresence,
+    ComparerExports,
     ImprimerListesPresence,
     ImprimerFichesSante,
     EstimerChandails,
     FaireSousGroupes,
+    ModifierSousGroupes,
     ImprimerStats,
     AfficherDonnees,
+    SauvegarderSession,
+    OuvrirSession,
 }
 
 #[derive(Debug, Default, Clone, Copy)]
@@ -77,11 +143,60 @@ enum AfficherActions {
     Groupes,
     Membres,
     Comptes,
+    RapportImport,
+    Concordance,
     #[default]
     Annuler,
 }
 
 #[derive(Debug, Default, Clone, Copy)]
+enum ChargerPresenceActions {
+    Fichiers,
+    Dossier,
+    #[default]
+    Retour,
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+enum RapportImportActions {
+    ExporterCsv,
+    #[default]
+    Retour,
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+enum ConcordanceActions {
+    ExporterCsv,
+    #[default]
+    Retour,
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+enum QualiteActions {
+    ExporterCsv,
+    #[default]
+    Retour,
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+enum SousGroupesActions {
+    Refaire,
+    Nouveaux,
+    Comparer,
+    #[default]
+    Retour,
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+enum StatsActions {
+    Afficher,
+    ExporterCsv,
+    ImprimerPdf,
+    #[default]
+    Annuler,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
 enum EstimationChandailMode {
     Simple,
     Complex,
@@ -89,18 +204,23 @@ enum EstimationChandailMode {
     Annuler,
 }
 
-fn main() {
+fn main() -> ExitCode {
+    let cli = Cli::parse();
     let out_term = console::Term::stdout();
     let err_term = console::Term::buffered_stderr();
-    let mut config = Config{
-        working_dir: std::env::current_dir().unwrap().to_str().unwrap().into(),
-        ..Config::default()
+    let working_dir: String = std::env::current_dir().unwrap().to_str().unwrap().into();
+    let mut config = match Config::trouver(cli.config.as_deref(), &working_dir) {
+        Ok(c) => c,
+        Err(e) => {
+            let _ = err_term.write_line(&format!("{}", e));
+            let _ = err_term.flush();
+            return ExitCode::FAILURE;
+        },
     };
     
     // get typst working dir from args
-    let args: Vec<String> = std::env::args().collect();
-    if args.len() > 1 {
-        config.typst_working_dir = args[1].clone();
+    if let Some(dir) = cli.typst.as_deref() {
+        config.typst_working_dir = dir.into();
     }
 
     let mut groupe_reg = GroupeReg::default();
@@ -113,18 +233,29 @@ fn main() {
 
     let mut program = ProgramData::new(out_term, err_term, config, groupe_reg, compte_reg, membre_reg);
 
+    if let Some(commande) = &cli.commande {
+        return executer_sans_interaction(&mut program, &cli, commande);
+    }
+    if let Some(session) = &cli.session {
+        let _ = cli::ouvrir_session_cli(&mut program, session);
+    }
+
     while {
         let _ = program.out.clear_screen();
         //println!("{:?}", std::env::current_dir());
         let action = choose_option(&program.out, &[
             ("Lire à partir de la programmation.", ProgramActions::ChargerDeProg),
             ("Lire à partir des listes de présences.", ProgramActions::ChargerDePresence),
+            ("Comparer avec un nouvel export de présences.", ProgramActions::ComparerExports),
             ("Faire les sous-groupes.", ProgramActions::FaireSousGroupes),
+            ("Modifier les sous-groupes.", ProgramActions::ModifierSousGroupes),
             ("Faire les fiches médicales.", ProgramActions::ImprimerFichesSante),
             ("Faire les listes de présences.", ProgramActions::ImprimerListesPresence),
             ("Estimer la quantité de chandails.", ProgramActions::EstimerChandails),
             ("Faire les statistiques de camp.", ProgramActions::ImprimerStats),
             ("Afficher les données.", ProgramActions::AfficherDonnees),
+            ("Sauvegarder la session.", ProgramActions::SauvegarderSession),
+            ("Ouvrir une session.", ProgramActions::OuvrirSession),
             ("Quitter", ProgramActions::Quitter),
         ]);
         let _ = program.out.clear_screen();
@@ -141,6 +272,10 @@ fn main() {
                 let _res = charger_from_list_presence(&mut program);
                 wait_to_continue()
             },
+            ProgramActions::ComparerExports => {
+                let _res = comparer_avec_export(&program);
+                wait_to_continue()
+            },
             ProgramActions::ImprimerListesPresence => {
                 // Obtenir le dossier de sortie
                 let out_dir = program.get_out_dir("Sélectionnez le dossier de sortie");
@@ -150,6 +285,7 @@ fn main() {
                 } else {
                     let _res = print_presences_anim(&program, out_dir.as_deref());
                     let _res = print_presences_sdj(&program, out_dir.as_deref());
+                    let _ = program.err.flush();
                     wait_to_continue()
                 }
             },
@@ -162,17 +298,54 @@ fn main() {
                 wait_to_continue()
             },
             ProgramActions::FaireSousGroupes => {
-                let _res = build_sous_groupes(&mut program);
-                wait_to_continue()
+                let action = choose_option(&program.out, &[
+                    ("Refaire tous les sous-groupes.", SousGroupesActions::Refaire),
+                    ("Placer seulement les nouveaux inscrits (les autres enfants restent dans leur sous-groupe).", SousGroupesActions::Nouveaux),
+                    ("Comparer les stratégies pour un groupe.", SousGroupesActions::Comparer),
+                    ("Retour", SousGroupesActions::Retour),
+                ]);
+                match action {
+                    SousGroupesActions::Refaire => match build_sous_groupes(&mut program, false) {
+                        Ok(()) => {
+                            let _res = afficher_qualite_sous_groupes(&program);
+                            true
+                        },
+                        Err(()) => wait_to_continue(),
+                    },
+                    SousGroupesActions::Nouveaux => match build_sous_groupes(&mut program, true) {
+                        Ok(()) => {
+                            let _res = afficher_qualite_sous_groupes(&program);
+                            true
+                        },
+                        Err(()) => wait_to_continue(),
+                    },
+                    SousGroupesActions::Comparer => {
+                        ui::sous_groupes::comparer_strategies(&mut program);
+                        true
+                    },
+                    SousGroupesActions::Retour => true,
+                }
+            },
+            ProgramActions::ModifierSousGroupes => {
+                ui::sous_groupes::modifier_sous_groupes(&mut program);
+                true
             },
             ProgramActions::ImprimerStats => {
-                let _res = program.out.write_line("Calcul des statistiques... (Pas encore implémenté)");
+                let _res = faire_stats(&program);
                 wait_to_continue()
             },
             ProgramActions::AfficherDonnees => {
                 let _res = afficher_donnees(&program);
                 true
-            }
+            },
+            ProgramActions::SauvegarderSession => {
+                let _res = sauvegarder_session(&program);
+                wait_to_continue()
+            },
+            ProgramActions::OuvrirSession => {
+                let _res = ouvrir_session(&mut program);
+                wait_to_continue()
+            },
         }
     } {}
 
@@ -183,28 +356,264 @@ fn main() {
     //let _res = print_presences_anim(&program);
     //let _res = print_presences_sdj(&program);
 
+    ExitCode::SUCCESS
+}
+
+fn executer_sans_interaction(program: &mut ProgramData, cli: &Cli, commande: &cli::Commande) -> ExitCode {
+    program.interactif = false;
+    if let Some(session) = &cli.session {
+        if cli::ouvrir_session_cli(program, session).is_err() {
+            let _ = program.err.flush();
+            return ExitCode::FAILURE;
+        }
+    }
+    let res = cli::executer(program, commande);
+    // la session est sauvegardée même si une partie de la commande a échoué, pour ne pas perdre ce qui a fonctionné
+    let res_session = match &cli.session {
+        Some(session) => sauvegarder_session_dans(program, session),
+        None => Ok(()),
+    };
+    let _ = program.out.flush();
+    let _ = program.err.flush();
+    match res.and(res_session) {
+        Ok(_) => ExitCode::SUCCESS,
+        Err(_) => ExitCode::FAILURE,
+    }
 }
 
 fn charger_from_list_presence(program: &mut ProgramData) -> Result<(), ()> {
-    let filepath = rfd::FileDialog::new()
-        .set_title("Sélectionner le fichier de présence")
-        .add_filter("excel", &["xlsx"])
-        .set_directory("/")
-        .pick_file();
-    if filepath.is_none() {
+    let action = choose_option(&program.out, &[
+        ("Un ou plusieurs fichiers", ChargerPresenceActions::Fichiers),
+        ("Tous les fichiers d'un dossier", ChargerPresenceActions::Dossier),
+        ("Retour", ChargerPresenceActions::Retour),
+    ]);
+    let chemins = match action {
+        ChargerPresenceActions::Retour => return Ok(()),
+        ChargerPresenceActions::Fichiers => program.get_in_files("Sélectionner les fichiers de présence", "excel, ods ou csv", source::EXTENSIONS),
+        ChargerPresenceActions::Dossier => program.get_in_dir("Sélectionner le dossier des fichiers de présence").map(|d| vec![d]),
+    };
+    if chemins.is_none() {
         let _ = program.err.write_line("Aucun fichier sélectionné.");
         return Err(());
     }
-    let filepath = filepath.unwrap().to_str().unwrap().to_string();
+    let chemins = chemins.unwrap();
     //let filepath: String = read_file_path("Fichier xlsx: ");
+    program.rapport_import = RapportImport::default();
+    let res = charger_presences(program, &chemins);
+    program.rapport_import.afficher(&program.out);
+    if !program.rapport_import.is_empty() {
+        let _ = program.out.write_line("Le rapport peut être exporté en CSV à partir de « Afficher les données ».");
+    }
+    res
+}
 
-    let res = fill_regs(&mut program.comptes, &mut program.membres, &mut program.groupes, &program.config, &filepath, &program.out, &program.err);
-    if let Err(e) = res {
-        let _ = program.err.write_line(&format!("{}", e));
-        return Err(())
+/// Lit des fichiers de présences, ou tous ceux d'un dossier, du plus ancien au plus récent.
+/// Leurs problèmes de lecture sont ajoutés au rapport d'importation du programme.
+/// Avec plusieurs fichiers, un résumé est affiché, avec les groupes présents dans plus d'un fichier.
+fn charger_presences(program: &mut ProgramData, chemins: &[String]) -> Result<(), ()> {
+    let fichiers = match lot::lister_fichiers(chemins) {
+        Ok(fichiers) => fichiers,
+        Err(e) => {
+            let _ = program.err.write_line(&format!("{}", style(e).red()));
+            let _ = program.err.flush();
+            return Err(());
+        },
+    };
+    let (rapport, resume) = lot::importer(&mut program.comptes, &mut program.membres, &mut program.groupes, &program.config, &fichiers, &program.out, &program.err);
+    program.rapport_import.fusionner(rapport);
+    if fichiers.len() > 1 {
+        resume.afficher(&program.out);
     }
     let _ = program.out.flush();
     let _ = program.err.flush();
+    if resume.nb_echecs() > 0 {Err(())} else {Ok(())}
+}
+
+fn exporter_rapport_import_csv(program: &ProgramData, filepath: &str) -> Result<(), ()> {
+    match program.rapport_import.exporter_csv(filepath) {
+        Ok(_) => {
+            let _ = program.out.write_line(&format!("Rapport d'importation exporté dans \"{}\"", style(filepath).green()));
+            Ok(())
+        },
+        Err(e) => {
+            let _ = program.err.write_line(&format!("{}", style(format!("Échec lors de l'exportation du rapport d'importation: {}", e)).red()));
+            let _ = program.err.flush();
+            Err(())
+        },
+    }
+}
+
+fn afficher_rapport_import(program: &ProgramData) -> Result<(), ()> {
+    program.rapport_import.afficher(&program.out);
+    if program.rapport_import.is_empty() {
+        wait_to_continue();
+        return Ok(());
+    }
+    let action = choose_option(&program.out, &[
+        ("Exporter en CSV", RapportImportActions::ExporterCsv),
+        ("Retour", RapportImportActions::Retour),
+    ]);
+    match action {
+        RapportImportActions::Retour => Ok(()),
+        RapportImportActions::ExporterCsv => {
+            let out_dir = program.get_out_dir("Sélectionnez le dossier de sortie");
+            if out_dir.is_none() {
+                let _ = program.err.write_line("Aucun dossier de sortie sélectionné.");
+                return Err(());
+            }
+            let filepath = std::path::Path::new(out_dir.as_deref().unwrap()).join("rapport_importation.csv");
+            let res = exporter_rapport_import_csv(program, filepath.to_str().unwrap());
+            wait_to_continue();
+            res
+        },
+    }
+}
+
+fn afficher_concordance(program: &ProgramData) -> Result<(), ()> {
+    let concordance = Concordance::calculer(&program.groupes);
+    concordance.afficher(&program.out);
+    if concordance.lignes.is_empty() {
+        wait_to_continue();
+        return Ok(());
+    }
+    let action = choose_option(&program.out, &[
+        ("Exporter en CSV", ConcordanceActions::ExporterCsv),
+        ("Retour", ConcordanceActions::Retour),
+    ]);
+    match action {
+        ConcordanceActions::Retour => Ok(()),
+        ConcordanceActions::ExporterCsv => {
+            let out_dir = program.get_out_dir("Sélectionnez le dossier de sortie");
+            if out_dir.is_none() {
+                let _ = program.err.write_line("Aucun dossier de sortie sélectionné.");
+                return Err(());
+            }
+            let res = exporter_concordance_csv(program, out_dir.as_deref().unwrap());
+            wait_to_continue();
+            res
+        },
+    }
+}
+
+fn exporter_concordance_csv(program: &ProgramData, out_dir: &str) -> Result<(), ()> {
+    let concordance = Concordance::calculer(&program.groupes);
+    let filepath = std::path::Path::new(out_dir).join("concordance.csv");
+    let filepath = filepath.to_str().unwrap();
+    match concordance.exporter_csv(filepath) {
+        Ok(_) => {
+            let _ = program.out.write_line(&format!("Rapport de concordance exporté dans \"{}\"", style(filepath).green()));
+            Ok(())
+        },
+        Err(e) => {
+            let _ = program.err.write_line(&format!("{}", style(format!("Échec lors de l'exportation du rapport de concordance: {}", e)).red()));
+            let _ = program.err.flush();
+            Err(())
+        },
+    }
+}
+
+fn afficher_qualite_sous_groupes(program: &ProgramData) -> Result<(), ()> {
+    let rapport = RapportQualite::calculer(&program.groupes, &program.membres);
+    rapport.afficher(&program.out);
+    if rapport.groupes.is_empty() {
+        wait_to_continue();
+        return Ok(());
+    }
+    let action = choose_option(&program.out, &[
+        ("Exporter en CSV", QualiteActions::ExporterCsv),
+        ("Retour", QualiteActions::Retour),
+    ]);
+    match action {
+        QualiteActions::Retour => Ok(()),
+        QualiteActions::ExporterCsv => {
+            let out_dir = program.get_out_dir("Sélectionnez le dossier de sortie");
+            if out_dir.is_none() {
+                let _ = program.err.write_line("Aucun dossier de sortie sélectionné.");
+                return Err(());
+            }
+            let res = exporter_qualite_csv(program, out_dir.as_deref().unwrap());
+            wait_to_continue();
+            res
+        },
+    }
+}
+
+fn exporter_qualite_csv(program: &ProgramData, out_dir: &str) -> Result<(), ()> {
+    let rapport = RapportQualite::calculer(&program.groupes, &program.membres);
+    let filepath = std::path::Path::new(out_dir).join("qualite_sous_groupes.csv");
+    let filepath = filepath.to_str().unwrap();
+    match rapport.exporter_csv(filepath) {
+        Ok(_) => {
+            let _ = program.out.write_line(&format!("Rapport de qualité des sous-groupes exporté dans \"{}\"", style(filepath).green()));
+            Ok(())
+        },
+        Err(e) => {
+            let _ = program.err.write_line(&format!("{}", style(format!("Échec lors de l'exportation du rapport de qualité des sous-groupes: {}", e)).red()));
+            let _ = program.err.flush();
+            Err(())
+        },
+    }
+}
+
+fn comparer_avec_export(program: &ProgramData) -> Result<(), ()> {
+    // sans données chargées, il faut aussi choisir l'ancien export
+    let ancien = if program.membres.membres().all(|m| *m == *NULL_MEMBRE) {
+        let extensions = [source::EXTENSIONS, &["json"]].concat();
+        match program.get_in_file("Sélectionner l'ancien export (ou une session)", "excel, ods, csv ou session", &extensions) {
+            None => {
+                let _ = program.err.write_line("Aucun fichier sélectionné.");
+                return Err(());
+            },
+            f => f,
+        }
+    } else {
+        None
+    };
+    let nouveau = program.get_in_file("Sélectionner le nouvel export", "excel, ods ou csv", source::EXTENSIONS);
+    if nouveau.is_none() {
+        let _ = program.err.write_line("Aucun fichier sélectionné.");
+        return Err(());
+    }
+    comparer_exports(program, ancien.as_deref(), nouveau.as_deref().unwrap())
+}
+
+/// Lit un export de présences ou une session dans des registres à part, sans toucher aux données courantes.
+fn lire_donnees(program: &ProgramData, filepath: &str) -> Result<(GroupeReg, CompteReg, MembreReg), ()> {
+    if filepath.to_lowercase().ends_with(".json") {
+        return Session::ouvrir(filepath).and_then(Session::into_regs).map_err(|e| {
+            let _ = program.err.write_line(&format!("{}", e));
+        });
+    }
+    let mut groupes = GroupeReg::default();
+    let mut comptes = CompteReg::default();
+    let mut membres = MembreReg::default();
+    let mut config = program.config.clone();
+    config.verbose = false;
+    fill_regs(&mut comptes, &mut membres, &mut groupes, &config, filepath, &program.out, &program.err).map_err(|e| {
+        let _ = program.err.write_line(&format!("{}", e));
+    })?;
+    Ok((groupes, comptes, membres))
+}
+
+/// Compare deux exports. Sans ancien export, le nouveau est comparé aux données courantes.
+fn comparer_exports(program: &ProgramData, ancien: Option<&str>, nouveau: &str) -> Result<(), ()> {
+    let regs_ancien = match ancien {
+        Some(f) => Some(lire_donnees(program, f)?),
+        None => None,
+    };
+    let (groupes, comptes, membres) = lire_donnees(program, nouveau)?;
+    let donnees_ancien = match &regs_ancien {
+        Some((g, c, m)) => Donnees::new(g, c, m),
+        None => Donnees::new(&program.groupes, &program.comptes, &program.membres),
+    };
+    let delta = Delta::calculer(donnees_ancien, Donnees::new(&groupes, &comptes, &membres));
+    let _ = program.out.write_line(&format!("Changements de \"{}\" à \"{}\"",
+        style(ancien.unwrap_or("données courantes")).green(),
+        style(nouveau).green(),
+    ));
+    delta.afficher(&program.out);
+    let _ = program.out.flush();
+    let _ = program.err.flush();
     Ok(())
 }
 
@@ -216,6 +625,13 @@ fn print_fiche_santes(program: &ProgramData) -> Result<(), ()> {
         let _ = program.err.write_line("Aucun dossier de sortie sélectionné.");
         return Err(());
     }
+    let res = print_fiche_santes_dans(program, out_dir.as_deref().unwrap(), false);
+    let _ = program.err.flush();
+    res
+}
+
+fn print_fiche_santes_dans(program: &ProgramData, out_dir: &str, update: bool) -> Result<(), ()> {
+    let mut res = Ok(());
 
     // identifie quel enfant est sur quel site
     let mut site_mbrs: HashMap<&str, HashSet<MembreID>> = HashMap::new();
@@ -238,39 +654,46 @@ fn print_fiche_santes(program: &ProgramData) -> Result<(), ()> {
             if let Ok(membre) = program.membres.get(mid) {
                 let compte = program.comptes.get(membre.compte.unwrap_or_default()).unwrap_or(&NULL_COMPTE);
 
-                let _res = print_fiche_med(membre, compte, &program.config, site, false, out_dir.as_deref());
+                let _res = print_fiche_med(membre, compte, &program.config, site, update, Some(out_dir));
                 match _res {
                     Ok(_) => {
                         let _ = program.out.write_line(&format!("{}", style(format!("Impression de la fiche santé de [{} {}]", &membre.prenom, &membre.nom)).cyan()));
                     },
                     Err(_e) => {
                         let _ = program.err.write_line(&format!("{}", style(format!("Échec lors de l'impression de la fiche santé de [{} {}]", &membre.prenom, &membre.nom)).red()));
+                        res = Err(());
                     },
                 }
             } else {
                 let _ = program.err.write_line(&format!("{}", style(format!("Membre {mid} inexistant")).red()));
+                res = Err(());
             }
         }
     }
-    Ok(())
+    res
 }
 
 fn print_presences_anim(program: &ProgramData, out_dir: Option<&str>) -> Result<(), ()> {
     let mut compte = 0;
+    let mut res = Ok(());
     for grp in program.groupes.groupes() {
         compte += 1;
         if grp == &(*NULL_GROUPE) {continue;}
-        if grp.sous_groupe.is_empty() {
-            print_presence_anim(grp, None, &program.membres, &program.comptes, &program.config, out_dir.as_deref()).expect("Oups");
+        let sous_groupes: Vec<Option<&SousGroupe>> = if grp.sous_groupe.is_empty() {
+            vec![None]
         } else {
-            for sg in &grp.sous_groupe {
-                print_presence_anim(grp, Some(sg), &program.membres, &program.comptes, &program.config, out_dir.as_deref()).expect("AAAAAAh");
+            grp.sous_groupe.iter().map(Some).collect()
+        };
+        for sg in sous_groupes {
+            if print_presence_anim(grp, sg, &program.membres, &program.comptes, &program.config, out_dir).is_err() {
+                let _ = program.err.write_line(&format!("{}", style(format!("Échec lors de l'impression de la liste de présence de [{}]", grp.short_desc())).red()));
+                res = Err(());
             }
         }
         
     }
     let _ = program.out.write_line(&format!("À imprimé {}/{} groupes", compte, program.groupes.len()));
-    Ok(())
+    res
 }
 
 fn print_presences_sdj(program: &ProgramData, out_dir: Option<&str>) -> Result<(), ()> {
@@ -283,26 +706,38 @@ fn print_presences_sdj(program: &ProgramData, out_dir: Option<&str>) -> Result<(
         let gi = grp.get_sdj_info();
         grp_info.insert(gi);
     }
+    let mut res = Ok(());
     for gi in grp_info.iter() {
-        let _ = print_presence_sdj(gi, &program.groupes, &program.membres, &program.comptes, &program.config, out_dir.as_deref());
+        if print_presence_sdj(gi, &program.groupes, &program.membres, &program.comptes, &program.config, out_dir).is_err() {
+            res = Err(());
+        }
     }
-    Ok(())
+    res
 }
 
 fn charger_from_prog(program: &mut ProgramData) -> Result<(), ()> {
-    let filepath = rfd::FileDialog::new()
-        .set_title("Sélectionner le fichier de programmation")
-        .add_filter("excel", &["xlsx"])
-        .set_directory("/")
-        .pick_file();
+    let filepath = program.get_in_file("Sélectionner le fichier de programmation", "excel, ods ou csv", source::EXTENSIONS);
     if filepath.is_none() {
         let _ = program.err.write_line("Aucun fichier sélectionné.");
         return Err(());
     }
-    let filepath = filepath.unwrap().to_str().unwrap().to_string();
+    let filepath = filepath.unwrap();
+    // seuls les problèmes de ce fichier sont affichés, puis ajoutés au rapport des importations précédentes
+    let precedent = std::mem::take(&mut program.rapport_import);
+    let res = charger_prog(program, &filepath);
+    program.rapport_import.afficher(&program.out);
+    let rapport = std::mem::replace(&mut program.rapport_import, precedent);
+    program.rapport_import.fusionner(rapport);
+    res
+}
 
-    let mut wb = match Excel::open(&filepath) {
-        Ok(wb) => wb,
+fn charger_prog(program: &mut ProgramData, filepath: &str) -> Result<(), ()> {
+    let feuilles = source::ouvrir(filepath).and_then(|mut wb| {
+        let noms = wb.feuilles()?;
+        noms.into_iter().map(|nom| wb.feuille(&nom).map(|rng| (nom, rng))).collect::<Result<Vec<_>, _>>()
+    });
+    let feuilles = match feuilles {
+        Ok(feuilles) => feuilles,
         Err(e) => {
             let _ = program.err.write_line(&format!("{}", e));
             let _ = program.err.flush();
@@ -311,16 +746,75 @@ fn charger_from_prog(program: &mut ProgramData) -> Result<(), ()> {
     };
     let _ = program.out.write_line(&format!("Lecture de \"{}\"", style(filepath).green()));
 
-    let sheets = wb.sheet_names().unwrap();
-    for sheet in sheets {
-        let rng = wb.worksheet_range(&sheet).unwrap();
-        crate::extract::prog::fill_groupe_reg_from_prog(&rng, &mut program.groupes, &program.out, &program.err);
+    for (nom, rng) in feuilles {
+        let pos = Position { fichier: filepath, feuille: &nom, ligne: None };
+        let rapport = crate::extract::prog::fill_groupe_reg_from_prog(&rng, &mut program.groupes, &program.config, &pos, &program.out, &program.err);
+        program.rapport_import.fusionner(rapport);
     }
     let _ = program.out.flush();
     let _ = program.err.flush();
     Ok(())
 }
 
+fn sauvegarder_session(program: &ProgramData) -> Result<(), ()> {
+    let filepath = rfd::FileDialog::new()
+        .set_title("Sauvegarder la session")
+        .add_filter("session", &["json"])
+        .set_file_name("session.json")
+        .save_file();
+    if filepath.is_none() {
+        let _ = program.err.write_line("Aucun fichier sélectionné.");
+        return Err(());
+    }
+    let filepath = filepath.unwrap().to_str().unwrap().to_string();
+    sauvegarder_session_dans(program, &filepath)
+}
+
+fn sauvegarder_session_dans(program: &ProgramData, filepath: &str) -> Result<(), ()> {
+    let session = Session::from_regs(&program.groupes, &program.comptes, &program.membres);
+    if let Err(e) = session.sauvegarder(filepath) {
+        let _ = program.err.write_line(&format!("{}", e));
+        let _ = program.err.flush();
+        return Err(());
+    }
+    let _ = program.out.write_line(&format!("Session sauvegardée dans \"{}\" ({} groupes, {} comptes, {} membres)",
+        style(filepath).green(),
+        session.groupes.len(),
+        session.comptes.len(),
+        session.membres.len(),
+    ));
+    Ok(())
+}
+
+fn ouvrir_session(program: &mut ProgramData) -> Result<(), ()> {
+    let filepath = program.get_in_file("Ouvrir une session", "session", &["json"]);
+    if filepath.is_none() {
+        let _ = program.err.write_line("Aucun fichier sélectionné.");
+        return Err(());
+    }
+    let filepath = filepath.unwrap();
+    ouvrir_session_de(program, &filepath)
+}
+
+fn ouvrir_session_de(program: &mut ProgramData, filepath: &str) -> Result<(), ()> {
+    let regs = Session::ouvrir(filepath).and_then(Session::into_regs);
+    match regs {
+        Ok((groupes, comptes, membres)) => {
+            // la session ouverte remplace les données courantes
+            program.groupes = groupes;
+            program.comptes = comptes;
+            program.membres = membres;
+            let _ = program.out.write_line(&format!("Session \"{}\" ouverte ({} groupes)", style(filepath).green(), program.groupes.len()));
+            Ok(())
+        },
+        Err(e) => {
+            let _ = program.err.write_line(&format!("{}", e));
+            let _ = program.err.flush();
+            Err(())
+        },
+    }
+}
+
 fn afficher_donnees(program: &ProgramData) -> Result<(), ()> {
 
     while {
@@ -330,6 +824,8 @@ fn afficher_donnees(program: &ProgramData) -> Result<(), ()> {
             ("Groupes", AfficherActions::Groupes),
             ("Membres", AfficherActions::Membres),
             ("Comptes", AfficherActions::Comptes),
+            ("Rapport d'importation", AfficherActions::RapportImport),
+            ("Concordance programmation / présences", AfficherActions::Concordance),
             ("Retour", AfficherActions::Annuler),
         ]);
         let _ = program.out.clear_screen();
@@ -339,10 +835,12 @@ fn afficher_donnees(program: &ProgramData) -> Result<(), ()> {
                 for groupe in program.groupes.groupes() {
                     compte += 1;
                     if groupe == &(*NULL_GROUPE) {continue;}
-                    let _ = program.out.write_line(&format!("{id}: {desc} --- inscriptions: {insc}/{cap}",
+                    let _ = program.out.write_line(&format!("{id}: {desc}{periode} --- inscriptions: {insc}/{cap}{vide}",
                         id=groupe.id,
                         desc=groupe.desc(),
+                        periode=groupe.periode().map(|p| format!(" ({})", p)).unwrap_or_default(),
                         insc=groupe.participants.len(),
+                        vide=if groupe.vide {" (groupe vide)"} else {""},
                         cap=match groupe.capacite {
                             None => String::from("-"),
                             Some(c) => c.to_string(),
@@ -353,12 +851,20 @@ fn afficher_donnees(program: &ProgramData) -> Result<(), ()> {
                 wait_to_continue()
             },
             AfficherActions::Membres => {
-                let _res = program.out.write_line("Affichage des membres... (Pas encore implémenté)");
-                wait_to_continue()
+                ui::membres::parcourir_membres(program);
+                true
             },
             AfficherActions::Comptes => {
-                let _res = program.out.write_line("Affichage des comptes... (Pas encore implémenté)");
-                wait_to_continue()
+                ui::comptes::parcourir_comptes(program);
+                true
+            },
+            AfficherActions::RapportImport => {
+                let _ = afficher_rapport_import(program);
+                true
+            },
+            AfficherActions::Concordance => {
+                let _ = afficher_concordance(program);
+                true
             },
             AfficherActions::Annuler => {
                 false
@@ -369,39 +875,92 @@ fn afficher_donnees(program: &ProgramData) -> Result<(), ()> {
     Ok(())
 }
 
-fn build_sous_groupes(program: &mut ProgramData) -> Result<(), ()> {
+/// Fait les sous-groupes de chaque groupe. Avec `nouveaux`, les groupes qui ont déjà des sous-groupes les gardent:
+/// seuls les nouveaux inscrits sont placés et les enfants désinscrits retirés, puis les listes à réimprimer sont affichées.
+fn build_sous_groupes(program: &mut ProgramData, nouveaux: bool) -> Result<(), ()> {
+    let mut res = Ok(());
+    let mut listes = Vec::new();
     for grp in program.groupes.groupes_mut() {
         if *grp == *NULL_GROUPE { continue; } // skip le groupe null
-        let nb_sg = guess_nb_sous_groupes(grp);
+        if nouveaux && !grp.sous_groupe.is_empty() {
+            let strategie = program.config.strategie(grp.category.as_deref());
+            match grp.completer_sous_groupes(&program.membres, &program.config.sous_groupes, strategie) {
+                Ok((_, changements)) if changements.is_empty() => {},
+                Ok((explication, changements)) => {
+                    let _ = program.out.write_line(&format!("{}", style(format!("Nouveaux inscrits pour [{}]", grp.short_desc())).cyan()));
+                    explication.afficher(&program.out);
+                    for c in changements {
+                        let _ = program.out.write_line(&format!("\t{}", style(&c.titre).bold()));
+                        for e in c.ajoutes.iter() {
+                            let _ = program.out.write_line(&format!("\t\t{} {}", style("+").green(), e));
+                        }
+                        for e in c.retires.iter() {
+                            let _ = program.out.write_line(&format!("\t\t{} {}", style("-").red(), e));
+                        }
+                        listes.push(format!("{} - {}", grp.desc(), c.titre));
+                    }
+                },
+                Err(_) => {
+                    let _ = program.err.write_line(&format!("{}", style(format!("Échec lors du placement des nouveaux inscrits pour [{}]", grp.short_desc())).red()));
+                    res = Err(());
+                },
+            }
+            continue;
+        }
+        let nb_sg = guess_nb_sous_groupes(grp, &program.config.categories, program.interactif, &program.err);
         if let Some(nb_sg) = nb_sg {
-            match grp.mk_sous_groupes(nb_sg, &program.membres) {
-                Ok(_) => {
-                    let _ = program.out.write_line(&format!("{}", style(format!("Création de {nb_sg} sous-groupes pour [{}]", grp.short_desc())).cyan()));
+            let strategie = program.config.strategie(grp.category.as_deref());
+            match grp.mk_sous_groupes(nb_sg, &program.membres, &program.config.sous_groupes, strategie) {
+                Ok(explication) => {
+                    let animateurs = match grp.category.as_deref().and_then(|c| program.config.categories.get(c)) {
+                        Some(cat) => format!(" ({} animateurs requis)", cat.nb_animateurs(grp.participants.len())),
+                        None => String::new(),
+                    };
+                    let _ = program.out.write_line(&format!("{}", style(format!("Création de {} sous-groupes pour [{}]{animateurs}", grp.sous_groupe.len(), grp.short_desc())).cyan()));
+                    explication.afficher(&program.out);
+                    if nouveaux {
+                        listes.extend(grp.sous_groupe.iter().filter(|sg| !sg.participants.is_empty()).map(|sg| format!("{} - {}", grp.desc(), sg.titre())));
+                    }
                 },
                 Err(_) => {
                     let _ = program.err.write_line(&format!("{}", style(format!("Échec lors de la création de {nb_sg} sous-groupes pour [{}]", grp.short_desc())).red()));
+                    res = Err(());
                 },
             }
         }
     }
+    if nouveaux {
+        let _ = program.out.write_line(&format!("{}", style(format!("Listes de présences à réimprimer ({})", listes.len())).bold().cyan()));
+        for l in listes {
+            let _ = program.out.write_line(&format!("\t{}", l));
+        }
+    }
+    let _ = program.err.flush();
+    res
+}
+
+/// Affiche, pour chaque groupe, les sous-groupes de chaque stratégie côte à côte, sans rien changer.
+fn comparer_strategies(program: &ProgramData) -> Result<(), ()> {
+    let mut groupes: Vec<&Groupe> = program.groupes.groupes().filter(|g| **g != *NULL_GROUPE && !g.participants.is_empty()).collect();
+    groupes.sort_by_key(|g| g.desc());
+    for grp in groupes {
+        if let Some(nb_sg) = guess_nb_sous_groupes(grp, &program.config.categories, program.interactif, &program.err) {
+            let propositions = ui::sous_groupes::proposer(program, grp, nb_sg);
+            ui::sous_groupes::afficher_comparaison(program, grp, &propositions);
+            let _ = program.out.write_line("");
+        }
+    }
     Ok(())
 }
 
-fn guess_nb_sous_groupes(grp: &Groupe) -> Option<usize> {
-    let cat = grp.category.as_ref().map(|s| s.to_lowercase());
-    match (cat.as_deref(), grp.estime_cap()) {
+fn guess_nb_sous_groupes(grp: &Groupe, categories: &Categories, interactif: bool, err: &Term) -> Option<usize> {
+    let cat = grp.category.as_deref().and_then(|c| categories.get(c));
+    match (cat, grp.estime_cap()) {
         (_, 0) => None,
-        (Some("crocus"), i) => { // crocus -> 10 par groupes
-            Some((i as f32/10.0).ceil() as usize)
-        },
-        (Some("balaous"), i) => { // balaous -> 12 par groupes
-            Some((i as f32/12.0).ceil() as usize)
-        },
-        (Some("basaltes"), i) => { // basaltes -> 15 par groupes
-            Some((i as f32/15.0).ceil() as usize)
-        },
-        (Some("12-15 ans"), i) => {
-            Some((i as f32/15.0).ceil() as usize)
+        (Some(cat), i) => Some(cat.nb_sous_groupes(i)),
+        (_c, _) if !interactif => { // inconnu, mais on ne peut pas demander
+            let _ = err.write_line(&format!("{}", style(format!("Catégorie inconnue, pas de sous-groupes pour [{}]", grp.short_desc())).yellow()));
+            None
         },
         (_c, _) => { // inconnu, on doit demander
             //println!("Cat de groupe inconnu: {:?}", c);
@@ -418,13 +977,6 @@ fn guess_nb_sous_groupes(grp: &Groupe) -> Option<usize> {
 }
 
 fn estimation_chandail(program: &ProgramData) -> Result<(), ()> {
-
-    /* DONNÉES 2024
-        Crocus: 85 enfants
-        Balaous: 111 enfants
-        Basaltes: 102 enfants
-     */
-
     let _ = program.out.clear_screen();
     let _ = program.out.write_line("Quel mode d'estimation voulez-vous utiliser?");
     let mode = choose_option(&program.out, &[
@@ -432,23 +984,194 @@ fn estimation_chandail(program: &ProgramData) -> Result<(), ()> {
         ("Complet (utilise les données des années précédentes)", EstimationChandailMode::Complex),
         ("Retour", EstimationChandailMode::Annuler),
     ]);
+    afficher_estimation_chandail(program, mode, &BTreeMap::new(), None)
+}
 
+/// `prevus` remplace le nombre d'enfants prévu par l'historique pour les catégories données.
+/// Si `enregistrer` est donné, l'estimation est ajoutée à l'historique sous ce nom de saison.
+fn afficher_estimation_chandail(program: &ProgramData, mode: EstimationChandailMode, prevus: &BTreeMap<String, usize>, enregistrer: Option<&str>) -> Result<(), ()> {
+    let chemin_historique = program.config.chemin_chandails();
+    let mut historique = match HistoriqueChandails::charger(&chemin_historique) {
+        Ok(h) => h,
+        Err(e) => {
+            let _ = program.err.write_line(&format!("{}", style(format!("Historique des chandails illisible: {}", e)).red()));
+            let _ = program.err.flush();
+            return Err(());
+        },
+    };
+    let passee = historique.derniere();
+    // le nombre d'enfants de chaque catégorie sur lequel repose l'estimation, pour l'historique
+    let mut enfants = crate::stats::enfants_par_categorie(&program.groupes, &program.membres);
     let estimation = match mode {
         EstimationChandailMode::Annuler => {return Ok(());},
         EstimationChandailMode::Simple => crate::stats::calcul_chandail(&program.groupes, &program.membres),
-        EstimationChandailMode::Complex => crate::stats::calcul_chandail_complex(&program.groupes, &program.membres),
+        EstimationChandailMode::Complex => {
+            let prevus = nombres_prevus(program, passee, prevus);
+            enfants.extend(prevus.iter().map(|(cat, nb)| (cat.clone(), *nb)));
+            crate::stats::calcul_chandail_complex(&program.groupes, &program.membres, &prevus, passee)
+        },
     };
 
+    if let Some(passee) = passee {
+        let _ = program.out.write_line(&format!("Comparaison avec {}:", style(&passee.saison).cyan()));
+    }
     let mut total = 0;
-    for (taille, nb) in estimation {
-        let _ = program.out.write_line(&format!("{}: {}", taille, nb));
+    let mut total_passe = 0;
+    for taille in Taille::tailles() {
+        let nb = estimation.get(taille).copied().unwrap_or(0);
         total += nb;
+        match passee {
+            Some(passee) => {
+                let nb_passe = passee.tailles.get(taille).copied().unwrap_or(0);
+                total_passe += nb_passe;
+                let _ = program.out.write_line(&format!("{}: {} ({}, {:+})", taille, nb, nb_passe, nb as i64 - nb_passe as i64));
+            },
+            None => {
+                let _ = program.out.write_line(&format!("{}: {}", taille, nb));
+            },
+        }
+    }
+    match passee {
+        Some(_) => {
+            let _ = program.out.write_line(&format!("Total: {} ({}, {:+})", total, total_passe, total as i64 - total_passe as i64));
+        },
+        None => {
+            let _ = program.out.write_line(&format!("Total: {}", total));
+        },
+    }
+
+    let saison = match enregistrer {
+        Some(s) => Some(s.to_string()),
+        None if program.interactif => read_string_option("Nom de la saison pour enregistrer l'estimation dans l'historique (vide pour ne pas l'enregistrer)"),
+        None => None,
+    };
+    if let Some(saison) = saison {
+        historique.ajouter(SaisonChandails {
+            saison,
+            enfants,
+            tailles: estimation.into_iter().collect(),
+        });
+        match historique.sauvegarder(&chemin_historique) {
+            Ok(_) => {
+                let _ = program.out.write_line(&format!("Estimation enregistrée dans \"{}\". Corrigez-y les quantités réellement commandées au besoin.", style(&chemin_historique).green()));
+            },
+            Err(e) => {
+                let _ = program.err.write_line(&format!("{}", style(format!("N'a pu enregistrer l'historique des chandails: {}", e)).red()));
+                let _ = program.err.flush();
+                return Err(());
+            },
+        }
+    }
+
+    Ok(())
+}
+
+/// Nombre d'enfants prévu pour chaque catégorie: celui donné, sinon celui entré par l'utilisateur, sinon celui de la saison passée.
+fn nombres_prevus(program: &ProgramData, passee: Option<&SaisonChandails>, prevus: &BTreeMap<String, usize>) -> BTreeMap<String, usize> {
+    let mut categories: Vec<String> = crate::stats::enfants_par_categorie(&program.groupes, &program.membres).into_keys().collect();
+    if let Some(passee) = passee {
+        for cat in passee.enfants.keys() {
+            if !categories.iter().any(|c| c.eq_ignore_ascii_case(cat)) {
+                categories.push(cat.clone());
+            }
+        }
     }
-    let _ = program.out.write_line(&format!("Total: {}", total));
 
+    let mut res = BTreeMap::new();
+    for cat in categories {
+        let hist = passee.and_then(|p| p.enfants_pour(&cat));
+        let donne = prevus.iter().find(|(c, _)| c.eq_ignore_ascii_case(&cat)).map(|(_, n)| *n);
+        let nb = match (donne, hist) {
+            (Some(n), _) => Some(n),
+            (None, _) if program.interactif => {
+                let msg = match (hist, passee) {
+                    (Some(h), Some(p)) => format!("Nombre prévu de {} (vide pour {} selon {})", cat, h, p.saison),
+                    _ => format!("Nombre prévu de {} (vide pour les inscriptions courantes)", cat),
+                };
+                read_int_option(&msg).map(|n| n as usize).or(hist)
+            },
+            (None, hist) => hist,
+        };
+        if let Some(nb) = nb {
+            res.insert(cat, nb);
+        }
+    }
+    res
+}
+
+fn faire_stats(program: &ProgramData) -> Result<(), ()> {
+    let _ = program.out.clear_screen();
+    let _ = program.out.write_line("Que voulez-vous faire des statistiques?");
+    let action = choose_option(&program.out, &[
+        ("Afficher", StatsActions::Afficher),
+        ("Exporter en csv", StatsActions::ExporterCsv),
+        ("Imprimer en pdf", StatsActions::ImprimerPdf),
+        ("Retour", StatsActions::Annuler),
+    ]);
+    match action {
+        StatsActions::Afficher => afficher_stats(program),
+        StatsActions::ExporterCsv => {
+            let out_dir = program.get_out_dir("Sélectionnez le dossier de sortie");
+            match out_dir {
+                Some(out_dir) => exporter_stats_csv(program, &out_dir),
+                None => {
+                    let _ = program.err.write_line("Aucun dossier de sortie sélectionné.");
+                    Err(())
+                },
+            }
+        },
+        StatsActions::ImprimerPdf => {
+            let out_dir = program.get_out_dir("Sélectionnez le dossier de sortie");
+            match out_dir {
+                Some(out_dir) => print_stats_pdf(program, Some(&out_dir)),
+                None => {
+                    let _ = program.err.write_line("Aucun dossier de sortie sélectionné.");
+                    Err(())
+                },
+            }
+        },
+        StatsActions::Annuler => Ok(()),
+    }
+}
+
+fn print_stats_pdf(program: &ProgramData, out_dir: Option<&str>) -> Result<(), ()> {
+    let stats = StatsCamp::calculer(&program.groupes, &program.membres);
+    match print_stats(&stats, &program.config, out_dir) {
+        Ok(_) => {
+            let _ = program.out.write_line("Statistiques imprimées");
+            Ok(())
+        },
+        Err(_) => {
+            let _ = program.err.write_line(&format!("{}", style("Échec lors de l'impression des statistiques").red()));
+            let _ = program.err.flush();
+            Err(())
+        },
+    }
+}
+
+fn afficher_stats(program: &ProgramData) -> Result<(), ()> {
+    let stats = StatsCamp::calculer(&program.groupes, &program.membres);
+    stats.afficher(&program.out);
     Ok(())
 }
 
+fn exporter_stats_csv(program: &ProgramData, out_dir: &str) -> Result<(), ()> {
+    let stats = StatsCamp::calculer(&program.groupes, &program.membres);
+    let filepath = std::path::Path::new(out_dir).join("statistiques.csv");
+    let filepath = filepath.to_str().unwrap();
+    match stats.exporter_csv(filepath) {
+        Ok(_) => {
+            let _ = program.out.write_line(&format!("Statistiques exportées dans \"{}\"", style(filepath).green()));
+            Ok(())
+        },
+        Err(e) => {
+            let _ = program.err.write_line(&format!("{}", style(format!("Échec lors de l'exportation des statistiques: {}", e)).red()));
+            let _ = program.err.flush();
+            Err(())
+        },
+    }
+}
+
 fn choose_option<T: Default + Copy + Clone>(term: &Term, options: &[(&str, T)]) -> T {
     for (i, (txt, _)) in options.iter().enumerate() {
         let _ = term.write_line(&format!("[{}] {}", i+1, *txt));
@@ -70,6 +70,35 @@ pub fn today() -> Date {
 	chrono::offset::Local::now().date_naive().into()
 }
 
+/// Sérialise un ensemble en ordre croissant, pour que deux sauvegardes des mêmes données donnent le même fichier.
+pub fn serialiser_trie<T, S>(ensemble: &std::collections::HashSet<T>, serializer: S) -> Result<S::Ok, S::Error>
+where
+	T: serde::Serialize + Ord,
+	S: serde::Serializer,
+{
+	let mut elements: Vec<&T> = ensemble.iter().collect();
+	elements.sort();
+	serializer.collect_seq(elements)
+}
+
+/// Âge en années, avec la fraction, à la date `reference`.
+pub fn age_au(naissance: Date, reference: Date) -> f32 {
+	(reference - naissance).num_days() as f32 / 365.25
+}
+
+/// Noms des mois en français, de janvier à décembre.
+pub static NOMS_MOIS: [&str; 12] = ["janvier", "février", "mars", "avril", "mai", "juin", "juillet", "août", "septembre", "octobre", "novembre", "décembre"];
+
+/// Jour et mois en toutes lettres, p. ex. "1er juillet" ou "30 juin".
+pub fn jour_mois(date: Date) -> String {
+	use chrono::Datelike;
+	let jour = match date.day() {
+		1 => String::from("1er"),
+		j => j.to_string(),
+	};
+	format!("{} {}", jour, NOMS_MOIS[date.month0() as usize])
+}
+
 /// provient de https://nick.groenen.me/notes/capitalize-a-string-in-rust/
 /// Capitalizes the first character in s.
 pub fn capitalize(s: &str) -> String {
@@ -80,6 +109,26 @@ pub fn capitalize(s: &str) -> String {
 	}
 }
 
+/// Minuscules et sans accents, pour comparer des noms entrés à la main.
+pub fn normaliser(s: &str) -> String {
+	s.trim().to_lowercase().chars().flat_map(|c| {
+		let r: &[char] = match c {
+			'à' | 'â' | 'ä' | 'á' | 'ã' => &['a'],
+			'ç' => &['c'],
+			'é' | 'è' | 'ê' | 'ë' => &['e'],
+			'î' | 'ï' | 'í' | 'ì' => &['i'],
+			'ô' | 'ö' | 'ó' | 'ò' | 'õ' => &['o'],
+			'ù' | 'û' | 'ü' | 'ú' => &['u'],
+			'ÿ' => &['y'],
+			'ñ' => &['n'],
+			'œ' => &['o', 'e'],
+			'æ' => &['a', 'e'],
+			_ => return vec![c],
+		};
+		r.to_vec()
+	}).collect()
+}
+
 #[allow(clippy::mut_from_ref)]
 pub unsafe fn immut2mut_shenanigans<T>(var: &T) -> &mut T {
 	let p: *mut T = (var as *const T) as *mut T;
@@ -1,6 +1,6 @@
 use chrono::{Datelike, Local};
 
-use crate::{config::Config, data::cam::CAM, groupes::{comptes::{Compte, CompteReg, NULL_COMPTE}, groupes::{Groupe, GroupeID, GroupeReg, SousGroupe}, membres::{Interet, Membre, MembreID, MembreReg}}};
+use crate::{config::Config, data::cam::CAM, stats::{CleStats, Regroupement, StatsCamp, StatsLigne}, groupes::{comptes::{Compte, CompteReg, NULL_COMPTE}, groupes::{Groupe, GroupeID, GroupeReg, SousGroupe}, membres::{Interet, Membre, MembreID, MembreReg}}};
 
 use super::PrintError;
 use core::str;
@@ -71,7 +71,9 @@ pub fn print_fiche_med(membre: &Membre, compte: &Compte, config: &Config, site:
 }
 
 pub fn print_presence_anim(groupe: &Groupe, sous_groupe: Option<&SousGroupe>, membres: &MembreReg, comptes: &CompteReg, config: &Config, out_dir: Option<&str>) -> Result<(), PrintError> {
-	print!("Attempting {} => ", groupe.short_desc());
+	if config.verbose {
+		print!("Attempting {} => ", groupe.short_desc());
+	}
 	let root = out_dir.unwrap_or(&config.out_dir);
 	// calcul le nom du fichier de sortie
 	let dir = format!("{out}/{saison}/{site}/anim/sem{semaine}", 
@@ -132,7 +134,9 @@ pub fn print_presence_anim(groupe: &Groupe, sous_groupe: Option<&SousGroupe>, me
 	}
 
 	std::fs::remove_file(tmp_file_path).unwrap();
-	println!("Wrote {}", out_file);
+	if config.verbose {
+		println!("Wrote {}", out_file);
+	}
 	Ok(())
 }
 
@@ -177,16 +181,19 @@ pub fn print_presence_sdj(info: &PresenceSDJInfo, groupes: &GroupeReg, membres:
 	let tmp_file_path = format!("{}/tmp{}.typ", tmp_file_dir, out_filebase);
 	let mut file = OpenOptions::new().write(true).truncate(true).create(true).open(&tmp_file_path).expect("Could not open temporary file");
 
+	let dates = groupes.groupes().filter(|g| filter_grp(g, info)).find_map(Groupe::periode);
 	let _ = write!(file,
 "#import \"template.typ\": *
 #let site = {site}
 #let saison = {saison}
 #let semaine = {semaine}
+#let dates = {dates}
 #let groupes = (
 ",
 		site = po(info.site, Delimiter::Brackets),
 		saison = po(info.saison, Delimiter::Brackets),
 		semaine = po(info.semaine, Delimiter::Brackets),
+		dates = po(dates.as_ref(), Delimiter::Brackets),
 	);
 	
 	let mut participants = HashSet::new();
@@ -231,6 +238,7 @@ pub fn print_presence_sdj(info: &PresenceSDJInfo, groupes: &GroupeReg, membres:
 	site: site,
 	saison: saison,
 	semaine: semaine,
+	dates: dates,
 	groupes: groupes,
 	participants: participants,
 )");
@@ -244,10 +252,103 @@ pub fn print_presence_sdj(info: &PresenceSDJInfo, groupes: &GroupeReg, membres:
 	}
 
 	std::fs::remove_file(tmp_file_path).unwrap();
-	println!("Wrote {}", out_file);
+	if config.verbose {
+		println!("Wrote {}", out_file);
+	}
+	Ok(())
+}
+
+pub fn print_stats(stats: &StatsCamp, config: &Config, out_dir: Option<&str>) -> Result<(), PrintError> {
+	let root = out_dir.unwrap_or(&config.out_dir);
+	let _ = std::fs::create_dir_all(root);
+	let out_file = format!("{}/statistiques.pdf", root);
+
+	// ouvre le fichier temporaire
+	let tmp_file_dir = format!("{}/templates", config.working_dir);
+	let _ = std::fs::create_dir_all(&tmp_file_dir);
+	let tmp_file_path = format!("{}/tmp_stats.typ", tmp_file_dir);
+	let mut file = match OpenOptions::new().write(true).truncate(true).create(true).open(&tmp_file_path) {
+		Ok(f) => f,
+		Err(_e) => return Err(PrintError::TempFileError),
+	};
+
+	let _ = write!(file,
+"#import \"template.typ\": *
+#let date = [{date}]
+#let lignes = (
+",
+		date=Local::now().date_naive(),
+	);
+	for regroupement in Regroupement::TOUS {
+		if regroupement == Regroupement::Total { continue; }
+		for (cle, ligne) in stats.regrouper(regroupement) {
+			let _ = writeln!(file, "{},", mk_stats(regroupement, &cle, &ligne));
+		}
+	}
+	let _ = write!(file,
+")
+#let total = {total}
+#show: it => stats_camp(it, date: date, lignes: lignes, total: total)
+",
+		total=mk_stats(Regroupement::Total, &CleStats::default(), &stats.total()),
+	);
+
+	drop(file);
+
+	let res = print_typst(config, &tmp_file_path, &out_file);
+	if let Err(e) = res {
+		println!("Error printing stats: {:?}", e);
+		return Err(e);
+	}
+
+	std::fs::remove_file(tmp_file_path).unwrap();
+	if config.verbose {
+		println!("Wrote {}", out_file);
+	}
 	Ok(())
 }
 
+fn mk_stats(regroupement: Regroupement, cle: &CleStats, ligne: &StatsLigne) -> String {
+	let distribution = |valeurs: Vec<(String, usize)>| valeurs.into_iter()
+		.map(|(nom, n)| format!("([{}], {}),", po(Some(nom), Delimiter::None), n))
+		.collect::<String>();
+	format!("new_stats(
+		regroupement: \"{regroupement}\",
+		nom: [{nom}],
+		saison: {saison},
+		site: {site},
+		semaine: {semaine},
+		categorie: {categorie},
+		groupes: {groupes},
+		inscriptions: {inscriptions},
+		capacite: {capacite},
+		taux: {taux},
+		enfants: {enfants},
+		familles: {familles},
+		accompagnement: {accompagnement},
+		genres: ({genres}),
+		ages: ({ages}),
+		interets: ({interets}),
+	)",
+		regroupement=regroupement.titre(),
+		nom=po(Some(cle.desc()), Delimiter::None),
+		saison=po(cle.saison.as_ref(), Delimiter::Brackets),
+		site=po(cle.site.as_ref(), Delimiter::Brackets),
+		semaine=po(cle.semaine.as_ref(), Delimiter::Brackets),
+		categorie=po(cle.category.as_ref(), Delimiter::Brackets),
+		groupes=ligne.nb_groupes,
+		inscriptions=ligne.inscriptions,
+		capacite=po(ligne.capacite_connue(), Delimiter::None),
+		taux=po(ligne.taux_occupation().map(|t| format!("{:.3}", t)), Delimiter::None),
+		enfants=ligne.enfants.len(),
+		familles=ligne.familles.len(),
+		accompagnement=ligne.accompagnement,
+		genres=distribution(ligne.genres.iter().map(|(g, n)| (g.map_or("Inconnu".into(), |g| g.to_string()), *n)).collect()),
+		ages=distribution(ligne.ages.iter().map(|(a, n)| (format!("{} ans", a), *n)).collect()),
+		interets=distribution(ligne.interets.iter().map(|(i, n)| (i.map_or("Aucun".into(), |i| i.to_string()), *n)).collect()),
+	)
+}
+
 fn mk_membre(membre: &Membre, compte: &Compte) -> String {
 	format!("new_enfant(
 		id: \"{mid}\",
@@ -333,16 +434,18 @@ fn mk_membre(membre: &Membre, compte: &Compte) -> String {
 }
 
 fn mk_groupe(groupe: &Groupe, sous_groupe: Option<&SousGroupe>) -> String {
-	format!("new_groupe(saison: {saison}, site: {site}, categorie: {categorie}, discriminant: {discriminant}, animateur: {animateur}, semaine: {semaine}, activite: {activite}, profil: {profil}, groupe_num: {groupe_num})",
+	format!("new_groupe(saison: {saison}, site: {site}, categorie: {categorie}, discriminant: {discriminant}, animateur: {animateur}, semaine: {semaine}, dates: {dates}, activite: {activite}, profil: {profil}, groupe_num: {groupe_num}, nom_sous_groupe: {nom_sous_groupe})",
 	saison=po(groupe.saison.as_ref(), Delimiter::Brackets),
 	site=po(groupe.site.as_ref(), Delimiter::Brackets),
 	categorie=po(groupe.category.as_ref().map(String::as_str), Delimiter::Brackets),
 	discriminant=po(groupe.discriminant.as_ref(), Delimiter::Brackets),
 	animateur=po(sous_groupe.map(|sg| sg.animateur.as_ref()).unwrap_or(None).map(String::as_str), Delimiter::Brackets),
 	semaine=po(groupe.semaine.as_ref(), Delimiter::Brackets),
+	dates=po(groupe.periode().as_ref(), Delimiter::Brackets),
 	activite=po(groupe.activite.as_ref(), Delimiter::Brackets),
 	groupe_num=po(sous_groupe.map(|sg| sg.disc).as_ref().map(u32::to_string), Delimiter::Brackets),
 	profil=po(sous_groupe.map(|sg| sg.profil.as_ref()).unwrap_or(None).map(Interet::as_str), Delimiter::Brackets),
+	nom_sous_groupe=po(sous_groupe.and_then(|sg| sg.nom.as_ref()), Delimiter::Brackets),
 	)
 }
 
@@ -362,4 +465,4 @@ fn print_typst(config: &Config, tmp_file_path: &str, out_file: &str) -> Result<(
 	}
 	//std::fs::remove_file(tmp_file_path).unwrap();
 	Ok(())
-}
\ No newline at end of file
+}
\ No newline at end of file
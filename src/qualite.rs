@@ -0,0 +1,212 @@
+use console::{pad_str, style, Alignment, Term};
+
+use crate::{data::Genre, groupes::{groupes::{Groupe, GroupeReg, SousGroupe, NULL_GROUPE}, membres::{Interet, Membre, MembreReg}}, prelude::{age_au, today, O}};
+
+/// Colonnes du tableau affiché: titre et largeur.
+const COLONNES: [(&str, usize); 9] = [
+    ("Sous-groupe", 32),
+    ("Enfants", 8),
+    ("Âges", 6),
+    ("Médiane", 8),
+    ("Genres", 12),
+    ("Profil 1er/2e", 16),
+    ("Fratries", 9),
+    ("Accomp.", 8),
+    ("Comport.", 9),
+];
+
+/// Mesures d'un sous-groupe, pour juger de la répartition avant d'imprimer les listes.
+#[derive(Debug, Clone, Default)]
+pub struct MesuresSousGroupe {
+    pub titre: String,
+    pub profil: O<Interet>,
+    pub taille: usize,
+    /// Âges en années au début du groupe
+    pub age_min: f32,
+    pub age_max: f32,
+    pub age_median: f32,
+    /// Hommes, femmes, autres et genre inconnu
+    pub genres: [usize; 4],
+    /// Enfants ayant le profil du sous-groupe en 1er choix, puis en 2e choix
+    pub profil_1er: usize,
+    pub profil_2e: usize,
+    /// Enfants ayant un frère ou une sœur (même compte) dans le sous-groupe
+    pub fratries: usize,
+    pub accompagnement: usize,
+    pub comportement: usize,
+}
+impl MesuresSousGroupe {
+    fn calculer(grp: &Groupe, sg: &SousGroupe, membres: &MembreReg) -> Self {
+        let reference = grp.debut.unwrap_or_else(today);
+        let mut mbrs: Vec<&Membre> = sg.participants.iter().filter_map(|mid| membres.get(*mid).ok()).collect();
+        mbrs.sort_by_key(|m| std::cmp::Reverse(m.naissance));
+        let ages: Vec<f32> = mbrs.iter().map(|m| age_au(m.naissance, reference)).collect();
+        let mut res = Self {
+            titre: sg.titre(),
+            profil: sg.profil,
+            taille: mbrs.len(),
+            age_min: ages.first().copied().unwrap_or_default(),
+            age_max: ages.last().copied().unwrap_or_default(),
+            age_median: mediane(&ages),
+            ..Self::default()
+        };
+        for m in mbrs.iter() {
+            res.genres[match m.genre {
+                Some(Genre::Homme) => 0,
+                Some(Genre::Femme) => 1,
+                Some(Genre::Autre) => 2,
+                None => 3,
+            }] += 1;
+            if let Some(profil) = sg.profil {
+                if m.interets[0] == Some(profil) {
+                    res.profil_1er += 1;
+                } else if m.interets[1] == Some(profil) {
+                    res.profil_2e += 1;
+                }
+            }
+            if m.compte.is_some() && mbrs.iter().any(|f| f.id != m.id && f.compte == m.compte) {
+                res.fratries += 1;
+            }
+            if m.accompagnement == Some(true) {
+                res.accompagnement += 1;
+            }
+            if m.fiche_sante.probleme_comportement.as_ref().is_some_and(|p| p.reponse) {
+                res.comportement += 1;
+            }
+        }
+        res
+    }
+
+    /// Part des enfants ayant le profil en 1er ou 2e choix (aucune sans profil).
+    pub fn part_profil(&self) -> O<f32> {
+        self.profil.filter(|_| self.taille > 0).map(|_| (self.profil_1er + self.profil_2e) as f32 / self.taille as f32)
+    }
+
+    fn genres_str(&self) -> String {
+        let [hommes, femmes, autres, inconnus] = self.genres;
+        let mut s = format!("H{} F{}", hommes, femmes);
+        if autres > 0 {
+            s += &format!(" A{}", autres);
+        }
+        if inconnus > 0 {
+            s += &format!(" ?{}", inconnus);
+        }
+        s
+    }
+
+    fn cellules(&self) -> [String; 9] {
+        let profil = match self.part_profil() {
+            Some(part) => {
+                let s = format!("{}+{}/{} ({:.0} %)", self.profil_1er, self.profil_2e, self.taille, part * 100.0);
+                // moins de la moitié du sous-groupe a choisi son profil
+                if part < 0.5 { style(s).yellow().to_string() } else { s }
+            },
+            None => "-".into(),
+        };
+        [
+            self.titre.clone(),
+            self.taille.to_string(),
+            format!("{}-{}", self.age_min.floor() as u32, self.age_max.floor() as u32),
+            format!("{:.1}", self.age_median),
+            self.genres_str(),
+            profil,
+            self.fratries.to_string(),
+            self.accompagnement.to_string(),
+            self.comportement.to_string(),
+        ]
+    }
+}
+
+fn mediane(valeurs_triees: &[f32]) -> f32 {
+    let n = valeurs_triees.len();
+    match n {
+        0 => 0.0,
+        _ if n % 2 == 1 => valeurs_triees[n / 2],
+        _ => (valeurs_triees[n / 2 - 1] + valeurs_triees[n / 2]) / 2.0,
+    }
+}
+
+/// Les mesures de chaque sous-groupe d'un groupe.
+#[derive(Debug, Clone)]
+pub struct QualiteGroupe {
+    pub groupe: String,
+    pub sous_groupes: Vec<MesuresSousGroupe>,
+}
+impl QualiteGroupe {
+    pub fn calculer(grp: &Groupe, membres: &MembreReg) -> Self {
+        Self {
+            groupe: grp.desc(),
+            sous_groupes: grp.sous_groupe.iter().map(|sg| MesuresSousGroupe::calculer(grp, sg, membres)).collect(),
+        }
+    }
+
+    pub fn afficher(&self, term: &Term) {
+        let ligne = |cellules: &[String]| cellules.iter().zip(COLONNES.iter())
+            .map(|(c, (_, largeur))| pad_str(c, *largeur, Alignment::Left, Some("…")).into_owned())
+            .collect::<Vec<String>>()
+            .join(" ");
+        let _ = term.write_line(&format!("{}", style(&self.groupe).bold()));
+        let entete: Vec<String> = COLONNES.iter().map(|(titre, _)| titre.to_string()).collect();
+        let _ = term.write_line(&format!("\t{}", style(ligne(&entete).trim_end()).underlined()));
+        for sg in self.sous_groupes.iter() {
+            let _ = term.write_line(&format!("\t{}", ligne(&sg.cellules()).trim_end()));
+        }
+    }
+}
+
+/// Rapport de qualité des sous-groupes: les mesures de chaque sous-groupe, groupe par groupe.
+#[derive(Debug, Clone, Default)]
+pub struct RapportQualite {
+    pub groupes: Vec<QualiteGroupe>,
+}
+impl RapportQualite {
+    /// Les groupes ayant des participants et des sous-groupes, triés par description.
+    pub fn calculer(groupes: &GroupeReg, membres: &MembreReg) -> Self {
+        let mut groupes: Vec<&Groupe> = groupes.groupes().filter(|g| **g != *NULL_GROUPE && !g.participants.is_empty() && !g.sous_groupe.is_empty()).collect();
+        groupes.sort_by_key(|g| g.desc());
+        Self { groupes: groupes.into_iter().map(|g| QualiteGroupe::calculer(g, membres)).collect() }
+    }
+
+    pub fn afficher(&self, term: &Term) {
+        let _ = term.write_line(&format!("{}", style("Qualité des sous-groupes").bold().cyan()));
+        if self.groupes.is_empty() {
+            let _ = term.write_line(&format!("{}", style("Aucun sous-groupe: faire les sous-groupes d'abord").yellow()));
+            return;
+        }
+        for g in self.groupes.iter() {
+            g.afficher(term);
+        }
+    }
+
+    pub fn exporter_csv(&self, filepath: &str) -> Result<(), csv::Error> {
+        let mut wtr = csv::WriterBuilder::new().delimiter(b';').from_path(filepath)?;
+        wtr.write_record(["Groupe", "Sous-groupe", "Profil", "Enfants", "Âge min", "Âge max", "Âge médian", "Hommes", "Femmes", "Autres", "Genre inconnu",
+            "Profil en 1er choix", "Profil en 2e choix", "Part du profil", "Fratries", "Accompagnement", "Comportement"])?;
+        for g in self.groupes.iter() {
+            for sg in g.sous_groupes.iter() {
+                let [hommes, femmes, autres, inconnus] = sg.genres;
+                wtr.write_record([
+                    g.groupe.clone(),
+                    sg.titre.clone(),
+                    sg.profil.map(|p| p.to_string()).unwrap_or_default(),
+                    sg.taille.to_string(),
+                    format!("{:.1}", sg.age_min),
+                    format!("{:.1}", sg.age_max),
+                    format!("{:.1}", sg.age_median),
+                    hommes.to_string(),
+                    femmes.to_string(),
+                    autres.to_string(),
+                    inconnus.to_string(),
+                    sg.profil_1er.to_string(),
+                    sg.profil_2e.to_string(),
+                    sg.part_profil().map(|p| format!("{:.0} %", p * 100.0)).unwrap_or_default(),
+                    sg.fratries.to_string(),
+                    sg.accompagnement.to_string(),
+                    sg.comportement.to_string(),
+                ])?;
+            }
+        }
+        wtr.flush()?;
+        Ok(())
+    }
+}
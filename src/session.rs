@@ -0,0 +1,174 @@
+use std::{error::Error, fmt::Display, fs::File, io::BufWriter};
+
+use serde::{Deserialize, Serialize};
+
+use crate::groupes::{comptes::{Compte, CompteReg, NULL_COMPTE}, groupes::{Groupe, GroupeReg, NULL_GROUPE}, membres::{Membre, MembreReg, NULL_MEMBRE}};
+
+/// Version du format de fichier de session. À incrémenter à chaque changement incompatible.
+pub const SESSION_VERSION: u32 = 1;
+
+#[derive(Debug)]
+pub enum SessionError {
+    Lecture(std::io::Error),
+    Ecriture(std::io::Error),
+    Format(serde_json::Error),
+    VersionInconnue(u32),
+    IdDuplique(String),
+}
+impl Display for SessionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SessionError::Lecture(e) => write!(f, "SessionError: N'a pu lire le fichier ({})", e),
+            SessionError::Ecriture(e) => write!(f, "SessionError: N'a pu écrire le fichier ({})", e),
+            SessionError::Format(e) => write!(f, "SessionError: Fichier de session invalide ({})", e),
+            SessionError::VersionInconnue(v) => write!(f, "SessionError: Version de session non supportée ({}, max {})", v, SESSION_VERSION),
+            SessionError::IdDuplique(id) => write!(f, "SessionError: Identifiant en double ({})", id),
+        }
+    }
+}
+impl Error for SessionError {}
+
+#[derive(Deserialize)]
+struct EnteteSession {
+    version: u32,
+}
+
+/// Contenu complet d'une session de travail: les groupes (avec leurs sous-groupes), les comptes et les membres.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Session {
+    pub version: u32,
+    pub groupes: Vec<Groupe>,
+    pub comptes: Vec<Compte>,
+    pub membres: Vec<Membre>,
+}
+impl Session {
+    pub fn from_regs(groupes: &GroupeReg, comptes: &CompteReg, membres: &MembreReg) -> Self {
+        // trié par id, comme les ensembles d'identifiants (voir `serialiser_trie`), pour que deux sauvegardes des mêmes données donnent le même fichier
+        let mut groupes: Vec<Groupe> = groupes.groupes().cloned().collect();
+        groupes.sort_by_key(|g| g.id);
+        let mut comptes: Vec<Compte> = comptes.comptes().cloned().collect();
+        comptes.sort_by_key(|c| c.id);
+        let mut membres: Vec<Membre> = membres.membres().cloned().collect();
+        membres.sort_by_key(|m| m.id);
+        Self {
+            version: SESSION_VERSION,
+            groupes,
+            comptes,
+            membres,
+        }
+    }
+
+    /// Reconstruit les registres en conservant les identifiants tels quels.
+    pub fn into_regs(self) -> Result<(GroupeReg, CompteReg, MembreReg), SessionError> {
+        let mut groupe_reg = GroupeReg::default();
+        let mut compte_reg = CompteReg::default();
+        let mut membre_reg = MembreReg::default();
+
+        for grp in self.groupes {
+            let id = grp.id;
+            if groupe_reg.add(grp).is_err() {
+                return Err(SessionError::IdDuplique(id.to_string()));
+            }
+        }
+        for cpt in self.comptes {
+            let id = cpt.id;
+            if compte_reg.add(cpt).is_err() {
+                return Err(SessionError::IdDuplique(id.to_string()));
+            }
+        }
+        for mbr in self.membres {
+            let id = mbr.id;
+            if membre_reg.add(mbr).is_err() {
+                return Err(SessionError::IdDuplique(id.to_string()));
+            }
+        }
+
+        // s'assurer que les éléments nuls sont toujours présents
+        if !groupe_reg.contains(NULL_GROUPE.id) {
+            let _ = groupe_reg.add(NULL_GROUPE.clone());
+        }
+        if !compte_reg.contains(NULL_COMPTE.id) {
+            let _ = compte_reg.add(NULL_COMPTE.clone());
+        }
+        if !membre_reg.contains(NULL_MEMBRE.id) {
+            let _ = membre_reg.add(NULL_MEMBRE.clone());
+        }
+
+        Ok((groupe_reg, compte_reg, membre_reg))
+    }
+
+    pub fn sauvegarder(&self, filepath: &str) -> Result<(), SessionError> {
+        let file = File::create(filepath).map_err(SessionError::Ecriture)?;
+        serde_json::to_writer_pretty(BufWriter::new(file), self).map_err(SessionError::Format)
+    }
+
+    pub fn ouvrir(filepath: &str) -> Result<Self, SessionError> {
+        let contenu = std::fs::read_to_string(filepath).map_err(SessionError::Lecture)?;
+        // lire la version en premier, pour ne pas essayer d'interpréter un format inconnu
+        let entete: EnteteSession = serde_json::from_str(&contenu).map_err(SessionError::Format)?;
+        if entete.version > SESSION_VERSION {
+            return Err(SessionError::VersionInconnue(entete.version));
+        }
+        serde_json::from_str(&contenu).map_err(SessionError::Format)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{groupes::{comptes::CompteID, groupes::{GroupeID, SousGroupe}, membres::{MembreID, Modification}}, prelude::*};
+    use super::*;
+
+    fn regs() -> (GroupeReg, CompteReg, MembreReg) {
+        let mut groupes = GroupeReg::default();
+        let mut comptes = CompteReg::default();
+        let mut membres = MembreReg::default();
+        groupes.add(NULL_GROUPE.clone()).unwrap();
+        comptes.add(NULL_COMPTE.clone()).unwrap();
+        membres.add(NULL_MEMBRE.clone()).unwrap();
+
+        let mut compte = Compte::new(CompteID(7), "Julie Tremblay".into());
+        compte.historique.push(Modification {date: Date::from_ymd_opt(2025, 6, 2).unwrap(), source: "b.xlsx".into(), champ: "Téléphone".into(), avant: "-".into(), apres: "514-555-1234".into()});
+        let mut groupe = Groupe {id: GroupeID(3), site: Some("Parc Lafontaine".into()), ..Groupe::default()};
+        for i in 1..=12 {
+            let mut m = Membre::new(MembreID(i), "Tremblay".into(), format!("Enfant {}", i), Date::from_ymd_opt(2016, 1, i).unwrap());
+            m.compte = Some(compte.id);
+            compte.membres.insert(m.id);
+            groupe.participants.insert(m.id);
+            membres.add(m).unwrap();
+        }
+        membres.get_mut(MembreID(1)).unwrap().historique.push(Modification {date: Date::from_ymd_opt(2025, 6, 2).unwrap(), source: "b.xlsx".into(), champ: "Commentaire".into(), avant: "Lunettes".into(), apres: "-".into()});
+        groupe.sous_groupe = vec![
+            SousGroupe {disc: 1, participants: (1..=6).map(MembreID).collect(), groupe: groupe.id, verrouille: true, nom: Some("Les castors".into()), ..SousGroupe::default()},
+            SousGroupe {disc: 2, participants: (7..=12).map(MembreID).collect(), groupe: groupe.id, membres_verrouilles: [MembreID(8), MembreID(11)].into_iter().collect(), ..SousGroupe::default()},
+        ];
+        groupes.add(groupe).unwrap();
+        comptes.add(compte).unwrap();
+        (groupes, comptes, membres)
+    }
+
+    #[test]
+    fn aller_retour_garde_sous_groupes_verrous_et_historique() {
+        let (groupes, comptes, membres) = regs();
+        let chemin = std::env::temp_dir().join(format!("presencejj-session-{}.json", std::process::id()));
+        let chemin = chemin.to_str().unwrap();
+        Session::from_regs(&groupes, &comptes, &membres).sauvegarder(chemin).unwrap();
+        let premier = std::fs::read_to_string(chemin).unwrap();
+        let (g, c, m) = Session::ouvrir(chemin).unwrap().into_regs().unwrap();
+        // les ensembles sont écrits triés: sauvegarder les données relues donne le même fichier
+        Session::from_regs(&g, &c, &m).sauvegarder(chemin).unwrap();
+        let second = std::fs::read_to_string(chemin).unwrap();
+        let _ = std::fs::remove_file(chemin);
+        assert_eq!(premier, second);
+
+        let groupe = g.get(GroupeID(3)).unwrap();
+        let avant = groupes.get(GroupeID(3)).unwrap();
+        assert_eq!(groupe.participants, avant.participants);
+        assert_eq!(groupe.sous_groupe, avant.sous_groupe);
+        assert!(groupe.sous_groupe[0].verrouille);
+        assert_eq!(groupe.sous_groupe[1].membres_verrouilles.len(), 2);
+        let compte = c.get(CompteID(7)).unwrap();
+        assert_eq!(compte.membres.len(), 12);
+        assert_eq!(compte.historique.len(), 1);
+        assert_eq!(m.get(MembreID(1)).unwrap().historique[0].champ, "Commentaire");
+    }
+}
@@ -1,6 +1,10 @@
-use std::collections::{BTreeSet, HashMap};
+use std::{cmp::Ordering, collections::{BTreeMap, BTreeSet, HashMap}};
 
-use crate::{data::Taille, groupes::{groupes::{Groupe, GroupeReg}, membres::{MembreID, MembreReg}}, prelude::read_int};
+use console::{style, Term};
+use serde::{Deserialize, Serialize};
+use strum::IntoEnumIterator;
+
+use crate::{config::ConfigError, data::{Genre, Taille}, groupes::{comptes::CompteID, groupes::{Groupe, GroupeReg, NULL_GROUPE}, membres::{Interet, MembreID, MembreReg}}, prelude::{today, O}};
 
 
 struct ChandailCalcul {
@@ -41,26 +45,32 @@ fn update_calcul_chandail(cc: &mut ChandailCalcul, grp: &Groupe, membres: &Membr
     }
 }
 
-pub fn calcul_chandail(groupes: &GroupeReg, membres: &MembreReg) -> HashMap<Taille, usize> {
+fn chandails_par_categorie(groupes: &GroupeReg, membres: &MembreReg) -> HashMap<String, ChandailCalcul> {
     let mut chandails_cat: HashMap<String, ChandailCalcul> = HashMap::new();
     let mut tried = BTreeSet::new();
     for groupe in groupes.groupes() {
         let cat = groupe.category.as_ref().map_or("None".into(), String::clone);
-        let cc = {
-            if !chandails_cat.contains_key(&cat) {
-                chandails_cat.insert(cat.clone(), ChandailCalcul::new());
-            }
-            chandails_cat.get_mut(&cat).unwrap()
-        };
+        let cc = chandails_cat.entry(cat).or_insert_with(ChandailCalcul::new);
         update_calcul_chandail(cc, groupe, membres, &mut tried);
     }
+    chandails_cat
+}
 
+/// Nombre d'enfants présentement inscrits dans chaque catégorie, sans les groupes vides.
+pub fn enfants_par_categorie(groupes: &GroupeReg, membres: &MembreReg) -> BTreeMap<String, usize> {
+    chandails_par_categorie(groupes, membres).into_iter()
+        .filter(|(_, cc)| cc.capacite > 0)
+        .map(|(cat, cc)| (cat, cc.capacite))
+        .collect()
+}
+
+pub fn calcul_chandail(groupes: &GroupeReg, membres: &MembreReg) -> HashMap<Taille, usize> {
     let mut res = HashMap::new();
     for t in Taille::tailles() {
         res.insert(*t, 0);
     }
 
-    for (_, cc) in chandails_cat {
+    for (_, cc) in chandails_par_categorie(groupes, membres) {
         let total = cc.total() as f32;
         let cap = cc.capacite as f32;
         for t in Taille::tailles() {
@@ -78,18 +88,13 @@ pub fn calcul_chandail(groupes: &GroupeReg, membres: &MembreReg) -> HashMap<Tail
     res
 }
 
-pub fn calcul_chandail_complex(groupes: &GroupeReg, membres: &MembreReg) -> HashMap<Taille, usize> {
-    let mut chandails_cat: HashMap<String, ChandailCalcul> = HashMap::new();
-    let mut tried = BTreeSet::new();
-    for groupe in groupes.groupes() {
-        let cat = groupe.category.as_ref().map_or("None".into(), String::clone);
-        let cc = {
-            if !chandails_cat.contains_key(&cat) {
-                chandails_cat.insert(cat.clone(), ChandailCalcul::new());
-            }
-            chandails_cat.get_mut(&cat).unwrap()
-        };
-        update_calcul_chandail(cc, groupe, membres, &mut tried);
+/// Estime les chandails à partir du nombre d'enfants prévu dans chaque catégorie. Les catégories sans prévision
+/// utilisent les inscriptions courantes. Sans tailles connues pour une catégorie, la répartition des tailles
+/// de la saison passée est utilisée.
+pub fn calcul_chandail_complex(groupes: &GroupeReg, membres: &MembreReg, prevus: &BTreeMap<String, usize>, passee: O<&SaisonChandails>) -> HashMap<Taille, usize> {
+    let mut chandails_cat = chandails_par_categorie(groupes, membres);
+    for cat in prevus.keys() {
+        chandails_cat.entry(cat.clone()).or_insert_with(ChandailCalcul::new);
     }
 
     let mut res = HashMap::new();
@@ -97,20 +102,438 @@ pub fn calcul_chandail_complex(groupes: &GroupeReg, membres: &MembreReg) -> Hash
         res.insert(*t, 0);
     }
 
+    let total_passe: usize = passee.map_or(0, |p| p.tailles.values().sum());
     for (cat, cc) in chandails_cat {
-        let total = cc.total() as f32;
-        let cap: f32 = read_int(&format!("Nombre prévu de {}", cat)) as f32;
+        let cap = prevus.get(&cat).copied().unwrap_or(cc.capacite) as f32;
+        let total = cc.total();
         for t in Taille::tailles() {
-            if total > 0.0 {
-                if cap > 0.0 {
-                    let f: f32 = *cc.comptes.get(t).unwrap() as f32 / total;
-                    *res.get_mut(t).unwrap() += (cap*f).ceil() as usize;
-                } else {
-                    *res.get_mut(t).unwrap() += *cc.comptes.get(t).unwrap();
-                }
-            }
+            let f: f32 = if total > 0 {
+                *cc.comptes.get(t).unwrap() as f32 / total as f32
+            } else if total_passe > 0 {
+                passee.and_then(|p| p.tailles.get(t)).copied().unwrap_or(0) as f32 / total_passe as f32
+            } else {
+                0.0
+            };
+            *res.get_mut(t).unwrap() += (cap*f).ceil() as usize;
         }
     }
 
     res
-}
\ No newline at end of file
+}
+
+/// Chandails d'une saison passée: le nombre d'enfants de chaque catégorie et les chandails commandés de chaque taille.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SaisonChandails {
+    pub saison: String,
+    pub enfants: BTreeMap<String, usize>,
+    pub tailles: BTreeMap<Taille, usize>,
+}
+impl SaisonChandails {
+    pub fn enfants_pour(&self, cat: &str) -> O<usize> {
+        self.enfants.iter().find(|(c, _)| c.eq_ignore_ascii_case(cat)).map(|(_, n)| *n)
+    }
+}
+
+/// Historique des chandails, de la plus ancienne saison à la plus récente.
+///
+/// ```toml
+/// [[saisons]]
+/// saison = "Été 2024"
+/// enfants = { Crocus = 85, Balaous = 111, Basaltes = 102 }
+/// tailles = { XS = 40, S = 95, M = 90, L = 50, XL = 20, XXL = 3 }
+/// ```
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HistoriqueChandails {
+    pub saisons: Vec<SaisonChandails>,
+}
+impl HistoriqueChandails {
+    /// Un fichier absent donne un historique vide.
+    pub fn charger(filepath: &str) -> Result<Self, ConfigError> {
+        if !std::path::Path::new(filepath).exists() {
+            return Ok(Self::default());
+        }
+        let contenu = std::fs::read_to_string(filepath).map_err(ConfigError::Lecture)?;
+        toml::from_str(&contenu).map_err(|e| ConfigError::Format(e.to_string()))
+    }
+
+    pub fn sauvegarder(&self, filepath: &str) -> Result<(), ConfigError> {
+        let contenu = toml::to_string_pretty(self).map_err(|e| ConfigError::Format(e.to_string()))?;
+        std::fs::write(filepath, contenu).map_err(ConfigError::Ecriture)
+    }
+
+    pub fn derniere(&self) -> O<&SaisonChandails> {
+        self.saisons.last()
+    }
+
+    /// Ajoute une saison à la fin de l'historique, en remplaçant celle du même nom s'il y en a une.
+    pub fn ajouter(&mut self, saison: SaisonChandails) {
+        self.saisons.retain(|s| s.saison != saison.saison);
+        self.saisons.push(saison);
+    }
+}
+
+/// Regroupement d'une ligne de statistiques. Un champ à `None` regroupe toutes les valeurs
+/// (ou les groupes pour lesquels l'information est absente, au niveau du détail).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CleStats {
+    pub saison: O<String>,
+    pub site: O<String>,
+    pub semaine: O<String>,
+    pub category: O<String>,
+}
+impl CleStats {
+    fn de_groupe(grp: &Groupe) -> Self {
+        Self {
+            saison: grp.saison.clone(),
+            site: grp.site.clone(),
+            semaine: grp.semaine.clone(),
+            category: grp.category.clone(),
+        }
+    }
+
+    pub fn desc(&self) -> String {
+        let l = [
+            self.saison.clone(),
+            self.site.clone(),
+            self.semaine.as_ref().map(|s| format!("Sem. {}", s)),
+            self.category.clone(),
+        ];
+        let s = l.into_iter().flatten().collect::<Vec<String>>().join(" | ");
+        if s.is_empty() { "Total".into() } else { s }
+    }
+}
+impl Ord for CleStats {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // les semaines sont triées par numéro, pour que la semaine 10 vienne après la semaine 9
+        let sem = |s: &O<String>| s.as_ref().map(|s| (s.parse::<u32>().unwrap_or(u32::MAX), s.clone()));
+        self.saison.cmp(&other.saison)
+            .then_with(|| self.site.cmp(&other.site))
+            .then_with(|| sem(&self.semaine).cmp(&sem(&other.semaine)))
+            .then_with(|| self.category.cmp(&other.category))
+    }
+}
+impl PartialOrd for CleStats {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Ce qui est compté pour un enfant, une seule fois par ligne de statistiques.
+#[derive(Debug, Clone, Copy)]
+struct StatsEnfant {
+    accompagnement: bool,
+    genre: O<Genre>,
+    /// Âge au début du premier groupe de l'enfant dans la ligne, et ce début
+    age: O<u32>,
+    debut: chrono::NaiveDate,
+    interet: O<Interet>,
+}
+
+/// Statistiques d'un ensemble de groupes. Les inscriptions comptent un enfant une fois par groupe,
+/// alors que les enfants, les familles, l'accompagnement et les répartitions (genres, âges, intérêts)
+/// ne comptent un enfant qu'une fois.
+#[derive(Debug, Clone, Default)]
+pub struct StatsLigne {
+    pub nb_groupes: usize,
+    /// Groupes offerts qui n'ont encore aucune inscription
+    pub groupes_vides: usize,
+    pub inscriptions: usize,
+    /// Somme des capacités des groupes dont la capacité est connue
+    pub capacite: usize,
+    pub groupes_sans_capacite: usize,
+    pub enfants: BTreeSet<MembreID>,
+    pub familles: BTreeSet<CompteID>,
+    pub accompagnement: usize,
+    pub genres: BTreeMap<O<Genre>, usize>,
+    pub ages: BTreeMap<u32, usize>,
+    /// Répartition selon le premier choix d'intérêt
+    pub interets: BTreeMap<O<Interet>, usize>,
+    par_enfant: BTreeMap<MembreID, StatsEnfant>,
+}
+impl StatsLigne {
+    /// `date` remplace le début d'un groupe dont les dates sont inconnues.
+    fn ajouter_groupe(&mut self, grp: &Groupe, membres: &MembreReg, date: chrono::NaiveDate) {
+        let debut = grp.debut.unwrap_or(date);
+        self.nb_groupes += 1;
+        if grp.participants.is_empty() {
+            self.groupes_vides += 1;
+        }
+        match grp.capacite {
+            Some(cap) => self.capacite += cap,
+            None => self.groupes_sans_capacite += 1,
+        }
+        for mid in grp.participants.iter() {
+            let membre = match membres.get(*mid) {
+                Ok(m) => m,
+                Err(_) => continue,
+            };
+            self.inscriptions += 1;
+            self.enfants.insert(*mid);
+            if let Some(cid) = membre.compte {
+                self.familles.insert(cid);
+            }
+            self.ajouter_enfant(*mid, StatsEnfant {
+                accompagnement: membre.accompagnement == Some(true),
+                genre: membre.genre,
+                age: debut.years_since(membre.naissance),
+                debut,
+                interet: membre.interets[0],
+            });
+        }
+        self.recompter();
+    }
+
+    fn ajouter_enfant(&mut self, mid: MembreID, enfant: StatsEnfant) {
+        self.par_enfant.entry(mid)
+            .and_modify(|e| if enfant.debut < e.debut {*e = enfant})
+            .or_insert(enfant);
+    }
+
+    /// Recalcule l'accompagnement et les répartitions à partir des enfants distincts.
+    fn recompter(&mut self) {
+        self.accompagnement = self.par_enfant.values().filter(|e| e.accompagnement).count();
+        self.genres.clear();
+        self.ages.clear();
+        self.interets.clear();
+        for e in self.par_enfant.values() {
+            *self.genres.entry(e.genre).or_default() += 1;
+            if let Some(age) = e.age {
+                *self.ages.entry(age).or_default() += 1;
+            }
+            *self.interets.entry(e.interet).or_default() += 1;
+        }
+    }
+
+    fn ajouter(&mut self, autre: &StatsLigne) {
+        self.nb_groupes += autre.nb_groupes;
+        self.groupes_vides += autre.groupes_vides;
+        self.inscriptions += autre.inscriptions;
+        self.capacite += autre.capacite;
+        self.groupes_sans_capacite += autre.groupes_sans_capacite;
+        self.enfants.extend(autre.enfants.iter());
+        self.familles.extend(autre.familles.iter());
+        for (mid, enfant) in autre.par_enfant.iter() {
+            self.ajouter_enfant(*mid, *enfant);
+        }
+        self.recompter();
+    }
+
+    /// Inscriptions sur capacité. Inconnu si un des groupes n'a pas de capacité.
+    pub fn taux_occupation(&self) -> O<f32> {
+        match self.capacite_connue() {
+            Some(cap) if cap > 0 => Some(self.inscriptions as f32 / cap as f32),
+            _ => None,
+        }
+    }
+
+    pub fn capacite_connue(&self) -> O<usize> {
+        if self.groupes_sans_capacite > 0 { None } else { Some(self.capacite) }
+    }
+
+    fn capacite_str(&self) -> String {
+        self.capacite_connue().map_or("?".into(), |c| c.to_string())
+    }
+
+    fn taux_str(&self) -> String {
+        match self.taux_occupation() {
+            Some(t) => format!("{:.0}%", t*100.0),
+            None => "-".into(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Regroupement {
+    Detail,
+    Site,
+    SiteSemaine,
+    Semaine,
+    Categorie,
+    Total,
+}
+impl Regroupement {
+    pub const TOUS: [Regroupement; 6] = [Self::Detail, Self::Site, Self::SiteSemaine, Self::Semaine, Self::Categorie, Self::Total];
+
+    pub fn titre(&self) -> &'static str {
+        match self {
+            Self::Detail => "Par groupe",
+            Self::Site => "Par site",
+            Self::SiteSemaine => "Par site et semaine",
+            Self::Semaine => "Par semaine",
+            Self::Categorie => "Par catégorie",
+            Self::Total => "Total",
+        }
+    }
+    fn cle(&self, cle: &CleStats) -> CleStats {
+        match self {
+            Self::Detail => cle.clone(),
+            Self::Site => CleStats { saison: cle.saison.clone(), site: cle.site.clone(), ..Default::default() },
+            Self::SiteSemaine => CleStats { saison: cle.saison.clone(), site: cle.site.clone(), semaine: cle.semaine.clone(), ..Default::default() },
+            Self::Semaine => CleStats { saison: cle.saison.clone(), semaine: cle.semaine.clone(), ..Default::default() },
+            Self::Categorie => CleStats { saison: cle.saison.clone(), category: cle.category.clone(), ..Default::default() },
+            Self::Total => CleStats::default(),
+        }
+    }
+}
+
+/// Statistiques de camp, calculées par saison, site, semaine et catégorie.
+#[derive(Debug, Clone, Default)]
+pub struct StatsCamp {
+    pub detail: BTreeMap<CleStats, StatsLigne>,
+}
+impl StatsCamp {
+    pub fn calculer(groupes: &GroupeReg, membres: &MembreReg) -> Self {
+        let date = today();
+        let mut detail: BTreeMap<CleStats, StatsLigne> = BTreeMap::new();
+        for grp in groupes.groupes() {
+            if *grp == *NULL_GROUPE { continue; }
+            detail.entry(CleStats::de_groupe(grp)).or_default().ajouter_groupe(grp, membres, date);
+        }
+        Self { detail }
+    }
+
+    pub fn regrouper(&self, regroupement: Regroupement) -> BTreeMap<CleStats, StatsLigne> {
+        let mut res: BTreeMap<CleStats, StatsLigne> = BTreeMap::new();
+        for (cle, ligne) in self.detail.iter() {
+            res.entry(regroupement.cle(cle)).or_default().ajouter(ligne);
+        }
+        res
+    }
+
+    pub fn total(&self) -> StatsLigne {
+        self.regrouper(Regroupement::Total).into_values().next().unwrap_or_default()
+    }
+
+    pub fn afficher(&self, term: &Term) {
+        for regroupement in [Regroupement::Detail, Regroupement::Site, Regroupement::Semaine, Regroupement::Categorie] {
+            let _ = term.write_line(&format!("{}", style(regroupement.titre()).bold().cyan()));
+            for (cle, ligne) in self.regrouper(regroupement) {
+                let _ = term.write_line(&format!("\t{}: {}/{} ({}), {} familles, {} accompagnement",
+                    cle.desc(),
+                    ligne.inscriptions,
+                    ligne.capacite_str(),
+                    ligne.taux_str(),
+                    ligne.familles.len(),
+                    ligne.accompagnement,
+                ));
+            }
+        }
+
+        let total = self.total();
+        let _ = term.write_line(&format!("{}", style("Total").bold().cyan()));
+        let _ = term.write_line(&format!("\tInscriptions: {}/{} ({})", total.inscriptions, total.capacite_str(), total.taux_str()));
+        let _ = term.write_line(&format!("\tGroupes: {} (dont {} vides)", total.nb_groupes, total.groupes_vides));
+        let _ = term.write_line(&format!("\tEnfants: {}", total.enfants.len()));
+        let _ = term.write_line(&format!("\tFamilles: {}", total.familles.len()));
+        let _ = term.write_line(&format!("\tAccompagnement: {}", total.accompagnement));
+        let genres = total.genres.iter()
+            .map(|(g, n)| format!("{} {}", g.map_or("Inconnu".into(), |g| g.to_string()), n))
+            .collect::<Vec<String>>().join(", ");
+        let _ = term.write_line(&format!("\tGenres: {}", genres));
+        let ages = total.ages.iter().map(|(a, n)| format!("{} ans: {}", a, n)).collect::<Vec<String>>().join(", ");
+        let _ = term.write_line(&format!("\tÂges: {}", ages));
+        let interets = total.interets.iter()
+            .map(|(i, n)| format!("{} {}", i.map_or("Aucun", |i| i.as_str()), n))
+            .collect::<Vec<String>>().join(", ");
+        let _ = term.write_line(&format!("\tPremier intérêt: {}", interets));
+    }
+
+    /// Exporte tous les regroupements dans un fichier csv (séparé par des points-virgules, pour Excel).
+    pub fn exporter_csv(&self, filepath: &str) -> Result<(), csv::Error> {
+        let total = self.total();
+        let ages: Vec<u32> = total.ages.keys().copied().collect();
+        let mut wtr = csv::WriterBuilder::new().delimiter(b';').from_path(filepath)?;
+
+        let mut entete: Vec<String> = ["Regroupement", "Saison", "Site", "Semaine", "Catégorie", "Groupes", "Groupes vides", "Inscriptions", "Capacité", "Taux d'occupation", "Enfants", "Familles", "Accompagnement"]
+            .iter().map(|s| s.to_string()).collect();
+        entete.extend(Genre::iter().map(|g| g.to_string()));
+        entete.push("Genre inconnu".into());
+        entete.extend(ages.iter().map(|a| format!("{} ans", a)));
+        entete.extend(Interet::iter().map(|i| i.to_string()));
+        entete.push("Intérêt inconnu".into());
+        wtr.write_record(&entete)?;
+
+        for regroupement in Regroupement::TOUS {
+            for (cle, ligne) in self.regrouper(regroupement) {
+                let mut rec: Vec<String> = vec![
+                    regroupement.titre().into(),
+                    cle.saison.clone().unwrap_or_default(),
+                    cle.site.clone().unwrap_or_default(),
+                    cle.semaine.clone().unwrap_or_default(),
+                    cle.category.clone().unwrap_or_default(),
+                    ligne.nb_groupes.to_string(),
+                    ligne.groupes_vides.to_string(),
+                    ligne.inscriptions.to_string(),
+                    ligne.capacite_str(),
+                    ligne.taux_occupation().map(|t| format!("{:.0}%", t*100.0)).unwrap_or_default(),
+                    ligne.enfants.len().to_string(),
+                    ligne.familles.len().to_string(),
+                    ligne.accompagnement.to_string(),
+                ];
+                rec.extend(Genre::iter().map(|g| ligne.genres.get(&Some(g)).copied().unwrap_or(0).to_string()));
+                rec.push(ligne.genres.get(&None).copied().unwrap_or(0).to_string());
+                rec.extend(ages.iter().map(|a| ligne.ages.get(a).copied().unwrap_or(0).to_string()));
+                rec.extend(Interet::iter().map(|i| ligne.interets.get(&Some(i)).copied().unwrap_or(0).to_string()));
+                rec.push(ligne.interets.get(&None).copied().unwrap_or(0).to_string());
+                wtr.write_record(&rec)?;
+            }
+        }
+        wtr.flush()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{groupes::{groupes::GroupeID, membres::Membre}, prelude::Date};
+    use super::*;
+
+    fn groupe(id: u32, category: &str, debut: Date, participants: &[u32]) -> Groupe {
+        Groupe {
+            id: GroupeID(id),
+            saison: Some("Été 2025".into()),
+            site: Some("Parc Lafontaine".into()),
+            semaine: Some("2".into()),
+            category: Some(category.into()),
+            debut: Some(debut),
+            participants: participants.iter().map(|i| MembreID(*i)).collect(),
+            ..Groupe::default()
+        }
+    }
+
+    #[test]
+    fn enfant_dans_deux_groupes_de_la_meme_semaine_compte_une_fois() {
+        let mut membres = MembreReg::default();
+        let mut lea = Membre::new(MembreID(1), "Tremblay".into(), "Léa".into(), Date::from_ymd_opt(2016, 7, 3).unwrap());
+        lea.genre = Some(Genre::Femme);
+        lea.accompagnement = Some(true);
+        lea.compte = Some(CompteID(1));
+        let mut noe = Membre::new(MembreID(2), "Tremblay".into(), "Noé".into(), Date::from_ymd_opt(2018, 1, 10).unwrap());
+        noe.genre = Some(Genre::Homme);
+        noe.compte = Some(CompteID(1));
+        membres.add(lea).unwrap();
+        membres.add(noe).unwrap();
+
+        // Léa a 9 ans au début du groupe de l'après-midi, mais encore 8 au début de celui du matin
+        let mut groupes = GroupeReg::default();
+        groupes.add(groupe(1, "Balaous", Date::from_ymd_opt(2025, 7, 7).unwrap(), &[1, 2])).unwrap();
+        groupes.add(groupe(2, "Crocus", Date::from_ymd_opt(2025, 6, 30).unwrap(), &[1])).unwrap();
+
+        let stats = StatsCamp::calculer(&groupes, &membres);
+        let lignes = stats.regrouper(Regroupement::SiteSemaine);
+        assert_eq!(lignes.len(), 1);
+        let ligne = lignes.values().next().unwrap();
+        assert_eq!(ligne.nb_groupes, 2);
+        assert_eq!(ligne.inscriptions, 3);
+        assert_eq!(ligne.enfants.len(), 2);
+        assert_eq!(ligne.familles.len(), 1);
+        assert_eq!(ligne.accompagnement, 1);
+        assert_eq!(ligne.genres.get(&Some(Genre::Femme)), Some(&1));
+        assert_eq!(ligne.genres.get(&Some(Genre::Homme)), Some(&1));
+        assert_eq!(ligne.ages.get(&8), Some(&1));
+        assert_eq!(ligne.ages.get(&9), None);
+        assert_eq!(ligne.ages.get(&7), Some(&1));
+    }
+}
@@ -0,0 +1,127 @@
+use console::style;
+
+use crate::{groupes::{comptes::{Compte, NULL_COMPTE}, membres::{Membre, MembreReg}}, prelude::*, ProgramData};
+
+use super::{chiffres, lire_commande, membres::{afficher_membre, groupes_par_membre}, CommandeListe, Page};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TriComptes {
+    Mandataire,
+    NbEnfants,
+}
+impl TriComptes {
+    fn suivant(self) -> Self {
+        match self {
+            Self::Mandataire => Self::NbEnfants,
+            Self::NbEnfants => Self::Mandataire,
+        }
+    }
+    fn as_str(&self) -> &'static str {
+        match self {
+            Self::Mandataire => "mandataire",
+            Self::NbEnfants => "nombre d'enfants",
+        }
+    }
+}
+
+fn enfants<'a>(compte: &Compte, membres: &'a MembreReg) -> Vec<&'a Membre> {
+    let mut enfants: Vec<&Membre> = compte.membres.iter().filter_map(|mid| membres.get(*mid).ok()).collect();
+    enfants.sort_by(|a, b| a.cmp_nom(b));
+    enfants
+}
+
+/// Recherche par mandataire, téléphone, courriel ou nom d'un des enfants.
+fn compte_correspond(compte: &Compte, membres: &MembreReg, recherche: &str) -> bool {
+    let texte = normaliser(recherche);
+    if normaliser(&compte.mandataire).contains(&texte) {
+        return true;
+    }
+    if compte.email.as_ref().is_some_and(|e| e.as_str().to_lowercase().contains(&texte)) {
+        return true;
+    }
+    let tel = chiffres(recherche);
+    if tel.len() >= 4 && compte.tel.is_some_and(|t| t.as_str().contains(&tel)) {
+        return true;
+    }
+    enfants(compte, membres).iter().any(|m| {
+        normaliser(&format!("{} {}", m.prenom, m.nom)).contains(&texte) || normaliser(&format!("{} {}", m.nom, m.prenom)).contains(&texte)
+    })
+}
+
+pub(crate) fn parcourir_comptes(program: &ProgramData) {
+    let tous: Vec<&Compte> = program.comptes.comptes().filter(|c| c.id != NULL_COMPTE.id).collect();
+    let mut tri = TriComptes::Mandataire;
+    let mut recherche: O<String> = None;
+    let mut page = Page::default();
+    let mut liste: Vec<&Compte> = Vec::new();
+    let mut a_jour = false;
+
+    loop {
+        if !a_jour {
+            liste = tous.iter()
+                .filter(|c| recherche.as_ref().is_none_or(|r| compte_correspond(c, &program.membres, r)))
+                .copied()
+                .collect();
+            match tri {
+                TriComptes::Mandataire => liste.sort_by_key(|c| normaliser(&c.mandataire)),
+                TriComptes::NbEnfants => liste.sort_by_key(|c| (std::cmp::Reverse(c.membres.len()), normaliser(&c.mandataire))),
+            }
+            page.reinitialiser(liste.len());
+            a_jour = true;
+        }
+
+        let _ = program.out.clear_screen();
+        let _ = program.out.write_line(&format!("{}", style("Comptes").bold().cyan()));
+        for (i, compte) in liste.iter().enumerate().take(page.fin()).skip(page.debut()) {
+            let noms = enfants(compte, &program.membres).iter().map(|m| m.prenom.clone()).collect::<Vec<String>>().join(", ");
+            let _ = program.out.write_line(&format!("{:>4}. {} | {} | {} | enfants: {}",
+                i + 1,
+                compte.mandataire,
+                print_option(&compte.tel),
+                print_option(&compte.email),
+                noms,
+            ));
+        }
+
+        match lire_commande(&program.out, &page, tri.as_str(), recherche.as_deref()) {
+            CommandeListe::Suivant => page.suivante(),
+            CommandeListe::Precedent => page.precedente(),
+            CommandeListe::Trier => {
+                tri = tri.suivant();
+                a_jour = false;
+            },
+            CommandeListe::Rechercher(r) => {
+                recherche = Some(r);
+                a_jour = false;
+            },
+            CommandeListe::Effacer => {
+                recherche = None;
+                a_jour = false;
+            },
+            CommandeListe::Detail(n) => {
+                let _ = program.out.clear_screen();
+                afficher_compte(program, liste[n]);
+                crate::wait_to_continue();
+            },
+            CommandeListe::Retour => return,
+        }
+    }
+}
+
+/// Le compte, suivi de la fiche complète de chacun de ses enfants.
+fn afficher_compte(program: &ProgramData, compte: &Compte) {
+    let out = &program.out;
+    let _ = out.write_line(&format!("{}", style(format!("{} ({})", compte.mandataire, compte.id)).bold().cyan()));
+    let _ = out.write_line(&format!("{}: {}", style("Téléphone").bold(), print_option(&compte.tel)));
+    let _ = out.write_line(&format!("{}: {}", style("Courriel").bold(), print_option(&compte.email)));
+    let _ = out.write_line(&format!("{}: {}", style("Adresse").bold(), print_option(&compte.adresse)));
+    for m in compte.historique.iter() {
+        let _ = out.write_line(&format!("\t{} {}: {} -> {} ({})", m.date, style(&m.champ).bold(), m.avant, style(&m.apres).yellow(), m.source));
+    }
+
+    let groupes_de = groupes_par_membre(&program.groupes);
+    for membre in enfants(compte, &program.membres) {
+        let _ = out.write_line("");
+        afficher_membre(program, membre, &groupes_de);
+    }
+}
@@ -0,0 +1,267 @@
+use std::collections::HashMap;
+
+use console::style;
+
+use crate::{data::BoolJustifie, groupes::{comptes::CompteReg, groupes::{Groupe, GroupeReg, NULL_GROUPE}, membres::{Membre, MembreID, NULL_MEMBRE}}, prelude::*, ProgramData};
+
+use super::{chiffres, lire_commande, CommandeListe, Page};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TriMembres {
+    Nom,
+    Age,
+    Groupe,
+}
+impl TriMembres {
+    fn suivant(self) -> Self {
+        match self {
+            Self::Nom => Self::Age,
+            Self::Age => Self::Groupe,
+            Self::Groupe => Self::Nom,
+        }
+    }
+    fn as_str(&self) -> &'static str {
+        match self {
+            Self::Nom => "nom",
+            Self::Age => "âge",
+            Self::Groupe => "groupe",
+        }
+    }
+}
+
+/// Les groupes auxquels participe chaque membre.
+pub fn groupes_par_membre(groupes: &GroupeReg) -> HashMap<MembreID, Vec<&Groupe>> {
+    let mut res: HashMap<MembreID, Vec<&Groupe>> = HashMap::new();
+    for grp in groupes.groupes() {
+        if *grp == *NULL_GROUPE { continue; }
+        for mid in grp.participants.iter() {
+            res.entry(*mid).or_default().push(grp);
+        }
+    }
+    for grps in res.values_mut() {
+        grps.sort_by_key(|g| g.short_desc());
+    }
+    res
+}
+
+/// Recherche par nom, par numéro de téléphone (du compte ou des contacts) ou par NAM.
+pub fn membre_correspond(membre: &Membre, comptes: &CompteReg, recherche: &str) -> bool {
+    let texte = normaliser(recherche);
+    let nom = normaliser(&format!("{} {}", membre.nom, membre.prenom));
+    let nom_inverse = normaliser(&format!("{} {}", membre.prenom, membre.nom));
+    if nom.contains(&texte) || nom_inverse.contains(&texte) {
+        return true;
+    }
+
+    let tel = chiffres(recherche);
+    if tel.len() >= 4 {
+        let compte_tel = membre.compte.and_then(|c| comptes.get(c).ok()).and_then(|c| c.tel);
+        let contacts_tel = membre.contacts.iter().flatten().filter_map(|c| c.tel);
+        if compte_tel.into_iter().chain(contacts_tel).any(|t| t.as_str().contains(&tel)) {
+            return true;
+        }
+    }
+
+    let nam: String = recherche.chars().filter(char::is_ascii_alphanumeric).collect::<String>().to_uppercase();
+    nam.len() >= 4 && membre.fiche_sante.cam.as_ref().is_some_and(|cam| cam.numero().as_str().contains(&nam))
+}
+
+fn age(membre: &Membre) -> O<u32> {
+    today().years_since(membre.naissance)
+}
+
+fn resume_groupe(grp: &Groupe, mid: MembreID) -> String {
+    let mut s = [grp.category.clone(), grp.semaine.as_ref().map(|s| format!("sem. {}", s)), grp.site.clone()]
+        .into_iter().flatten().collect::<Vec<String>>().join(" ");
+    if let Some(sg) = grp.get_sous_groupe_for(mid) {
+        s += &format!(" (sg {}{})", sg.disc, sg.profil.map(|p| format!(" {}", p)).unwrap_or_default());
+    }
+    s
+}
+
+pub(crate) fn parcourir_membres(program: &ProgramData) {
+    let groupes_de = groupes_par_membre(&program.groupes);
+    let tous: Vec<&Membre> = program.membres.membres().filter(|m| **m != *NULL_MEMBRE).collect();
+    let mut tri = TriMembres::Nom;
+    let mut recherche: O<String> = None;
+    let mut page = Page::default();
+    let mut liste: Vec<&Membre> = Vec::new();
+    let mut a_jour = false;
+
+    loop {
+        if !a_jour {
+            liste = tous.iter()
+                .filter(|m| recherche.as_ref().is_none_or(|r| membre_correspond(m, &program.comptes, r)))
+                .copied()
+                .collect();
+            match tri {
+                TriMembres::Nom => liste.sort_by(|a, b| a.cmp_nom(b)),
+                TriMembres::Age => liste.sort_by(|a, b| b.naissance.cmp(&a.naissance).then_with(|| a.cmp_nom(b))),
+                TriMembres::Groupe => liste.sort_by_key(|m| (
+                    groupes_de.get(&m.id).and_then(|g| g.first()).map(|g| g.short_desc()),
+                    normaliser(&m.nom),
+                    normaliser(&m.prenom),
+                )),
+            }
+            page.reinitialiser(liste.len());
+            a_jour = true;
+        }
+
+        let _ = program.out.clear_screen();
+        let _ = program.out.write_line(&format!("{}", style("Membres").bold().cyan()));
+        for (i, membre) in liste.iter().enumerate().take(page.fin()).skip(page.debut()) {
+            let groupes = groupes_de.get(&membre.id)
+                .map(|grps| grps.iter().map(|g| resume_groupe(g, membre.id)).collect::<Vec<String>>().join(", "))
+                .unwrap_or("aucun groupe".into());
+            let allergies = if membre.fiche_sante.allergies.is_empty() {
+                String::new()
+            } else {
+                format!(" | {}", style(format!("Allergies: {}", membre.fiche_sante.allergies.join(", "))).red())
+            };
+            let _ = program.out.write_line(&format!("{:>4}. {}, {} ({} ans) | {}{}",
+                i + 1,
+                membre.nom,
+                membre.prenom,
+                print_option(&age(membre)),
+                groupes,
+                allergies,
+            ));
+        }
+
+        match lire_commande(&program.out, &page, tri.as_str(), recherche.as_deref()) {
+            CommandeListe::Suivant => page.suivante(),
+            CommandeListe::Precedent => page.precedente(),
+            CommandeListe::Trier => {
+                tri = tri.suivant();
+                a_jour = false;
+            },
+            CommandeListe::Rechercher(r) => {
+                recherche = Some(r);
+                a_jour = false;
+            },
+            CommandeListe::Effacer => {
+                recherche = None;
+                a_jour = false;
+            },
+            CommandeListe::Detail(n) => {
+                let _ = program.out.clear_screen();
+                afficher_membre(program, liste[n], &groupes_de);
+                crate::wait_to_continue();
+            },
+            CommandeListe::Retour => return,
+        }
+    }
+}
+
+fn print_bool(val: O<bool>) -> &'static str {
+    match val {
+        None => "-",
+        Some(true) => "Oui",
+        Some(false) => "Non",
+    }
+}
+
+fn print_bool_justifie(val: &O<BoolJustifie>) -> String {
+    match val {
+        None => "-".into(),
+        Some(bj) => match &bj.justification {
+            Some(j) => format!("{}, {}", print_bool(Some(bj.reponse)), j),
+            None => print_bool(Some(bj.reponse)).into(),
+        },
+    }
+}
+
+/// Tout ce que l'on sait d'un enfant, incluant chacun de ses groupes.
+pub(crate) fn afficher_membre(program: &ProgramData, membre: &Membre, groupes_de: &HashMap<MembreID, Vec<&Groupe>>) {
+    let out = &program.out;
+    let ligne = |titre: &str, valeur: String| {
+        let _ = out.write_line(&format!("{}: {}", style(titre).bold(), valeur));
+    };
+    let _ = out.write_line(&format!("{}", style(format!("{}, {} ({})", membre.nom, membre.prenom, membre.id)).bold().cyan()));
+    ligne("Naissance", format!("{} ({} ans)", membre.naissance, print_option(&age(membre))));
+    ligne("Genre", print_option(&membre.genre));
+    ligne("Taille", print_option(&membre.taille));
+    ligne("Intérêts", membre.interets.iter().flatten().map(|i| i.to_string()).collect::<Vec<String>>().join(", "));
+    ligne("Accompagnement", print_bool(membre.accompagnement).into());
+    ligne("Autorisation photo", print_bool(membre.auth_photo).into());
+    if let Some(c) = &membre.commentaire {
+        ligne("Commentaire", c.clone());
+    }
+
+    let _ = out.write_line(&format!("{}", style("Compte").bold().cyan()));
+    match membre.compte.and_then(|c| program.comptes.get(c).ok()) {
+        None => ligne("Mandataire", "-".into()),
+        Some(compte) => {
+            ligne("Mandataire", compte.mandataire.clone());
+            ligne("Téléphone", print_option(&compte.tel));
+            ligne("Courriel", print_option(&compte.email));
+            ligne("Adresse", print_option(&compte.adresse));
+            let fratrie = compte.membres.iter()
+                .filter(|mid| **mid != membre.id)
+                .filter_map(|mid| program.membres.get(*mid).ok())
+                .map(|m| format!("{} {}", m.prenom, m.nom))
+                .collect::<Vec<String>>();
+            if !fratrie.is_empty() {
+                ligne("Fratrie", fratrie.join(", "));
+            }
+        },
+    }
+    for (i, contact) in membre.contacts.iter().enumerate() {
+        if let Some(c) = contact {
+            ligne(&format!("Contact d'urgence {}", i + 1), format!("{} ({}), {}", c.nom, print_option(&c.lien), print_option(&c.tel)));
+        }
+    }
+    ligne("Quitte avec", membre.quitte.avec.join(", "));
+    ligne("Mot de passe", print_option(&membre.quitte.mdp));
+
+    let fiche = &membre.fiche_sante;
+    let _ = out.write_line(&format!("{}", style("Fiche santé").bold().cyan()));
+    ligne("Carte d'assurance maladie", print_option(&fiche.cam));
+    ligne("Allergies", fiche.allergies.join(", "));
+    ligne("Maladies", fiche.maladies.join(", "));
+    ligne("Prise de médicament", print_bool_justifie(&fiche.prise_med));
+    ligne("Problèmes de comportement", print_bool_justifie(&fiche.probleme_comportement));
+    ligne("Autorisation de soigner", print_bool(fiche.auth_soins).into());
+    let med = &fiche.auth_medicaments;
+    ligne("Médicaments autorisés", [
+        ("Acétaminophène", med.acetaminophene),
+        ("Ibuprofène", med.ibuprofene),
+        ("Anti-inflammatoire", med.anti_inflamatoire),
+        ("Antibiotique", med.anti_biotique),
+        ("Antiémétique", med.anti_emetique),
+        ("Sirop pour la toux", med.sirop_toux),
+    ].iter().filter(|(_, v)| *v == Some(true)).map(|(n, _)| *n).collect::<Vec<&str>>().join(", "));
+    ligne("Piscine", format!("partage aux sauveteurs: {}, VFI: {}, tête sous l'eau: {}",
+        print_bool(membre.piscine.partage),
+        print_bool(membre.piscine.vfi),
+        print_bool(membre.piscine.tete_sous_eau),
+    ));
+
+    if !membre.historique.is_empty() {
+        let _ = out.write_line(&format!("{}", style("Modifications").bold().cyan()));
+        for m in membre.historique.iter() {
+            let _ = out.write_line(&format!("\t{} {}: {} -> {} ({})", m.date, style(&m.champ).bold(), m.avant, style(&m.apres).yellow(), m.source));
+        }
+    }
+
+    let _ = out.write_line(&format!("{}", style("Groupes").bold().cyan()));
+    match groupes_de.get(&membre.id) {
+        None => {
+            let _ = out.write_line("Aucun groupe");
+        },
+        Some(grps) => {
+            for grp in grps {
+                let sg = match grp.get_sous_groupe_for(membre.id) {
+                    None => String::new(),
+                    Some(sg) => format!(" | sous-groupe {}{}{}{}",
+                        sg.disc,
+                        sg.nom.as_ref().map(|n| format!(" {}", n)).unwrap_or_default(),
+                        sg.profil.map(|p| format!(", profil {}", p)).unwrap_or_default(),
+                        sg.animateur.as_ref().map(|a| format!(" ({})", a)).unwrap_or_default(),
+                    ),
+                };
+                let _ = out.write_line(&format!("\t{}{}", grp.short_desc(), sg));
+            }
+        },
+    }
+}
@@ -0,0 +1,93 @@
+pub mod comptes;
+pub mod membres;
+pub mod sous_groupes;
+
+use console::{style, Term};
+
+/// Nombre de lignes affichées par page dans les listes.
+pub static TAILLE_PAGE: usize = 20;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CommandeListe {
+    Suivant,
+    Precedent,
+    Trier,
+    Rechercher(String),
+    Effacer,
+    Detail(usize),
+    Retour,
+}
+
+/// Position dans une liste paginée.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Page {
+    pub num: usize,
+    pub total: usize,
+}
+impl Page {
+    pub fn nb_pages(&self) -> usize {
+        self.total.div_ceil(TAILLE_PAGE).max(1)
+    }
+    pub fn debut(&self) -> usize {
+        self.num * TAILLE_PAGE
+    }
+    pub fn fin(&self) -> usize {
+        (self.debut() + TAILLE_PAGE).min(self.total)
+    }
+    pub fn suivante(&mut self) {
+        if self.num + 1 < self.nb_pages() {
+            self.num += 1;
+        }
+    }
+    pub fn precedente(&mut self) {
+        self.num = self.num.saturating_sub(1);
+    }
+    /// Revient à la première page, par exemple après une nouvelle recherche.
+    pub fn reinitialiser(&mut self, total: usize) {
+        self.num = 0;
+        self.total = total;
+    }
+}
+
+/// Affiche le bas de la liste et lit la commande de l'utilisateur.
+pub fn lire_commande(term: &Term, page: &Page, tri: &str, recherche: Option<&str>) -> CommandeListe {
+    let _ = term.write_line(&format!("{}", style(format!("Page {}/{} ({} résultats), trié par {}{}",
+        page.num + 1,
+        page.nb_pages(),
+        page.total,
+        tri,
+        recherche.map(|r| format!(", recherche \"{}\"", r)).unwrap_or_default(),
+    )).dim()));
+    let _ = term.write_line("[numéro] détail, [s]uivant, [p]récédent, [t]rier, [r <texte>] rechercher, [e]ffacer la recherche, [q]uitter");
+    loop {
+        let input: String = dialoguer::Input::new()
+            .with_prompt("Entrez votre choix")
+            .allow_empty(true)
+            .interact_text()
+            .expect("N'a pu lire l'entrée");
+        let input = input.trim();
+        let (cmd, arg) = match input.split_once(char::is_whitespace) {
+            Some((c, a)) => (c, a.trim()),
+            None => (input, ""),
+        };
+        match cmd.to_lowercase().as_str() {
+            "" | "s" => return CommandeListe::Suivant,
+            "p" => return CommandeListe::Precedent,
+            "t" => return CommandeListe::Trier,
+            "r" if !arg.is_empty() => return CommandeListe::Rechercher(arg.into()),
+            "e" => return CommandeListe::Effacer,
+            "q" => return CommandeListe::Retour,
+            n => match n.parse::<usize>() {
+                Ok(n) if n > 0 && n <= page.total => return CommandeListe::Detail(n - 1),
+                _ => {
+                    let _ = term.write_line("Entrée invalide.");
+                },
+            },
+        }
+    }
+}
+
+/// Ne garde que les chiffres, pour comparer des numéros de téléphone écrits de différentes façons.
+pub fn chiffres(s: &str) -> String {
+    s.chars().filter(char::is_ascii_digit).collect()
+}
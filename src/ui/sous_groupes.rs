@@ -0,0 +1,282 @@
+use std::str::FromStr;
+
+use console::{pad_str, style, Alignment};
+use strum::IntoEnumIterator;
+
+use crate::{groupes::{groupes::{ErreurEchange, Groupe, GroupeID, SousGroupe, NULL_GROUPE}, membres::{Interet, Membre, MembreID, MembreReg}, strategies::{nb_amis_separes, NomStrategie}}, prelude::*, ProgramData};
+
+/// Largeur d'une colonne de la comparaison des stratégies.
+static LARGEUR_COLONNE: usize = 40;
+
+/// Modification à la main des sous-groupes d'un groupe.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum CommandeSousGroupes {
+    /// Numéro de l'enfant, numéro du sous-groupe
+    Deplacer(usize, u32),
+    Echanger(usize, usize),
+    Renommer(u32, O<String>),
+    Profil(u32, O<Interet>),
+    VerrouillerMembre(usize),
+    VerrouillerSousGroupe(u32),
+    Retour,
+}
+
+fn lire_commande_sous_groupes(program: &ProgramData, nb_enfants: usize) -> CommandeSousGroupes {
+    let _ = program.out.write_line("[d <enfant> <sg>] déplacer, [e <enfant> <enfant>] échanger, [n <sg> <nom>] renommer, [p <sg> <intérêt|aucun>] profil,");
+    let _ = program.out.write_line("[v <enfant>] verrouiller un enfant, [vs <sg>] verrouiller un sous-groupe, [q]uitter");
+    loop {
+        let input: String = dialoguer::Input::new()
+            .with_prompt("Entrez votre choix")
+            .allow_empty(true)
+            .interact_text()
+            .expect("N'a pu lire l'entrée");
+        let mots: Vec<&str> = input.split_whitespace().collect();
+        let enfant = |i: usize| mots.get(i).and_then(|s| s.parse::<usize>().ok()).filter(|n| *n > 0 && *n <= nb_enfants).map(|n| n - 1);
+        let sg = |i: usize| mots.get(i).and_then(|s| s.parse::<u32>().ok());
+        let cmd = match mots.first().map(|m| m.to_lowercase()).as_deref() {
+            Some("q") => Some(CommandeSousGroupes::Retour),
+            Some("d") => enfant(1).zip(sg(2)).map(|(e, s)| CommandeSousGroupes::Deplacer(e, s)),
+            Some("e") => enfant(1).zip(enfant(2)).map(|(a, b)| CommandeSousGroupes::Echanger(a, b)),
+            Some("n") => sg(1).map(|s| CommandeSousGroupes::Renommer(s, Some(mots[2..].join(" ")).filter(|n| !n.is_empty()))),
+            Some("p") => match (sg(1), mots.get(2).map(|p| p.to_lowercase())) {
+                (Some(s), Some(p)) if p == "aucun" => Some(CommandeSousGroupes::Profil(s, None)),
+                (Some(s), Some(p)) => Interet::from_str(&p).ok().map(|i| CommandeSousGroupes::Profil(s, Some(i))),
+                _ => None,
+            },
+            Some("v") => enfant(1).map(CommandeSousGroupes::VerrouillerMembre),
+            Some("vs") => sg(1).map(CommandeSousGroupes::VerrouillerSousGroupe),
+            _ => None,
+        };
+        match cmd {
+            Some(cmd) => return cmd,
+            None => {
+                let _ = program.out.write_line("Entrée invalide.");
+            },
+        }
+    }
+}
+
+/// Les participants de chaque sous-groupe, dans l'ordre d'affichage: c'est leur numéro dans les commandes.
+fn numeroter(grp: &Groupe, membres: &MembreReg) -> Vec<MembreID> {
+    let mut res = Vec::new();
+    for sg in grp.sous_groupe.iter() {
+        let mut participants: Vec<_> = sg.participants.iter().filter_map(|mid| membres.get(*mid).ok()).collect();
+        participants.sort_by(|a, b| a.cmp_nom(b));
+        res.extend(participants.into_iter().map(|m| m.id));
+    }
+    res
+}
+
+fn afficher_sous_groupes(program: &ProgramData, grp: &Groupe, numeros: &[MembreID]) {
+    let _ = program.out.write_line(&format!("{}", style(grp.desc()).bold().cyan()));
+    for sg in grp.sous_groupe.iter() {
+        let _ = program.out.write_line(&format!("{}{} ({} enfants)",
+            style(sg.titre()).bold(),
+            if sg.verrouille {format!(" {}", style("[verrouillé]").yellow())} else {String::new()},
+            sg.participants.len(),
+        ));
+        for (i, mid) in numeros.iter().enumerate().filter(|(_, mid)| sg.participants.contains(mid)) {
+            let Ok(membre) = program.membres.get(*mid) else {continue};
+            let _ = program.out.write_line(&format!("{:>4}. {}, {} ({} ans){}",
+                i + 1,
+                membre.nom,
+                membre.prenom,
+                print_option(&today().years_since(membre.naissance)),
+                if sg.membres_verrouilles.contains(mid) {format!(" {}", style("[verrouillé]").yellow())} else {String::new()},
+            ));
+        }
+    }
+}
+
+/// Affiche les groupes qui ont des participants (et des sous-groupes, si demandé) et lit le choix de l'utilisateur.
+fn choisir_groupe(program: &ProgramData, avec_sous_groupes: bool) -> O<GroupeID> {
+    let mut groupes: Vec<(GroupeID, String)> = program.groupes.groupes()
+        .filter(|g| **g != *NULL_GROUPE && !g.participants.is_empty() && (!avec_sous_groupes || !g.sous_groupe.is_empty()))
+        .map(|g| (g.id, g.desc()))
+        .collect();
+    groupes.sort_by(|a, b| a.1.cmp(&b.1));
+    let _ = program.out.clear_screen();
+    if groupes.is_empty() {
+        let _ = program.out.write_line("Aucun groupe n'a de sous-groupes: faites les sous-groupes d'abord.");
+        return None;
+    }
+    let _ = program.out.write_line(&format!("{}", style("Groupes").bold().cyan()));
+    for (i, (_, desc)) in groupes.iter().enumerate() {
+        let _ = program.out.write_line(&format!("{:>4}. {}", i + 1, desc));
+    }
+    loop {
+        let input: String = dialoguer::Input::new()
+            .with_prompt("Numéro du groupe ([q]uitter)")
+            .allow_empty(true)
+            .interact_text()
+            .expect("N'a pu lire l'entrée");
+        match input.trim().parse::<usize>() {
+            Ok(n) if n > 0 && n <= groupes.len() => return Some(groupes[n - 1].0),
+            _ if input.trim().eq_ignore_ascii_case("q") => return None,
+            _ => {
+                let _ = program.out.write_line("Entrée invalide.");
+            },
+        }
+    }
+}
+
+/// Choisit un groupe ayant des sous-groupes, puis permet de les modifier.
+pub(crate) fn modifier_sous_groupes(program: &mut ProgramData) {
+    while let Some(gid) = choisir_groupe(program, true) {
+        modifier_groupe(program, gid);
+    }
+}
+
+/// Les sous-groupes que donnerait chaque stratégie pour ce groupe.
+pub(crate) fn proposer(program: &ProgramData, grp: &Groupe, nb_sg: usize) -> Vec<(NomStrategie, Vec<SousGroupe>)> {
+    NomStrategie::iter()
+        .filter_map(|strategie| grp.proposer_sous_groupes(nb_sg, &program.membres, &program.config.sous_groupes, strategie).ok()
+            .map(|(sous_groupes, _)| (strategie, sous_groupes)))
+        .collect()
+}
+
+/// Résumé des sous-groupes d'une stratégie, une colonne de la comparaison.
+fn colonne(grp: &Groupe, strategie: NomStrategie, sous_groupes: &[SousGroupe], membres: &MembreReg) -> Vec<String> {
+    let reference = grp.debut.unwrap_or_else(today);
+    let age = |m: &Membre| age_au(m.naissance, reference);
+    let mut lignes = vec![format!("{}", style(strategie.to_string()).bold())];
+    let mut interesses = 0;
+    let mut ecarts = Vec::new();
+    for sg in sous_groupes {
+        let mbrs: Vec<&Membre> = sg.participants.iter().filter_map(|mid| membres.get(*mid).ok()).collect();
+        let ages: Vec<f32> = mbrs.iter().map(|m| age(m)).collect();
+        let (age_min, age_max) = (ages.iter().copied().fold(f32::MAX, f32::min), ages.iter().copied().fold(0.0, f32::max));
+        if !ages.is_empty() {
+            ecarts.push(age_max - age_min);
+        }
+        lignes.push(format!("{} {}: {} enf., {}-{} ans",
+            sg.disc,
+            sg.profil.map_or("sans profil".into(), |p| p.to_string()),
+            mbrs.len(),
+            if ages.is_empty() {0} else {age_min.floor() as u32},
+            age_max.floor() as u32,
+        ));
+        let hommes = mbrs.iter().filter(|m| m.genre == Some(crate::data::Genre::Homme)).count();
+        let femmes = mbrs.iter().filter(|m| m.genre == Some(crate::data::Genre::Femme)).count();
+        let mut details = format!("  H{} F{}", hommes, femmes);
+        if let Some(profil) = sg.profil {
+            let n = mbrs.iter().filter(|m| m.interets[..2].contains(&Some(profil))).count();
+            interesses += n;
+            details += &format!(", {} avec le profil", n);
+        }
+        lignes.push(details);
+    }
+    let tous: Vec<&Membre> = grp.participants.iter().filter_map(|mid| membres.get(*mid).ok()).collect();
+    let sous_groupe_de = |mid: MembreID| sous_groupes.iter().find(|sg| sg.participants.contains(&mid)).map(|sg| sg.disc);
+    lignes.push(format!("Profil en 1er/2e choix: {}/{}", interesses, tous.len()));
+    lignes.push(format!("Écart d'âge moyen: {:.1} an(s)", ecarts.iter().sum::<f32>() / ecarts.len().max(1) as f32));
+    lignes.push(format!("Amis séparés: {}", nb_amis_separes(&tous, sous_groupe_de)));
+    lignes
+}
+
+/// Affiche côte à côte les sous-groupes de chaque stratégie.
+pub(crate) fn afficher_comparaison(program: &ProgramData, grp: &Groupe, propositions: &[(NomStrategie, Vec<SousGroupe>)]) {
+    let _ = program.out.write_line(&format!("{}", style(grp.desc()).bold().cyan()));
+    let colonnes: Vec<Vec<String>> = propositions.iter().map(|(strategie, sgs)| colonne(grp, *strategie, sgs, &program.membres)).collect();
+    let nb_lignes = colonnes.iter().map(Vec::len).max().unwrap_or(0);
+    for i in 0..nb_lignes {
+        let ligne: String = colonnes.iter()
+            .map(|c| pad_str(c.get(i).map_or("", String::as_str), LARGEUR_COLONNE, Alignment::Left, Some("…")).into_owned())
+            .collect::<Vec<String>>()
+            .join(" | ");
+        let _ = program.out.write_line(ligne.trim_end());
+    }
+}
+
+/// Compare les stratégies pour un groupe, puis applique celle choisie.
+pub(crate) fn comparer_strategies(program: &mut ProgramData) {
+    while let Some(gid) = choisir_groupe(program, false) {
+        let Ok(grp) = program.groupes.get(gid) else {return};
+        let Some(nb_sg) = crate::guess_nb_sous_groupes(grp, &program.config.categories, program.interactif, &program.err) else {continue};
+        let propositions = proposer(program, grp, nb_sg);
+        let _ = program.out.clear_screen();
+        afficher_comparaison(program, grp, &propositions);
+        let _ = program.out.write_line(&format!("Stratégie de la configuration: {}", program.config.strategie(grp.category.as_deref())));
+
+        let options: Vec<(String, O<NomStrategie>)> = propositions.iter()
+            .map(|(strategie, _)| (format!("Appliquer: {}", strategie), Some(*strategie)))
+            .chain(std::iter::once(("Ne rien changer".to_string(), None)))
+            .collect();
+        let options: Vec<(&str, O<NomStrategie>)> = options.iter().map(|(s, o)| (s.as_str(), *o)).collect();
+        let choix = crate::choose_option(&program.out, &options);
+        if let (Some(strategie), Ok(grp)) = (choix, program.groupes.get_mut(gid)) {
+            if let Some((_, sous_groupes)) = propositions.into_iter().find(|(s, _)| *s == strategie) {
+                grp.sous_groupe = sous_groupes;
+                let _ = program.out.write_line(&format!("{}", style(format!("Sous-groupes de la stratégie {} appliqués", strategie)).green()));
+                crate::wait_to_continue();
+            }
+        }
+    }
+}
+
+fn modifier_groupe(program: &mut ProgramData, gid: GroupeID) {
+    let mut message: O<String> = None;
+    loop {
+        let Ok(grp) = program.groupes.get(gid) else {return};
+        let numeros = numeroter(grp, &program.membres);
+        let _ = program.out.clear_screen();
+        afficher_sous_groupes(program, grp, &numeros);
+        if let Some(m) = message.take() {
+            let _ = program.out.write_line(&m);
+        }
+
+        let cmd = lire_commande_sous_groupes(program, numeros.len());
+        let Ok(grp) = program.groupes.get_mut(gid) else {return};
+        message = Some(match cmd {
+            CommandeSousGroupes::Retour => return,
+            CommandeSousGroupes::Deplacer(e, disc) => {
+                if grp.deplacer_membre(numeros[e], disc) {
+                    format!("{}", style(format!("Enfant {} déplacé dans le sous-groupe {}", e + 1, disc)).green())
+                } else {
+                    format!("{}", style(format!("Aucun sous-groupe {}", disc)).red())
+                }
+            },
+            CommandeSousGroupes::Echanger(a, b) => {
+                let numero = |mid: MembreID| if mid == numeros[a] {a + 1} else {b + 1};
+                match grp.echanger_membres(numeros[a], numeros[b]) {
+                    Ok(()) => format!("{}", style(format!("Enfants {} et {} échangés", a + 1, b + 1)).green()),
+                    Err(ErreurEchange::MemeSousGroupe) => format!("{}", style(format!("Les enfants {} et {} sont dans le même sous-groupe", a + 1, b + 1)).red()),
+                    Err(ErreurEchange::NonParticipant(mid)) => format!("{}", style(format!("L'enfant {} n'est plus inscrit au groupe", numero(mid))).red()),
+                    Err(ErreurEchange::SansSousGroupe(mid)) => format!("{}", style(format!("L'enfant {} n'a pas de sous-groupe", numero(mid))).red()),
+                }
+            },
+            CommandeSousGroupes::Renommer(disc, nom) => match grp.get_sous_groupe_mut(disc) {
+                Some(sg) => {
+                    sg.nom = nom;
+                    format!("{}", style(format!("Sous-groupe {} renommé", disc)).green())
+                },
+                None => format!("{}", style(format!("Aucun sous-groupe {}", disc)).red()),
+            },
+            CommandeSousGroupes::Profil(disc, profil) => match grp.get_sous_groupe_mut(disc) {
+                Some(sg) => {
+                    sg.profil = profil;
+                    let mut m = format!("{}", style(format!("Profil du sous-groupe {}: {}", disc, profil.map_or("aucun".into(), |p| p.to_string()))).green());
+                    // sans verrou, la stratégie redonne un profil quand les sous-groupes sont refaits
+                    if !(sg.verrouille || profil.is_some() && sg.a_verrous()) {
+                        m += &format!("\n{}", style(format!("Ce profil sera recalculé si les sous-groupes sont refaits: verrouillez le sous-groupe (vs {}) pour le garder", disc)).yellow());
+                    }
+                    m
+                },
+                None => format!("{}", style(format!("Aucun sous-groupe {}", disc)).red()),
+            },
+            CommandeSousGroupes::VerrouillerMembre(e) => match grp.basculer_verrou_membre(numeros[e]) {
+                Some(true) => format!("{}", style(format!("Enfant {} verrouillé dans son sous-groupe", e + 1)).green()),
+                Some(false) => format!("{}", style(format!("Enfant {} déverrouillé", e + 1)).green()),
+                None => format!("{}", style(format!("L'enfant {} n'a pas de sous-groupe", e + 1)).red()),
+            },
+            CommandeSousGroupes::VerrouillerSousGroupe(disc) => match grp.get_sous_groupe_mut(disc) {
+                Some(sg) => {
+                    sg.verrouille = !sg.verrouille;
+                    let etat = if sg.verrouille {"verrouillé"} else {"déverrouillé"};
+                    format!("{}", style(format!("Sous-groupe {} {}", disc, etat)).green())
+                },
+                None => format!("{}", style(format!("Aucun sous-groupe {}", disc)).red()),
+            },
+        });
+    }
+}
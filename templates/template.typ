@@ -103,16 +103,18 @@
 	commentaire: commentaire,
 )
 
-#let new_groupe(saison: none, site: none, categorie: none, discriminant: none, animateur: none, semaine: none, activite: none, profil: none, groupe_num: none) = (
+#let new_groupe(saison: none, site: none, categorie: none, discriminant: none, animateur: none, semaine: none, dates: none, activite: none, profil: none, groupe_num: none, nom_sous_groupe: none) = (
 	saison: saison,
 	site: site,
 	categorie: categorie,
 	discriminant: discriminant,
 	animateur: animateur,
 	semaine: semaine,
+	dates: dates,
 	activite: activite,
 	profil: profil,
 	groupe_num: groupe_num,
+	nom_sous_groupe: nom_sous_groupe,
 )
 
 #let fiche_med(doc, 
@@ -252,12 +254,13 @@
 			if exists(groupe.activite) [#groupe.activite],
 			if exists(groupe.site) [#groupe.site],
 			if exists(groupe.categorie) [#groupe.categorie],
-			if exists(groupe.semaine) [sem. #groupe.semaine],
+			if exists(groupe.dates) [#groupe.dates] else if exists(groupe.semaine) [sem. #groupe.semaine],
 		).filter(it => exists(it))
 		= #ln.join(" | ") 
 		#let ln = (
 			if exists(groupe.discriminant) [#groupe.discriminant],
 			if exists(groupe.groupe_num) [#groupe.groupe_num],
+			if exists(groupe.nom_sous_groupe) [#groupe.nom_sous_groupe],
 			if exists(groupe.profil) [profil #groupe.profil],
 			if exists(groupe.animateur) [(#groupe.animateur)],
 		).filter(it => exists(it))
@@ -316,6 +319,7 @@
 	site: none,
 	saison: none,
 	semaine: none,
+	dates: none,
 	groupes: (:),
 	participants: (),
 ) = [
@@ -330,7 +334,7 @@
 	#grid(columns: (1fr, auto))[
 		#let ln = (
 			if exists(site) [#site],
-			if exists(semaine) [sem. #semaine],
+			if exists(dates) [#dates] else if exists(semaine) [sem. #semaine],
 		).filter(it => exists(it))
 		= #ln.join(" | ") 
 		== Liste de Présence SDJ
@@ -350,6 +354,139 @@
 	)
 ]
 
+#let new_stats(
+	regroupement: none,
+	nom: none,
+	saison: none,
+	site: none,
+	semaine: none,
+	categorie: none,
+	groupes: 0,
+	inscriptions: 0,
+	capacite: none,
+	taux: none,
+	enfants: 0,
+	familles: 0,
+	accompagnement: 0,
+	genres: (),
+	ages: (),
+	interets: (),
+) = (
+	regroupement: regroupement,
+	nom: nom,
+	saison: saison,
+	site: site,
+	semaine: semaine,
+	categorie: categorie,
+	groupes: groupes,
+	inscriptions: inscriptions,
+	capacite: capacite,
+	taux: taux,
+	enfants: enfants,
+	familles: familles,
+	accompagnement: accompagnement,
+	genres: genres,
+	ages: ages,
+	interets: interets,
+)
+
+#let print_taux(taux) = {
+	if not exists(taux) [-]
+	else [#int(calc.round(taux * 100))%]
+}
+#let couleur_taux(taux) = {
+	if taux > 1 { rgb("C62828") }
+	else if taux >= 0.9 { rgb("F9A825") }
+	else { rgb("2E7D32") }
+}
+// barre horizontale remplie selon le taux d'occupation (plafonnée à 100%)
+#let barre_taux(taux) = {
+	if not exists(taux) { return [_capacité inconnue_] }
+	box(width: 100%, height: 0.8em, stroke: 0.4pt,
+		align(left, rect(width: calc.min(taux, 1) * 100%, height: 100%, fill: couleur_taux(taux), stroke: none)))
+}
+// diagramme à barres d'une liste de (étiquette, nombre)
+#let barres(valeurs) = {
+	if valeurs.len() == 0 { return [] }
+	let max = calc.max(..valeurs.map(v => v.at(1)))
+	grid(columns: (auto, 1fr, auto), column-gutter: 0.5em, row-gutter: 0.4em,
+		..valeurs.map(((etiquette, n)) => (
+			etiquette,
+			box(width: 100%, height: 0.8em, align(left, rect(width: if max > 0 { n / max * 100% } else { 0% }, height: 100%, fill: rgb("1565C0"), stroke: none))),
+			[#n],
+		)).flatten()
+	)
+}
+#let table_stats(lignes) = table(
+	columns: (1fr, auto, auto, auto, auto, auto, auto),
+	align: (left, right, right, right, right, right, right),
+	table.header([], [*Groupes*], [*Inscriptions*], [*Capacité*], [*Taux*], [*Familles*], [*Accomp.*]),
+	..lignes.map(l => (
+		l.nom,
+		[#l.groupes],
+		[#l.inscriptions],
+		if exists(l.capacite) [#l.capacite] else [?],
+		print_taux(l.taux),
+		[#l.familles],
+		[#l.accompagnement],
+	)).flatten()
+)
+#let stats_camp(doc, date: none, lignes: (), total: new_stats()) = [
+	#set page(paper: "us-letter", margin: 1.5cm, numbering: "1 / 1")
+	#set text(size: 10pt)
+
+	#show heading.where(depth: 1): set text(size: 24pt)
+	#show heading.where(depth: 2): set text(size: 16pt)
+	#show heading.where(depth: 3): set text(size: 12pt)
+
+	#grid(columns: (1fr, auto))[
+		= Statistiques de camp
+		#if exists(date) [En date du #date]
+	][
+		#align(center+horizon, image("doc_skia.png", width: 2in))
+	]
+
+	== Sommaire
+	#table(columns: (1fr, auto), align: (left, right),
+		[Inscriptions], [#total.inscriptions],
+		[Capacité], if exists(total.capacite) [#total.capacite] else [?],
+		[Taux d'occupation], print_taux(total.taux),
+		[Enfants], [#total.enfants],
+		[Familles], [#total.familles],
+		[Enfants ayant besoin d'accompagnement], [#total.accompagnement],
+	)
+
+	== Occupation par site et semaine
+	#let par_site_semaine = lignes.filter(l => l.regroupement == "Par site et semaine")
+	#for site in par_site_semaine.map(l => l.site).dedup() [
+		=== #if exists(site) [#site] else [Site inconnu]
+		#grid(columns: (auto, 1fr, auto), column-gutter: 0.5em, row-gutter: 0.4em,
+			..par_site_semaine.filter(l => l.site == site).map(l => (
+				if exists(l.semaine) [Sem. #l.semaine] else [-],
+				barre_taux(l.taux),
+				[#l.inscriptions/#if exists(l.capacite) [#l.capacite] else [?] (#print_taux(l.taux))],
+			)).flatten()
+		)
+	]
+
+	#for regroupement in ("Par site", "Par semaine", "Par catégorie", "Par groupe") [
+		== #regroupement
+		#table_stats(lignes.filter(l => l.regroupement == regroupement))
+	]
+
+	== Répartition
+	#grid(columns: (1fr, 1fr, 1fr), column-gutter: 1.5em)[
+		=== Genres
+		#barres(total.genres)
+	][
+		=== Âges
+		#barres(total.ages)
+	][
+		=== Premier intérêt
+		#barres(total.interets)
+	]
+]
+
 /*
 (
 	if exists(enfant.mdp) {table.cell(rowspan: 3, breakable: false, align(center+horizon)[#enfant.mdp])} else [],
@@ -371,4 +508,4 @@
 		#bloc.filter(it => exists(it)).join("; ")
 	],
 )
-*/
\ No newline at end of file
+*/
\ No newline at end of file